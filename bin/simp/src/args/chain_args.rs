@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use clap::Args;
+use simp_primitives::ChainSpec;
+
+use super::utils::chain_spec_value_parser;
+use crate::config::Config;
+
+/// Parameters selecting the chain to sync and the rpc endpoint it is downloaded from
+#[derive(Debug, Clone, Args)]
+#[command(next_help_heading = "Chain")]
+pub struct ChainArgs {
+    /// The chain this node is indexing.
+    ///
+    /// Possible values are either a built-in chain or a chain id.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    /// - dev
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_ID",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = chain_spec_value_parser
+    )]
+    pub chain: Arc<ChainSpec>,
+
+    /// The rpc endpoint of the chain the blocks are downloaded from, overrides the one of the
+    /// config file.
+    #[arg(long = "rpc-url", value_name = "URL")]
+    pub rpc_url: Option<String>,
+
+    /// An rpc endpoint the requests fail over to when the main one keeps failing, can be repeated.
+    ///
+    /// Overrides the fallback endpoints of the config file.
+    #[arg(long = "rpc-fallback-url", value_name = "URL")]
+    pub rpc_fallback_urls: Vec<String>,
+
+    /// The websocket endpoint the new blocks are subscribed to, overrides the one of the config
+    /// file. The tip is polled over http without one.
+    #[arg(long = "rpc-ws-url", value_name = "URL")]
+    pub rpc_ws_url: Option<String>,
+}
+
+impl ChainArgs {
+    /// Returns the chain spec connected to the rpc endpoint of the command line, or of the config
+    /// file if none was given.
+    pub fn chain_spec(&self, config: &Config) -> eyre::Result<ChainSpec> {
+        let mut chain_spec = self.chain.as_ref().clone();
+        let chain_config = config.chain(&chain_spec);
+
+        chain_spec.rpc_connection = self
+            .rpc_url
+            .clone()
+            .or_else(|| chain_config.and_then(|chain| chain.rpc_url.clone()))
+            .ok_or_else(|| eyre::eyre!("No rpc url configured for chain {}", chain_spec.chain()))?;
+
+        if let Some(confirmation_blocks) = chain_config.and_then(|chain| chain.confirmation_blocks) {
+            chain_spec.confirmation_depth = confirmation_blocks;
+        }
+
+        if let Some(rpc_batch_size) = chain_config.and_then(|chain| chain.rpc_batch_size) {
+            chain_spec.rpc_batch_size = rpc_batch_size;
+        }
+
+        chain_spec.ws_connection =
+            self.rpc_ws_url.clone().or_else(|| chain_config.and_then(|chain| chain.ws_url.clone()));
+
+        chain_spec.rpc_fallbacks = if self.rpc_fallback_urls.is_empty() {
+            chain_config.map(|chain| chain.fallback_rpc_urls.clone()).unwrap_or_default()
+        } else {
+            self.rpc_fallback_urls.clone()
+        };
+
+        if let Some(chain) = chain_config {
+            chain_spec.rpc_transport.requests_per_second = chain.requests_per_second;
+
+            if let Some(max_retries) = chain.max_retries {
+                chain_spec.rpc_transport.max_retries = max_retries;
+            }
+        }
+
+        Ok(chain_spec)
+    }
+}
@@ -3,4 +3,9 @@ mod rpc_server_args;
 
 pub use rpc_server_args::RpcServerArgs;
 
-pub mod utils;
\ No newline at end of file
+/// ChainArgs struct for selecting the chain and its rpc endpoint
+mod chain_args;
+
+pub use chain_args::ChainArgs;
+
+pub mod utils;
@@ -3,7 +3,14 @@ use futures::TryFutureExt;
 use tracing::{info, debug};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use simp_rpc_builder::{constants, RpcServerConfig, ServerBuilder, RpcModuleBuilder, TransportRpcModuleConfig, RpcModuleConfig, RpcModuleSelection};
-use crate::cli::{config::SimpRpcConfig, components::{SimpRpcServerHandles, SimpRpcComponents}};
+use stages::{PipelineController, PipelineEventSubscriptions};
+use storage_provider::traits::{
+    BlockReader, MarketProvider, ProtocolProvider, StageCheckpointProvider, VolumetricReader,
+};
+use crate::{
+    cli::{config::SimpRpcConfig, components::{SimpRpcServerHandles, SimpRpcComponents}},
+    config::RpcConfig,
+};
 
 /// Default max number of subscriptions per connection.
 pub(crate) const RPC_DEFAULT_MAX_SUBS_PER_CONN: u32 = 1024;
@@ -32,11 +39,13 @@ pub struct RpcServerArgs {
     #[arg(long = "http.port", default_value_t = constants::DEFAULT_HTTP_RPC_PORT)]
     pub http_port: u16,
 
-    /* 
-    /// Rpc Modules to be configured for the HTTP server
-    #[arg(long = "http.api", value_parser = RpcModuleSelectionValueParser::default())]
-    pub http_api: Option<RpcModuleSelection>, 
-    
+    /// Rpc Modules to be configured for the HTTP server, e.g. `data,volumes,registry-admin`
+    ///
+    /// Admin modules like `registry-admin` and `admin` are only served when listed here.
+    #[arg(long = "http.api")]
+    pub http_api: Option<RpcModuleSelection>,
+
+    /*
     /// Http Corsdomain to allow request from
     #[arg(long = "http.corsdomain")]
     pub http_corsdomain: Option<String>,
@@ -53,14 +62,15 @@ pub struct RpcServerArgs {
     /// Ws server port to listen on
     #[arg(long = "ws.port", default_value_t = constants::DEFAULT_WS_RPC_PORT)]
     pub ws_port: u16,
+
+    /// Rpc Modules to be configured for the WS server
+    #[arg(long = "ws.api")]
+    pub ws_api: Option<RpcModuleSelection>,
+
 /* 
     /// Origins from which to accept WebSocket requests
     #[arg(long = "ws.origins", name = "ws.origins")]
-    pub ws_allowed_origins: Option<String>,
-
-    /// Rpc Modules to be configured for the WS server
-    #[arg(long = "ws.api", value_parser = RpcModuleSelectionValueParser::default())]
-    pub ws_api: Option<RpcModuleSelection>, */
+    pub ws_allowed_origins: Option<String>, */
 
     /// Set the maximum RPC request payload size for both HTTP and WS in megabytes.
     #[arg(long, default_value_t = RPC_DEFAULT_MAX_REQUEST_SIZE_MB)]
@@ -80,12 +90,61 @@ pub struct RpcServerArgs {
 }
 
 impl RpcServerArgs {
-    pub async fn start_servers(&self) -> eyre::Result<SimpRpcServerHandles> {
+    /// Applies the settings of the config file.
+    ///
+    /// Servers enabled on the command line stay enabled, other values are only taken from the
+    /// file while the flag is left at its default.
+    pub fn with_config(mut self, config: &RpcConfig) -> Self {
+        let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        self.http |= config.http;
+        self.ws |= config.ws;
+
+        if let Some(addr) = config.http_addr.filter(|_| self.http_addr == localhost) {
+            self.http_addr = addr;
+        }
+        if let Some(port) = config.http_port.filter(|_| self.http_port == constants::DEFAULT_HTTP_RPC_PORT) {
+            self.http_port = port;
+        }
+        if let Some(addr) = config.ws_addr.filter(|_| self.ws_addr == localhost) {
+            self.ws_addr = addr;
+        }
+        if let Some(port) = config.ws_port.filter(|_| self.ws_port == constants::DEFAULT_WS_RPC_PORT) {
+            self.ws_port = port;
+        }
+        if let Some(max_connections) =
+            config.max_connections.filter(|_| self.rpc_max_connections == RPC_DEFAULT_MAX_CONNECTIONS)
+        {
+            self.rpc_max_connections = max_connections;
+        }
+
+        self
+    }
+
+    /// Starts the configured servers, serving the data of `provider` and steering the `pipeline`
+    /// running next to them.
+    pub async fn start_servers<Provider, Pipeline>(
+        &self,
+        provider: Provider,
+        pipeline: Pipeline,
+    ) -> eyre::Result<SimpRpcServerHandles>
+    where
+        Provider: BlockReader
+            + VolumetricReader
+            + MarketProvider
+            + ProtocolProvider
+            + StageCheckpointProvider
+            + Clone
+            + 'static,
+        Pipeline: PipelineEventSubscriptions + PipelineController + Clone + 'static,
+    {
         debug!("Start server in RpcServerArgs");
 
         let module_config = self.transport_rpc_module_config();
 
         let (mut modules, mut registry) = RpcModuleBuilder::default()
+            .with_provider(provider)
+            .with_pipeline(pipeline)
             .build_with_auth_server(module_config);
 
         let server_config = self.rpc_server_config();
@@ -123,19 +182,17 @@ impl RpcServerArgs {
 
         if self.http {
             config = config.with_http(
-                RpcModuleSelection::standard_modules(),
-                /* self.http_api
+                self.http_api
                     .clone()
-                    .unwrap_or_else(|| RpcModuleSelection::standard_modules().into()), */
+                    .unwrap_or_else(|| RpcModuleSelection::standard_modules().into()),
             );
         }
 
         if self.ws {
             config = config.with_ws(
-                RpcModuleSelection::standard_modules(),
-                /* self.ws_api
+                self.ws_api
                     .clone()
-                    .unwrap_or_else(|| RpcModuleSelection::standard_modules().into()), */
+                    .unwrap_or_else(|| RpcModuleSelection::standard_modules().into()),
             );
         }
 
@@ -191,6 +248,7 @@ impl SimpRpcConfig for RpcServerArgs {
 mod tests {
     use super::*;
     use clap::Parser;
+    use simp_rpc_builder::SimpRpcModule;
     use std::net::SocketAddrV4;
 
     /// A helper type to parse Args more easily
@@ -227,4 +285,43 @@ mod tests {
             SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8888))
         );
     }
+
+    #[test]
+    fn test_rpc_server_args_with_config() {
+        let config = RpcConfig {
+            http: true,
+            http_port: Some(9545),
+            ws_port: Some(9546),
+            max_connections: Some(10),
+            ..Default::default()
+        };
+
+        let args = CommandParser::<RpcServerArgs>::parse_from(["simp", "--ws", "--ws.port", "8888"])
+            .args
+            .with_config(&config);
+
+        assert!(args.http && args.ws);
+        assert_eq!(args.http_port, 9545);
+        // flags take precedence over the file
+        assert_eq!(args.ws_port, 8888);
+        assert_eq!(args.rpc_max_connections, 10);
+    }
+
+    #[test]
+    fn test_rpc_module_selection() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "simp",
+            "--http",
+            "--http.api",
+            "data,registry-admin",
+        ])
+        .args;
+
+        let config = args.transport_rpc_module_config();
+        assert_eq!(
+            config.http().unwrap().clone().into_selection(),
+            vec![SimpRpcModule::Data, SimpRpcModule::RegistryAdmin]
+        );
+        assert!(config.ws().is_none());
+    }
 }
\ No newline at end of file
@@ -1,8 +1,27 @@
 //! Clap parser utilities
+use simp_primitives::{Chain, ChainSpec, ChainSpecBuilder, DEV, GOERLI, MAINNET, SEPOLIA};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs},
+    sync::Arc,
 };
 
+/// Clap value parser for [ChainSpec]s.
+///
+/// The value is either the name of a known chain or a chain id, unknown chains get the default
+/// spec.
+pub fn chain_spec_value_parser(s: &str) -> eyre::Result<Arc<ChainSpec>, eyre::Error> {
+    Ok(match s {
+        "mainnet" => MAINNET.clone(),
+        "goerli" => GOERLI.clone(),
+        "sepolia" => SEPOLIA.clone(),
+        "dev" => DEV.clone(),
+        _ => {
+            let chain = s.parse::<Chain>().map_err(|err| eyre::eyre!(err))?;
+            Arc::new(ChainSpecBuilder::default().chain(chain).build())
+        }
+    })
+}
+
 
 /// Error thrown while parsing a socket address.
 #[derive(thiserror::Error, Debug)]
@@ -55,6 +74,16 @@ mod tests {
     use proptest::prelude::Rng;
     use secp256k1::rand::thread_rng;
 
+    #[test]
+    fn parse_chain_spec() {
+        for chain in ["mainnet", "goerli", "sepolia", "dev"] {
+            chain_spec_value_parser(chain).unwrap();
+        }
+
+        assert_eq!(chain_spec_value_parser("123456").unwrap().chain(), Chain::Id(123456));
+        assert!(chain_spec_value_parser("not-a-chain").is_err());
+    }
+
     #[test]
     fn parse_socket_addresses() {
         for value in ["localhost:9000", ":9000", "9000"] {
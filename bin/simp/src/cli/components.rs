@@ -6,11 +6,11 @@ pub trait SimpNodeComponents {}
 /// Helper container to encapsulate [SimpModuleRegistry] and [TransportRpcModules].
 #[derive(Debug)]
 #[allow(clippy::type_complexity)]
-pub struct SimpRpcComponents<'a> {
+pub struct SimpRpcComponents<'a, Provider, Pipeline> {
     /// A Helper type the holds instances of the configured modules.
     ///
     /// This provides easy access to rpc handlers
-    pub registry: &'a mut SimpModuleRegistry<>,
+    pub registry: &'a mut SimpModuleRegistry<Provider, Pipeline>,
     /// Holds installed modules per transport type.
     ///
     /// This can be used to merge additional modules into the configured transports (http, ws). See [TransportRpcModules::merge_configured]
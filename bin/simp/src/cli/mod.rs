@@ -1,11 +1,11 @@
 use clap::{value_parser, ArgAction, Args, Parser, Subcommand, ValueEnum};
-use crate::{runner::CliRunner, server::ServerCommand, dirs::{PlatformPath, LogsDir}};
+use crate::{runner::CliRunner, server::ServerCommand, node::NodeCommand, db, stage, config::Config, dirs::{PlatformPath, LogsDir}};
 use simp_tracing::{
     tracing::{metadata::LevelFilter, Level, Subscriber},
     tracing_subscriber::{filter::Directive, registry::LookupSpan, EnvFilter},
     BoxedLayer, FileWorkerGuard,
 };
-use std::{fmt, fmt::Display};
+use std::{fmt, fmt::Display, path::PathBuf};
 
 pub mod config;
 pub mod components;
@@ -36,6 +36,12 @@ pub struct Cli {
     #[arg(long, value_name = "INSTANCE", global = true, default_value_t = 1, value_parser = value_parser!(u16).range(..=200))]
     instance: u16,
 
+    /// The path to the configuration file, defaults to `simp.toml` in the config directory.
+    ///
+    /// Command line flags take precedence over the values of the file.
+    #[arg(long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+
     #[clap(flatten)]
     logs: Logs,
 
@@ -50,11 +56,27 @@ impl Cli {
 
         let _guard = self.init_tracing()?;
 
+        // `config` loads the file itself, `config --default` has to work with a broken one
+        let load_config = || Config::load(self.config.as_deref());
+
         let runner = CliRunner;
 
         match self.command {
-            Commands::Server(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
-       }
+            Commands::Server(command) => {
+                let config = load_config()?;
+                runner.run_command_until_exit(|ctx| command.execute(ctx, config))
+            }
+            Commands::Node(command) => {
+                let config = load_config()?;
+                runner.run_command_until_exit(|ctx| command.execute(ctx, config))
+            }
+            Commands::Db(command) => command.execute(&load_config()?),
+            Commands::Stage(command) => {
+                let config = load_config()?;
+                runner.run_command_until_exit(|ctx| command.execute(ctx, config))
+            }
+            Commands::Config(command) => command.execute(self.config.as_deref()),
+        }
     }
 
     /// Initializes tracing with the configured options.
@@ -85,10 +107,26 @@ pub fn run() -> eyre::Result<()> {
 /// Commands to be executed
 #[derive(Debug, Subcommand)]
 pub enum Commands {
-    /// Start the node
+    /// Start the RPC server
     #[command(name = "server")]
     Server(ServerCommand),
 
+    /// Start the sync pipeline
+    #[command(name = "node")]
+    Node(NodeCommand),
+
+    /// Database debugging utilities
+    #[command(name = "db")]
+    Db(db::Command),
+
+    /// Manipulate individual stages
+    #[command(name = "stage")]
+    Stage(stage::Command),
+
+    /// Print the configuration, `--default` prints a template for `simp.toml`
+    #[command(name = "config")]
+    Config(crate::config::Command),
+
 }
 
 /// The log configuration.
@@ -252,6 +290,35 @@ mod tests {
         assert!(matches!(args.command, Commands::Server(_)));
     }
 
+    #[test]
+    fn parse_node_command() {
+        let args = Cli::try_parse_from([
+            "simp", "node", "--chain", "goerli", "--rpc-url", "http://localhost:8545", "--max-block", "100", "--ws",
+        ])
+        .unwrap();
+
+        match args.command {
+            Commands::Node(command) => {
+                assert_eq!(command.chain.chain.chain(), simp_primitives::Chain::goerli());
+                assert_eq!(command.chain.rpc_url.as_deref(), Some("http://localhost:8545"));
+                assert_eq!(command.max_block, Some(100));
+                assert!(command.rpc.ws && !command.rpc.http);
+            }
+            _ => panic!("expected the node command"),
+        }
+
+        // the rpc url can be read from the config file instead
+        assert!(Cli::try_parse_from(["simp", "node"]).is_ok());
+    }
+
+    #[test]
+    fn parse_config_command() {
+        let args = Cli::try_parse_from(["simp", "config", "--default", "--config", "simp.toml"]).unwrap();
+
+        assert!(matches!(args.command, Commands::Config(_)));
+        assert_eq!(args.config, Some(PathBuf::from("simp.toml")));
+    }
+
     #[test]
     fn parse_color_mode() {
         let simp = Cli::try_parse_from(["simp", "server", "--color", "always"]).unwrap();
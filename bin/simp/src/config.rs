@@ -0,0 +1,340 @@
+//! Configuration of the simp binary, read from a `simp.toml` file.
+//!
+//! Every value can also be set on the command line, flags take precedence over the file.
+use std::{
+    collections::BTreeMap,
+    net::IpAddr,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use db::options::{DatabaseCompressionType, DatabaseOptions};
+use eyre::Context;
+use serde::{Deserialize, Serialize};
+use simp_primitives::{Address, ChainSpec, TraceMethod, H256};
+use stages::stages::DEFAULT_HEADERS_BATCH_SIZE;
+
+use crate::dirs;
+
+/// Name of the config file looked up in the config directory.
+pub const CONFIG_FILE_NAME: &str = "simp.toml";
+
+/// Configuration of the simp binary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Connection settings of each chain, keyed by chain name or id.
+    pub chains: BTreeMap<String, ChainConfig>,
+    /// Stages of the sync pipeline.
+    pub stages: StagesConfig,
+    /// Database options.
+    pub db: DbConfig,
+    /// RPC server settings.
+    pub rpc: RpcConfig,
+    /// Contracts and protocols indexed by the node.
+    pub tracking: TrackingConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            chains: BTreeMap::from([("mainnet".to_string(), ChainConfig::default())]),
+            stages: StagesConfig::default(),
+            db: DbConfig::default(),
+            rpc: RpcConfig::default(),
+            tracking: TrackingConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file at `path`, or the one of the config directory if no path is given.
+    ///
+    /// An explicit path has to exist, a missing file in the config directory falls back to the
+    /// default config.
+    pub fn load(path: Option<&Path>) -> eyre::Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match default_config_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+
+        let content = std::fs::read_to_string(&path)
+            .wrap_err_with(|| format!("Could not read config file {}", path.display()))?;
+
+        toml::from_str(&content)
+            .wrap_err_with(|| format!("Could not parse config file {}", path.display()))
+    }
+
+    /// Returns the settings of the chain, if the file has any.
+    pub fn chain(&self, chain: &ChainSpec) -> Option<&ChainConfig> {
+        self.chains
+            .get(&chain.chain().to_string())
+            .or_else(|| self.chains.get(&chain.chain().id().to_string()))
+    }
+}
+
+/// Returns the path of the config file in the config directory.
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|root| root.join(CONFIG_FILE_NAME))
+}
+
+/// Connection settings of a chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChainConfig {
+    /// The rpc endpoint the blocks are downloaded from.
+    pub rpc_url: Option<String>,
+    /// The websocket endpoint the new blocks are subscribed to.
+    pub ws_url: Option<String>,
+    /// Number of blocks after which a block is considered final, overrides the chain spec.
+    pub confirmation_blocks: Option<u64>,
+    /// Number of requests sent in a single JSON-RPC batch, overrides the chain spec.
+    pub rpc_batch_size: Option<u64>,
+    /// Endpoints the requests fail over to when `rpc_url` keeps failing.
+    pub fallback_rpc_urls: Vec<String>,
+    /// Maximum number of requests per second sent to each endpoint.
+    pub requests_per_second: Option<u32>,
+    /// Number of times the endpoints are tried again before a request fails.
+    pub max_retries: Option<u32>,
+}
+
+impl Default for ChainConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: Some("http://localhost:8545".to_string()),
+            ws_url: None,
+            confirmation_blocks: None,
+            rpc_batch_size: None,
+            fallback_rpc_urls: Vec::new(),
+            requests_per_second: None,
+            max_retries: None,
+        }
+    }
+}
+
+/// Stages of the sync pipeline.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StagesConfig {
+    /// Headers stage, always enabled.
+    pub headers: HeadersConfig,
+    /// Block indexing stage.
+    pub block_indexing: StageConfig,
+    /// Traces stage, disabled by default as few rpc providers serve traces.
+    pub traces: TracesConfig,
+    /// Snapshots indexing stage.
+    pub snapshots_indexing: StageConfig,
+}
+
+/// Headers stage settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeadersConfig {
+    /// Number of headers downloaded before they are written to the database.
+    pub batch_size: u64,
+}
+
+impl Default for HeadersConfig {
+    fn default() -> Self {
+        Self { batch_size: DEFAULT_HEADERS_BATCH_SIZE }
+    }
+}
+
+/// Settings of a stage that can be left out of the pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StageConfig {
+    /// Whether the stage is part of the pipeline.
+    pub enabled: bool,
+}
+
+impl Default for StageConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Traces stage settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TracesConfig {
+    /// Whether the stage is part of the pipeline.
+    pub enabled: bool,
+    /// The rpc method the traces are downloaded with.
+    pub method: TraceMethod,
+}
+
+/// Database options, see [DatabaseOptions].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DbConfig {
+    /// Compression of every table.
+    pub compression: DatabaseCompressionType,
+    /// Size in bytes of the block cache shared by every table.
+    pub block_cache_size: Option<usize>,
+    /// Bits per key of the bloom filters of the tables read by key prefix.
+    pub bloom_filter_bits: Option<f64>,
+    /// Maximum number of files kept open, `-1` keeps every file open.
+    pub max_open_files: i32,
+}
+
+impl Default for DbConfig {
+    fn default() -> Self {
+        let options = DatabaseOptions::default();
+
+        Self {
+            compression: options.compression,
+            block_cache_size: options.block_cache_size,
+            bloom_filter_bits: options.bloom_filter_bits,
+            max_open_files: options.max_open_files,
+        }
+    }
+}
+
+impl DbConfig {
+    /// Returns the options the database is opened with.
+    pub fn database_options(&self) -> DatabaseOptions {
+        DatabaseOptions {
+            compression: self.compression.clone(),
+            block_cache_size: self.block_cache_size,
+            bloom_filter_bits: self.bloom_filter_bits,
+            max_open_files: self.max_open_files,
+            ..Default::default()
+        }
+    }
+}
+
+/// RPC server settings, unset values keep the defaults of the command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RpcConfig {
+    /// Enable the HTTP-RPC server.
+    pub http: bool,
+    /// Http server address to listen on.
+    pub http_addr: Option<IpAddr>,
+    /// Http server port to listen on.
+    pub http_port: Option<u16>,
+    /// Enable the WS-RPC server.
+    pub ws: bool,
+    /// Ws server address to listen on.
+    pub ws_addr: Option<IpAddr>,
+    /// Ws server port to listen on.
+    pub ws_port: Option<u16>,
+    /// Maximum number of RPC server connections.
+    pub max_connections: Option<u32>,
+}
+
+/// Contracts and protocols indexed by the node.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackingConfig {
+    /// Addresses of the contracts whose logs are decoded.
+    pub contracts: Vec<Address>,
+    /// Factory addresses of the protocols whose markets are indexed.
+    pub protocols: Vec<H256>,
+}
+
+/// `simp config` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Print the default config instead of the loaded one.
+    #[arg(long)]
+    default: bool,
+}
+
+impl Command {
+    /// Prints the config as TOML, it can be used as a template for `simp.toml`.
+    ///
+    /// The file at `config_path` is only read when the loaded config is printed, so the default
+    /// one can be printed to replace a broken file.
+    pub fn execute(self, config_path: Option<&Path>) -> eyre::Result<()> {
+        println!("{}", self.render(config_path)?);
+        Ok(())
+    }
+
+    fn render(&self, config_path: Option<&Path>) -> eyre::Result<String> {
+        let config = if self.default { Config::default() } else { Config::load(config_path)? };
+
+        Ok(toml::to_string_pretty(&config)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_roundtrip() {
+        let config = Config::default();
+        let content = toml::to_string_pretty(&config).unwrap();
+
+        assert_eq!(toml::from_str::<Config>(&content).unwrap(), config);
+    }
+
+    #[test]
+    fn print_default_config_over_broken_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, "[stages").unwrap();
+
+        let rendered = Command { default: true }.render(Some(&path)).unwrap();
+        assert_eq!(toml::from_str::<Config>(&rendered).unwrap(), Config::default());
+
+        assert!(Command { default: false }.render(Some(&path)).is_err());
+    }
+
+    #[test]
+    fn load_partial_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(
+            &path,
+            r#"
+            [chains.goerli]
+            rpc_url = "http://goerli:8545"
+            confirmation_blocks = 20
+            rpc_batch_size = 50
+            fallback_rpc_urls = ["http://fallback:8545"]
+            requests_per_second = 10
+
+            [stages.headers]
+            batch_size = 500
+
+            [stages.snapshots_indexing]
+            enabled = false
+
+            [stages.traces]
+            enabled = true
+            method = "debug_trace_block"
+
+            [db]
+            compression = "zstd"
+            "#,
+        )
+        .unwrap();
+
+        let config = Config::load(Some(&path)).unwrap();
+
+        let goerli = config.chain(&simp_primitives::GOERLI).unwrap();
+        assert_eq!(goerli.rpc_url.as_deref(), Some("http://goerli:8545"));
+        assert_eq!(goerli.confirmation_blocks, Some(20));
+        assert_eq!(goerli.rpc_batch_size, Some(50));
+        assert_eq!(goerli.fallback_rpc_urls, vec!["http://fallback:8545".to_string()]);
+        assert_eq!(goerli.requests_per_second, Some(10));
+        assert!(config.chain(&simp_primitives::MAINNET).is_none());
+
+        assert_eq!(config.stages.headers.batch_size, 500);
+        assert!(config.stages.block_indexing.enabled);
+        assert!(!config.stages.snapshots_indexing.enabled);
+        assert!(config.stages.traces.enabled);
+        assert_eq!(config.stages.traces.method, TraceMethod::DebugTraceBlock);
+
+        assert_eq!(config.db.compression, DatabaseCompressionType::Zstd);
+        assert_eq!(config.db.max_open_files, -1);
+
+        assert!(Config::load(Some(&dir.path().join("missing.toml"))).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+//! Database debugging tool
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+};
+
+use clap::{Parser, Subcommand};
+use db::{
+    init_db_with_options, open_db_secondary,
+    table::Table,
+    tables::{TableViewer, Tables},
+    transaction::{DbTx, TableStats},
+};
+use eyre::Context;
+use serde_json::Value as JsonValue;
+use tracing::info;
+
+use crate::{config::Config, dirs};
+
+/// `simp db` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Open the database as a read-only secondary instance storing its own files in this
+    /// directory, so it can be inspected while a node is running.
+    #[arg(long, value_name = "PATH")]
+    secondary: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Subcommand, Debug)]
+/// `simp db` subcommands
+pub enum Subcommands {
+    /// Lists the estimated number of entries and the size on disk of every table
+    Stats,
+    /// Gets the value of a key in a table, printed as JSON
+    Get {
+        /// The table name
+        table: Tables,
+        /// The key as JSON, e.g. `1` or `"0x...."`
+        key: String,
+    },
+    /// Lists the entries of a table, printed as JSON
+    List(ListArgs),
+    /// Deletes every entry of a table
+    Drop {
+        /// The table name
+        table: Tables,
+
+        /// Drop the table without asking for confirmation
+        #[arg(long, default_value_t = false)]
+        force: bool,
+    },
+}
+
+/// Arguments of `simp db list`
+#[derive(Parser, Debug)]
+pub struct ListArgs {
+    /// The table name
+    table: Tables,
+
+    /// Skip first N entries
+    #[arg(long, short, default_value_t = 0)]
+    skip: usize,
+
+    /// Walk the table from its last entry
+    #[arg(long, short, default_value_t = false)]
+    reverse: bool,
+
+    /// How many entries to list
+    #[arg(long, short, default_value_t = 5)]
+    len: usize,
+}
+
+impl Command {
+    /// Execute `db` command
+    pub fn execute(self, config: &Config) -> eyre::Result<()> {
+        if self.secondary.is_some() && matches!(self.command, Subcommands::Drop { .. }) {
+            eyre::bail!("Tables can't be dropped through a secondary instance")
+        }
+
+        let db_path = dirs::database_path()
+            .ok_or_else(|| eyre::eyre!("Could not resolve the database directory"))?;
+        let options = config.db.database_options();
+
+        match &self.secondary {
+            Some(secondary_path) => {
+                let db = open_db_secondary(&db_path, secondary_path, &options)
+                    .wrap_err("Could not open the database as a secondary instance")?;
+                self.command.run(&db)
+            }
+            None => {
+                let db = init_db_with_options(&db_path, &options)
+                    .wrap_err("Could not open the database")?;
+                self.command.run(&db)
+            }
+        }
+    }
+}
+
+impl Subcommands {
+    fn run<DB: DbTx>(&self, db: &DB) -> eyre::Result<()> {
+        match self {
+            Subcommands::Stats => {
+                println!("{:<32} {:>16} {:>16}", "Table", "Entries (est.)", "Size (bytes)");
+
+                let mut total_size = 0;
+                for table in Tables::ALL {
+                    let stats = table.view(&StatsViewer { db })?;
+                    total_size += stats.sst_files_size;
+
+                    println!(
+                        "{:<32} {:>16} {:>16}",
+                        table.name(),
+                        stats.estimated_entries,
+                        stats.sst_files_size
+                    );
+                }
+
+                println!("{:<32} {:>16} {:>16}", "Total", "", total_size);
+            }
+            Subcommands::Get { table, key } => {
+                match table.view(&GetValueViewer { db, key })? {
+                    Some(value) => println!("{}", serde_json::to_string_pretty(&value)?),
+                    None => info!(target: "simp::cli", "No entry found for {key} in {table}"),
+                }
+            }
+            Subcommands::List(args) => {
+                let entries = args.table.view(&ListViewer { db, args })?;
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+            }
+            Subcommands::Drop { table, force } => {
+                if !force && !confirm(&format!("Delete every entry of table {table}?"))? {
+                    info!(target: "simp::cli", "Table {table} was not dropped");
+                    return Ok(())
+                }
+
+                table.view(&DropViewer { db })?;
+                info!(target: "simp::cli", "Dropped table {table}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Asks the user to confirm `question` on the standard input, anything but `y` declines.
+fn confirm(question: &str) -> eyre::Result<bool> {
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Reads the size estimates RocksDB keeps for a table, the entries aren't read.
+struct StatsViewer<'a, DB> {
+    db: &'a DB,
+}
+
+impl<DB: DbTx> TableViewer<TableStats> for StatsViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<TableStats, Self::Error> {
+        Ok(self.db.dae_table_stats::<T>()?)
+    }
+}
+
+/// Reads the value of a JSON encoded key.
+struct GetValueViewer<'a, DB> {
+    db: &'a DB,
+    key: &'a str,
+}
+
+impl<DB: DbTx> TableViewer<Option<JsonValue>> for GetValueViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<Option<JsonValue>, Self::Error> {
+        let key = serde_json::from_str::<T::Key>(self.key)
+            .wrap_err_with(|| format!("Could not parse the key of table {}", T::NAME))?;
+
+        let value = self.db.dae_get::<T>(key)?;
+
+        Ok(value.map(serde_json::to_value).transpose()?)
+    }
+}
+
+/// Lists a page of entries of a table.
+struct ListViewer<'a, DB> {
+    db: &'a DB,
+    args: &'a ListArgs,
+}
+
+impl<DB: DbTx> TableViewer<Vec<JsonValue>> for ListViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<Vec<JsonValue>, Self::Error> {
+        let ListArgs { skip, len, reverse, .. } = *self.args;
+        let mut cursor = self.db.dae_cursor::<T>()?;
+
+        let rows = if reverse {
+            cursor.walk_back(None)?.skip(skip).take(len).collect::<Result<Vec<_>, _>>()?
+        } else {
+            cursor.walk(..)?.skip(skip).take(len).collect::<Result<Vec<_>, _>>()?
+        };
+
+        Ok(rows.into_iter().map(serde_json::to_value).collect::<Result<_, _>>()?)
+    }
+}
+
+/// Deletes every entry of a table.
+struct DropViewer<'a, DB> {
+    db: &'a DB,
+}
+
+impl<DB: DbTx> TableViewer<()> for DropViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        Ok(self.db.dae_clear::<T>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::{init_db, tables::Headers};
+    use simp_primitives::Header;
+
+    #[test]
+    fn parse_list_args() {
+        let command =
+            Command::try_parse_from(["db", "list", "Headers", "--skip", "2", "--len", "3", "-r"]).unwrap();
+
+        match command.command {
+            Subcommands::List(args) => {
+                assert_eq!(args.table, Tables::Headers);
+                assert_eq!((args.skip, args.len, args.reverse), (2, 3, true));
+            }
+            _ => panic!("expected the list subcommand"),
+        }
+
+        assert!(Command::try_parse_from(["db", "get", "NotATable", "1"]).is_err());
+
+        let command = Command::try_parse_from(["db", "drop", "Headers", "--force"]).unwrap();
+        assert!(matches!(command.command, Subcommands::Drop { table: Tables::Headers, force: true }));
+    }
+
+    #[test]
+    fn refuse_drop_on_secondary() {
+        let command =
+            Command::try_parse_from(["db", "--secondary", "/tmp/secondary", "drop", "Headers", "--force"])
+                .unwrap();
+
+        let err = command.execute(&Config::default()).unwrap_err();
+        assert!(err.to_string().contains("secondary"));
+    }
+
+    #[test]
+    fn view_tables() {
+        let db = init_db(tempfile::TempDir::new().unwrap().into_path()).unwrap();
+        for number in 0..10 {
+            db.dae_put::<Headers>(number, Header { number, ..Default::default() }).unwrap();
+        }
+
+        let stats = Tables::Headers.view(&StatsViewer { db: &db }).unwrap();
+        assert!(stats.estimated_entries > 0);
+
+        let value = Tables::Headers.view(&GetValueViewer { db: &db, key: "3" }).unwrap();
+        assert_eq!(value.unwrap()["number"], 3);
+
+        let args = ListArgs { table: Tables::Headers, skip: 1, len: 2, reverse: true };
+        let rows = Tables::Headers.view(&ListViewer { db: &db, args: &args }).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0], 8);
+
+        Tables::Headers.view(&DropViewer { db: &db }).unwrap();
+        assert_eq!(db.dae_entries::<Headers>().unwrap(), 0);
+    }
+}
@@ -1,7 +1,11 @@
 pub mod args;
 pub mod cli;
+pub mod config;
 pub mod runner;
 pub mod server;
+pub mod node;
+pub mod db;
+pub mod stage;
 pub mod dirs;
 pub mod utils;
 pub mod prometheus_exporter;
@@ -0,0 +1,166 @@
+//! Main node command
+//!
+//! Starts the staged sync pipeline, indexing the blocks of the chain into the database.
+use std::sync::Arc;
+
+use clap::Parser;
+use db::init_db_with_options;
+use eyre::Context;
+use futures::StreamExt;
+use simp_primitives::{BlockNumber, ChainRpcProvider};
+use stages::{
+    stages::{BlockIndexingStage, FinishStage, HeadersStage, SnapshotsIndexingStage, TracesStage},
+    Pipeline, PipelineEvent,
+};
+use storage_provider::{
+    options::AccessType,
+    traits::{ProtocolProvider, TrackingProvider, TrackingWriter},
+    DatabaseProvider,
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    args::{ChainArgs, RpcServerArgs},
+    config::{Config, TrackingConfig},
+    dirs,
+    runner::CliContext,
+};
+
+/// Start the node
+#[derive(Debug, Parser)]
+pub struct NodeCommand {
+    #[clap(flatten)]
+    pub chain: ChainArgs,
+
+    /// Stop the pipeline once every stage reached this block.
+    #[arg(long = "max-block", value_name = "BLOCK")]
+    pub max_block: Option<BlockNumber>,
+
+    /// The rpc servers run next to the pipeline, their subscriptions are fed by its events and the
+    /// `admin` module steers it.
+    #[clap(flatten)]
+    pub rpc: RpcServerArgs,
+}
+
+impl NodeCommand {
+    /// Runs the pipeline until `--max-block` is reached, or follows the tip of the chain forever if
+    /// it is not set.
+    ///
+    /// Every stage commits its writes per block together with its checkpoint, so the command can
+    /// be stopped at any time and resumes from the last committed block.
+    pub async fn execute(self, ctx: CliContext, config: Config) -> eyre::Result<()> {
+        let db_path = dirs::database_path()
+            .ok_or_else(|| eyre::eyre!("Could not resolve the database directory"))?;
+
+        info!(target: "simp::cli", path = ?db_path, "Opening database");
+        let db = init_db_with_options(&db_path, &config.db.database_options())
+            .wrap_err("Could not open the database")?;
+        let db_provider = Arc::new(DatabaseProvider::new(db, AccessType::Primary));
+
+        track(&db_provider, &config.tracking)?;
+
+        let chain_spec = self.chain.chain_spec(&config)?;
+        debug!(target: "simp::cli", chain = %chain_spec.chain(), "Chain spec");
+
+        let stages = &config.stages;
+        let mut builder =
+            Pipeline::builder().add_stage(HeadersStage::new(stages.headers.batch_size));
+
+        if stages.block_indexing.enabled {
+            builder = builder.add_stage(BlockIndexingStage);
+        }
+        if stages.traces.enabled {
+            builder = builder.add_stage(TracesStage::new(stages.traces.method));
+        }
+        if stages.snapshots_indexing.enabled {
+            builder = builder.add_stage(SnapshotsIndexingStage);
+        }
+        builder = builder.add_stage(FinishStage);
+
+        builder = match self.max_block {
+            Some(max_block) => builder.with_max_block(max_block),
+            // without a max block the pipeline keeps up with the chain, waking on every new block
+            None => builder.with_new_heads(chain_spec.subscribe_block()?),
+        };
+
+        let mut pipeline = builder.build(Arc::clone(&db_provider), chain_spec);
+
+        let events = pipeline.events();
+        ctx.task_executor.spawn(events.for_each(|event| async move { handle_pipeline_event(event) }));
+
+        let rpc = self.rpc.with_config(&config.rpc);
+        let _rpc_server_handles = if rpc.http || rpc.ws {
+            Some(rpc.start_servers(db_provider, pipeline.handle()).await?)
+        } else {
+            None
+        };
+
+        info!(target: "simp::cli", "Starting sync pipeline");
+        pipeline.run().await?;
+
+        info!(target: "simp::cli", max_block = ?self.max_block, "Pipeline reached the max block");
+        Ok(())
+    }
+}
+
+/// Registers the contracts and protocols of the config file that aren't tracked yet.
+fn track(db_provider: &DatabaseProvider, tracking: &TrackingConfig) -> eyre::Result<()> {
+    for address in tracking.contracts.iter() {
+        if !db_provider.is_contract_tracked(*address)? {
+            db_provider.insert_tracked_contract(*address)?;
+        }
+    }
+
+    let protocols = db_provider.get_all_protocols()?;
+    for factory_address in tracking.protocols.iter() {
+        if !protocols.iter().any(|protocol| protocol.factory_address == *factory_address) {
+            db_provider.create_protocol(*factory_address)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Forwards a [PipelineEvent] to tracing.
+fn handle_pipeline_event(event: PipelineEvent) {
+    match event {
+        PipelineEvent::Running { pipeline_stages_progress, stage_id, checkpoint } => {
+            info!(
+                target: "simp::cli",
+                pipeline_stages = %pipeline_stages_progress,
+                stage = %stage_id,
+                checkpoint = ?checkpoint,
+                "Executing stage",
+            );
+        }
+        PipelineEvent::Ran { pipeline_stages_progress, stage_id, result } => {
+            info!(
+                target: "simp::cli",
+                pipeline_stages = %pipeline_stages_progress,
+                stage = %stage_id,
+                checkpoint = result.checkpoint,
+                done = result.done,
+                "Stage committed progress",
+            );
+        }
+        PipelineEvent::Unwinding { stage_id, input } => {
+            info!(
+                target: "simp::cli",
+                stage = %stage_id,
+                from = input.checkpoint,
+                to = input.unwind_to,
+                bad_block = ?input.bad_block,
+                "Unwinding stage",
+            );
+        }
+        PipelineEvent::Unwound { stage_id, result } => {
+            info!(target: "simp::cli", stage = %stage_id, checkpoint = result.checkpoint, "Stage unwound");
+        }
+        PipelineEvent::Error { stage_id } => {
+            warn!(target: "simp::cli", stage = %stage_id, "Stage encountered an error");
+        }
+        PipelineEvent::Skipped { stage_id } => {
+            debug!(target: "simp::cli", stage = %stage_id, "Stage skipped");
+        }
+    }
+}
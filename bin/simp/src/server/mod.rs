@@ -1,8 +1,11 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 use clap::Parser;
+use db::init_db_with_options;
+use eyre::Context;
 use metrics_exporter_prometheus::PrometheusHandle;
+use storage_provider::{options::AccessType, DatabaseProvider};
 use tracing::{debug, info};
-use crate::{prometheus_exporter, runner::CliContext, args::{RpcServerArgs, utils::parse_socket_address}};
+use crate::{dirs, prometheus_exporter, runner::CliContext, config::Config, args::{RpcServerArgs, utils::parse_socket_address}};
 
 #[derive(Debug, Parser)]
 pub struct ServerCommand {
@@ -18,13 +21,18 @@ pub struct ServerCommand {
 }
 
 impl ServerCommand {
-    pub async fn execute(self, _ctx: CliContext) -> eyre::Result<()> {
+    pub async fn execute(mut self, _ctx: CliContext, config: Config) -> eyre::Result<()> {
         // Read config
         debug!("Read server config");
-        // let mut config: Config = self.load_config(config_path.clone())?;
+        self.rpc = self.rpc.with_config(&config.rpc);
 
         // Database config
         debug!("Database config: {:?}", self);
+        let db_path = dirs::database_path()
+            .ok_or_else(|| eyre::eyre!("Could not resolve the database directory"))?;
+        let db = init_db_with_options(&db_path, &config.db.database_options())
+            .wrap_err("Could not open the database")?;
+        let provider = Arc::new(DatabaseProvider::new(db, AccessType::Primary));
 
         let prometheus_handle = self.install_prometheus_recorder()?;
         
@@ -39,8 +47,9 @@ impl ServerCommand {
         /* // adjust rpc port numbers based on instance number
         self.adjust_instance_ports(); */
 
-        // Start RPC server
-        let _rpc_server_handles = self.rpc.start_servers().await?;
+        // Start RPC server, no pipeline runs in this process: subscriptions are closed right away
+        // and the admin commands fail
+        let _rpc_server_handles = self.rpc.start_servers(provider, ()).await?;
 
         /* // Start RPC servers
         let _rpc_server_handles =
@@ -0,0 +1,291 @@
+//! Stage debugging tool
+//!
+//! Runs, inspects and resets the stages of the sync pipeline one at a time.
+use clap::{Parser, Subcommand, ValueEnum};
+use db::{
+    init_db_with_options,
+    table::Table,
+    tables::{
+        AddressTraces, BlockIndices, BlockLogs, CanonicalHeaders, ContractLogs, DecodedLogs,
+        HeaderNumbers, Headers, Logs, Receipts, Traces, TransactionBlock, TransactionLogs,
+        Transactions, TxHashNumber,
+    },
+    transaction::DbTx,
+};
+use eyre::Context;
+use simp_primitives::{BlockNumber, StageId};
+use stages::{
+    stages::{BlockIndexingStage, FinishStage, HeadersStage, SnapshotsIndexingStage, TracesStage},
+    ExecInput, Stage,
+};
+use storage_provider::{
+    options::AccessType,
+    traits::{StageCheckpointProvider, StageCheckpointWriter},
+    DatabaseProvider,
+};
+use tracing::info;
+
+use crate::{args::ChainArgs, config::Config, dirs, runner::CliContext};
+
+/// `simp stage` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Subcommand, Debug)]
+/// `simp stage` subcommands
+pub enum Subcommands {
+    /// Runs a single stage for a range of blocks
+    Run(RunArgs),
+    /// Lists the checkpoint of every stage
+    Checkpoint,
+    /// Sets the checkpoint of a stage
+    SetCheckpoint {
+        /// The stage name
+        stage: StageEnum,
+        /// The block the stage is synced up to
+        block: BlockNumber,
+    },
+    /// Clears the tables written by a stage and the stages after it, and removes their
+    /// checkpoints
+    Drop {
+        /// The stage name
+        stage: StageEnum,
+    },
+}
+
+/// Arguments of `simp stage run`
+#[derive(Debug, Parser)]
+pub struct RunArgs {
+    /// The stage name
+    stage: StageEnum,
+
+    /// The first block to run the stage for
+    #[arg(long)]
+    from: BlockNumber,
+
+    /// The last block to run the stage for, inclusive
+    #[arg(long)]
+    to: BlockNumber,
+
+    /// Keep the checkpoint the stage saved while running, the previous checkpoint is restored
+    /// otherwise
+    #[arg(long, default_value_t = false)]
+    commit_checkpoint: bool,
+
+    #[clap(flatten)]
+    chain: ChainArgs,
+}
+
+/// The stages of the sync pipeline, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StageEnum {
+    Headers,
+    BlockIndexing,
+    Traces,
+    SnapshotsIndexing,
+    Finish,
+}
+
+impl StageEnum {
+    /// The id the stage checkpoint is stored under.
+    pub fn id(&self) -> StageId {
+        match self {
+            StageEnum::Headers => StageId::Headers,
+            StageEnum::BlockIndexing => StageId::BlockIndexing,
+            StageEnum::Traces => StageId::Traces,
+            StageEnum::SnapshotsIndexing => StageId::SnapshotsIndexing,
+            StageEnum::Finish => StageId::Finish,
+        }
+    }
+
+    fn stage(&self, config: &Config) -> Box<dyn Stage> {
+        match self {
+            StageEnum::Headers => Box::new(HeadersStage::new(config.stages.headers.batch_size)),
+            StageEnum::BlockIndexing => Box::new(BlockIndexingStage),
+            StageEnum::Traces => Box::new(TracesStage::new(config.stages.traces.method)),
+            StageEnum::SnapshotsIndexing => Box::new(SnapshotsIndexingStage),
+            StageEnum::Finish => Box::new(FinishStage),
+        }
+    }
+
+    /// The stage followed by every stage running after it, which depend on its data.
+    fn with_downstream(&self) -> impl Iterator<Item = StageEnum> + '_ {
+        Self::value_variants().iter().copied().skip_while(move |stage| stage != self)
+    }
+
+    /// Clears every table the stage writes to.
+    fn clear_tables<DB: DbTx>(&self, db: &DB) -> eyre::Result<()> {
+        match self {
+            StageEnum::Headers => {
+                clear::<CanonicalHeaders, _>(db)?;
+                clear::<Headers, _>(db)?;
+                clear::<HeaderNumbers, _>(db)?;
+            }
+            StageEnum::BlockIndexing => {
+                clear::<BlockIndices, _>(db)?;
+                clear::<Transactions, _>(db)?;
+                clear::<TransactionBlock, _>(db)?;
+                clear::<TxHashNumber, _>(db)?;
+                clear::<Receipts, _>(db)?;
+                clear::<TransactionLogs, _>(db)?;
+                clear::<BlockLogs, _>(db)?;
+                clear::<ContractLogs, _>(db)?;
+                clear::<Logs, _>(db)?;
+                clear::<DecodedLogs, _>(db)?;
+            }
+            StageEnum::Traces => {
+                clear::<Traces, _>(db)?;
+                clear::<AddressTraces, _>(db)?;
+            }
+            // snapshots are not persisted yet and the finish stage only has a checkpoint
+            StageEnum::SnapshotsIndexing | StageEnum::Finish => {}
+        }
+
+        Ok(())
+    }
+}
+
+fn clear<T: Table, DB: DbTx>(db: &DB) -> eyre::Result<()> {
+    db.dae_clear::<T>().wrap_err_with(|| format!("Could not clear table {}", T::NAME))
+}
+
+impl Command {
+    /// Execute `stage` command
+    pub async fn execute(self, _ctx: CliContext, config: Config) -> eyre::Result<()> {
+        let db_path = dirs::database_path()
+            .ok_or_else(|| eyre::eyre!("Could not resolve the database directory"))?;
+        let db = init_db_with_options(&db_path, &config.db.database_options())
+            .wrap_err("Could not open the database")?;
+        let provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        self.command.run(&provider, &config).await
+    }
+}
+
+impl Subcommands {
+    async fn run(self, provider: &DatabaseProvider, config: &Config) -> eyre::Result<()> {
+        match self {
+            Subcommands::Run(args) => {
+                let mut stage = args.stage.stage(config);
+                let previous_checkpoint = provider.get_stage_checkpoint(stage.id())?;
+                // stages start from the block after their checkpoint
+                let input = ExecInput { target: Some(args.to), checkpoint: args.from.checked_sub(1) };
+
+                info!(target: "simp::cli", stage = %stage.id(), from = args.from, to = args.to, "Running stage");
+                let chain = args.chain.chain_spec(config)?;
+                let output = stage.execute(input, provider, &chain).await;
+
+                // stages save their progress while running, the pipeline checkpoint only moves if
+                // asked to
+                match (&output, args.commit_checkpoint) {
+                    (Ok(output), true) => provider.save_stage_checkpoint(stage.id(), output.checkpoint)?,
+                    (Err(_), true) => {}
+                    (_, false) => match previous_checkpoint {
+                        Some(checkpoint) => provider.save_stage_checkpoint(stage.id(), checkpoint)?,
+                        None => provider.delete_stage_checkpoint(stage.id())?,
+                    },
+                }
+
+                info!(target: "simp::cli", stage = %stage.id(), checkpoint = output?.checkpoint, "Stage finished");
+            }
+            Subcommands::Checkpoint => {
+                for stage_id in StageId::ALL {
+                    match provider.get_stage_checkpoint(stage_id)? {
+                        Some(checkpoint) => println!("{stage_id:<20} {checkpoint}"),
+                        None => println!("{stage_id:<20} -"),
+                    }
+                }
+            }
+            Subcommands::SetCheckpoint { stage, block } => {
+                provider.save_stage_checkpoint(stage.id(), block)?;
+                info!(target: "simp::cli", stage = %stage.id(), checkpoint = block, "Stage checkpoint set");
+            }
+            Subcommands::Drop { stage } => {
+                for stage in stage.with_downstream() {
+                    stage.clear_tables(&provider.db)?;
+                    provider.delete_stage_checkpoint(stage.id())?;
+                    info!(target: "simp::cli", stage = %stage.id(), "Stage dropped");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::init_db;
+    use simp_primitives::{Header, Trace};
+    use storage_provider::traits::{HeaderWriter, TracesWriter};
+
+    #[test]
+    fn parse_run_args() {
+        let command = Command::try_parse_from([
+            "stage", "run", "block-indexing", "--from", "17000000", "--to", "17000100", "--rpc-url", "http://localhost:8545",
+        ])
+        .unwrap();
+
+        match command.command {
+            Subcommands::Run(args) => {
+                assert_eq!(args.stage, StageEnum::BlockIndexing);
+                assert_eq!((args.from, args.to), (17000000, 17000100));
+            }
+            _ => panic!("expected the run subcommand"),
+        }
+    }
+
+    #[tokio::test]
+    async fn drop_stage() {
+        let db = init_db(tempfile::TempDir::new().unwrap().into_path()).unwrap();
+        let provider = DatabaseProvider::new(db, AccessType::Primary);
+        let config = Config::default();
+
+        provider.insert_header(&1, Header::default()).unwrap();
+        Subcommands::SetCheckpoint { stage: StageEnum::Headers, block: 1 }.run(&provider, &config).await.unwrap();
+        Subcommands::SetCheckpoint { stage: StageEnum::BlockIndexing, block: 1 }.run(&provider, &config).await.unwrap();
+
+        Subcommands::SetCheckpoint { stage: StageEnum::Traces, block: 1 }.run(&provider, &config).await.unwrap();
+        provider.insert_traces(vec![(0, vec![Trace::default()])]).unwrap();
+
+        Subcommands::Drop { stage: StageEnum::BlockIndexing }.run(&provider, &config).await.unwrap();
+
+        assert_eq!(provider.db.dae_entries::<Traces>().unwrap(), 0);
+        assert_eq!(provider.get_stage_checkpoint(StageId::Headers).unwrap(), Some(1));
+        assert_eq!(provider.get_stage_checkpoint(StageId::BlockIndexing).unwrap(), None);
+        assert_eq!(provider.get_stage_checkpoint(StageId::Traces).unwrap(), None);
+
+        Subcommands::SetCheckpoint { stage: StageEnum::BlockIndexing, block: 1 }.run(&provider, &config).await.unwrap();
+        Subcommands::Drop { stage: StageEnum::Headers }.run(&provider, &config).await.unwrap();
+
+        assert_eq!(provider.db.dae_entries::<Headers>().unwrap(), 0);
+        for stage_id in StageId::ALL {
+            assert_eq!(provider.get_stage_checkpoint(stage_id).unwrap(), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn run_stage_keeps_checkpoint() {
+        let db = init_db(tempfile::TempDir::new().unwrap().into_path()).unwrap();
+        let provider = DatabaseProvider::new(db, AccessType::Primary);
+        let config = Config::default();
+        let run = |commit_checkpoint: &str| {
+            let mut args = vec!["stage", "run", "finish", "--from", "1", "--to", "10", "--rpc-url", "http://localhost:8545"];
+            if !commit_checkpoint.is_empty() {
+                args.push(commit_checkpoint);
+            }
+            Command::try_parse_from(args).unwrap().command
+        };
+
+        provider.save_stage_checkpoint(StageId::Finish, 3).unwrap();
+        run("").run(&provider, &config).await.unwrap();
+        assert_eq!(provider.get_stage_checkpoint(StageId::Finish).unwrap(), Some(3));
+
+        run("--commit-checkpoint").run(&provider, &config).await.unwrap();
+        assert_eq!(provider.get_stage_checkpoint(StageId::Finish).unwrap(), Some(10));
+    }
+}
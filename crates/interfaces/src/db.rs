@@ -43,6 +43,31 @@ pub enum DatabaseError {
     /// Failed to use the specified log level, as it's not available.
     #[error("Log level is not available: {0:?}")]
     LogLevelUnavailable(LogLevel),
+    /// The column family backing a table does not exist.
+    #[error("Column family for table \"{0}\" does not exist")]
+    MissingColumnFamily(&'static str),
+    /// Failed to encode or decode an entry of a table.
+    #[error("Failed to decode an entry of table \"{0}\"")]
+    Codec(&'static str),
+    /// RocksDB returned an error while reading or writing.
+    #[error("RocksDB error: {0}")]
+    Io(String),
+    /// The transaction conflicted with a concurrent one, it can be retried.
+    #[error("Transaction conflict: {0}")]
+    TransactionConflict(String),
+    /// The operation is applied to the database right away, it can't be part of a transaction.
+    #[error("{0} can't be part of a transaction")]
+    NotTransactional(&'static str),
+    /// The transaction can't be written while a cursor over it is open.
+    #[error("Transaction written while a cursor over it is open")]
+    CursorOpen,
+}
+
+impl DatabaseError {
+    /// Whether retrying the failed operation can succeed.
+    pub fn is_recoverable(&self) -> bool {
+        matches!(self, DatabaseError::TransactionConflict(_))
+    }
 }
 
 /// Database write operation type
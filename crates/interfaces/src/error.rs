@@ -8,6 +8,9 @@ pub enum Error {
     #[error(transparent)]
     Database(#[from] crate::db::DatabaseError),
 
+    #[error("range {start}..{end} exceeds the maximum of {max}")]
+    RangeTooLarge { start: u64, end: u64, max: u64 },
+
     #[error("{0}")]
     Custom(std::string::String),
 }
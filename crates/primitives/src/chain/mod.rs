@@ -9,12 +9,16 @@ use std::{fmt, str::FromStr};
 // The chain spec module.
 mod spec;
 pub use spec::{
-    ChainSpec, ChainSpecBuilder, DEV, GOERLI, MAINNET, SEPOLIA, ComputationEngine
+    ChainSpec, ChainSpecBuilder, DEV, GOERLI, MAINNET, SEPOLIA, ComputationEngine, DEFAULT_CONFIRMATION_DEPTH,
+    DEFAULT_RPC_BATCH_SIZE
 };
 
 mod traits;
 pub use traits::*;
 
+mod new_heads;
+pub use new_heads::NewHeadsStream;
+
 mod rpc;
 pub use rpc::*;
 /// Either a named or chain id or the actual id value
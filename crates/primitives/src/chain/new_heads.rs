@@ -0,0 +1,236 @@
+use futures::Stream;
+use jsonrpsee::{core::client::SubscriptionClientT, rpc_params, ws_client::WsClientBuilder};
+use std::{pin::Pin, time::Duration};
+use tokio::{sync::mpsc, time::MissedTickBehavior};
+use tracing::{debug, warn};
+
+use super::{error::RpcProviderError, ChainRpcProvider, ChainSpec};
+use crate::{BlockNumber, Header};
+
+/// Headers of the blocks added to the chain, see [ChainRpcProvider::subscribe_block].
+pub type NewHeadsStream = Pin<Box<dyn Stream<Item = Header> + Send>>;
+
+/// Number of headers buffered before the follower waits for the stream to be read.
+const NEW_HEADS_CAPACITY: usize = 64;
+
+/// Shortest interval between two polls of the tip, whatever the mint time of the chain.
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Wait before following the new heads again after the connection to the node failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+impl ChainSpec {
+    /// Spawns the task following the new heads and returns the stream it feeds.
+    ///
+    /// The task stops once the stream is dropped.
+    pub(crate) fn new_heads(&self) -> Result<NewHeadsStream, RpcProviderError> {
+        // built before cloning, so the follower shares the transport and its rate limits
+        self.transport()?;
+
+        let chain = self.clone();
+        let (tx, rx) = mpsc::channel(NEW_HEADS_CAPACITY);
+        tokio::spawn(async move { chain.follow_new_heads(tx).await });
+
+        Ok(Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|header| (header, rx))
+        })))
+    }
+
+    async fn follow_new_heads(self, tx: mpsc::Sender<Header>) {
+        // the last header sent, the next one has to follow it
+        let mut last = None;
+
+        loop {
+            let followed = match self.ws_connection.as_deref() {
+                Some(url) => self.subscribe_new_heads(url, &tx, &mut last).await,
+                None => self.poll_new_heads(&tx, &mut last).await,
+            };
+
+            match followed {
+                Ok(()) => return,
+                Err(err) => {
+                    warn!(target: "chain::rpc", %err, "Lost track of the new heads, reconnecting");
+
+                    tokio::select! {
+                        _ = tx.closed() => return,
+                        _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// Follows the new heads with an `eth_subscribe` subscription until the stream is dropped.
+    async fn subscribe_new_heads(
+        &self,
+        url: &str,
+        tx: &mpsc::Sender<Header>,
+        last: &mut Option<BlockNumber>,
+    ) -> Result<(), RpcProviderError> {
+        let client = WsClientBuilder::default()
+            .build(url)
+            .await
+            .map_err(|err| RpcProviderError::SocketError(err.to_string()))?;
+
+        let mut subscription = client
+            .subscribe::<Header, _>("eth_subscribe", rpc_params!["newHeads"], "eth_unsubscribe")
+            .await
+            .map_err(|err| RpcProviderError::SocketError(err.to_string()))?;
+
+        debug!(target: "chain::rpc", url, "Subscribed to the new heads");
+
+        loop {
+            let header = tokio::select! {
+                _ = tx.closed() => return Ok(()),
+                header = subscription.next() => header,
+            };
+
+            match header {
+                Some(Ok(header)) => {
+                    if !self.send_new_head(header, tx, last).await? {
+                        return Ok(())
+                    }
+                }
+                Some(Err(err)) => return Err(RpcProviderError::InvalidResponse(err.to_string())),
+                None => {
+                    return Err(RpcProviderError::SocketError(
+                        "new heads subscription closed".to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Follows the new heads by polling the tip once per mint time, for nodes only served over
+    /// http, until the stream is dropped.
+    async fn poll_new_heads(
+        &self,
+        tx: &mpsc::Sender<Header>,
+        last: &mut Option<BlockNumber>,
+    ) -> Result<(), RpcProviderError> {
+        let mut interval =
+            tokio::time::interval(Duration::from_millis(self.mint_time).max(MIN_POLL_INTERVAL));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = tx.closed() => return Ok(()),
+                _ = interval.tick() => {}
+            }
+
+            let tip = self.get_block_number().await?;
+            if last.map_or(false, |last| tip <= last) {
+                continue
+            }
+
+            let header = self.get_block_header::<Header>(tip).await?.result;
+            if !self.send_new_head(header, tx, last).await? {
+                return Ok(())
+            }
+        }
+    }
+
+    /// Sends the header, preceded by the headers of the blocks missed since the last one sent.
+    ///
+    /// Returns false once the stream is dropped.
+    async fn send_new_head(
+        &self,
+        header: Header,
+        tx: &mpsc::Sender<Header>,
+        last: &mut Option<BlockNumber>,
+    ) -> Result<bool, RpcProviderError> {
+        let mut headers = match *last {
+            Some(last) if header.number > last + 1 => {
+                self.get_blocks_headers::<Header>(last + 1, header.number - 1).await?
+            }
+            _ => vec![],
+        };
+        headers.push(header);
+
+        for header in headers {
+            *last = Some(header.number);
+
+            if tx.send(header).await.is_err() {
+                return Ok(false)
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use mockito::Matcher;
+    use serde_json::{json, Value};
+
+    fn header(number: u64) -> Value {
+        let hash = format!("0x{:064x}", number + 1);
+        let zero = format!("0x{:064x}", 0);
+
+        json!({
+            "hash": hash,
+            "parentHash": format!("0x{:064x}", number),
+            "sha3Uncles": zero,
+            "miner": format!("0x{:040x}", 0),
+            "transactionsRoot": zero,
+            "receiptsRoot": zero,
+            "stateRoot": zero,
+            "difficulty": "0x0",
+            "number": format!("0x{number:x}"),
+            "gasLimit": "0x0",
+            "gasUsed": "0x0",
+            "timestamp": "0x0",
+            "mixHash": zero,
+            "nonce": "0x0",
+            "extraData": "0x",
+        })
+    }
+
+    #[tokio::test]
+    async fn poll_new_heads_and_fill_gaps() {
+        let mut server = mockito::Server::new_async().await;
+
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJsonString(r#"{"method":"eth_blockNumber"}"#.to_string()))
+            .with_body(json!({"jsonrpc": "2.0", "id": 1, "result": "0x3"}).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJsonString(r#"{"method":"eth_getBlockByNumber"}"#.to_string()))
+            .with_body(json!({"jsonrpc": "2.0", "id": 1, "result": header(3)}).to_string())
+            .create_async()
+            .await;
+        // the batch downloading the missed blocks
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::Regex(r"^\[".to_string()))
+            .with_body(
+                json!([
+                    {"jsonrpc": "2.0", "id": 2, "result": header(2)},
+                    {"jsonrpc": "2.0", "id": 1, "result": header(1)},
+                ])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut chain = ChainSpec::default();
+        chain.rpc_connection = server.url();
+
+        let mut new_heads = chain.subscribe_block().unwrap();
+        assert_eq!(new_heads.next().await.map(|header| header.number), Some(3));
+
+        let (tx, mut rx) = mpsc::channel(NEW_HEADS_CAPACITY);
+        let header = serde_json::from_value::<Header>(header(3)).unwrap();
+        assert!(chain.send_new_head(header, &tx, &mut Some(0)).await.unwrap());
+
+        for number in 1..=3 {
+            assert_eq!(rx.recv().await.map(|header| header.number), Some(number));
+        }
+    }
+}
@@ -38,4 +38,48 @@ pub enum RpcProviderError {
 
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
+
+    /// The request did not complete in time.
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
+    /// The endpoint throttles the requests.
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    /// The endpoint answered with an http error status.
+    #[error("Http status {0}")]
+    HttpStatus(u16),
+
+    /// The node does not serve the method.
+    #[error("Method not found: {0}")]
+    MethodNotFound(String),
+
+    /// The node rejected the parameters of the request.
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+
+    /// Any other error object returned by the node.
+    #[error("Rpc error {code}: {message}")]
+    Rpc { code: i64, message: String },
+
+    /// The http client of the transport could not be built.
+    #[error("Could not build the http client: {0}")]
+    HttpClient(String),
+}
+
+impl RpcProviderError {
+    /// Returns true if sending the request again, possibly to another endpoint, may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RpcProviderError::SocketError(_) |
+            RpcProviderError::Timeout(_) |
+            RpcProviderError::RateLimited(_) => true,
+            RpcProviderError::HttpStatus(status) => *status == 429 || *status >= 500,
+            // internal errors and unavailable resources, -32000 is the catch-all of geth for
+            // errors of the request itself, e.g. "execution reverted"
+            RpcProviderError::Rpc { code, .. } => matches!(code, -32603 | -32002),
+            _ => false,
+        }
+    }
 }
@@ -1,5 +1,10 @@
 pub mod error;
 
+mod transport;
+pub use transport::{
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, RpcTransport, RpcTransportOptions,
+};
+
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
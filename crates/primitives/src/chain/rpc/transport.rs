@@ -0,0 +1,468 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+use super::{error::RpcProviderError, GenericNodeResponse};
+
+/// A JSON-RPC request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    pub id: u64,
+    pub method: String,
+    pub params: Value,
+}
+
+impl JsonRpcRequest {
+    pub fn new(id: u64, method: &str, params: Value) -> Self {
+        Self { jsonrpc: "2.0".to_string(), id, method: method.to_string(), params }
+    }
+}
+
+/// A JSON-RPC response, carrying either a result or an error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcResponse<T> {
+    pub jsonrpc: String,
+    pub id: u64,
+    pub result: Option<T>,
+    pub error: Option<JsonRpcError>,
+}
+
+impl<T> JsonRpcResponse<T> {
+    /// Returns the result, or the error the node responded with.
+    pub fn into_result(self) -> Result<T, RpcProviderError> {
+        match (self.result, self.error) {
+            (_, Some(error)) => Err(error.into()),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(RpcProviderError::InvalidResponse(format!(
+                "request {} has neither a result nor an error",
+                self.id
+            ))),
+        }
+    }
+}
+
+/// The error object of a failed JSON-RPC request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl JsonRpcError {
+    /// Returns true if the node refused the request because it spans too many blocks or results.
+    ///
+    /// Nodes don't agree on a code for it, e.g. geth answers "query returned more than 10000
+    /// results" and alchemy "Log response size exceeded", so the known messages are matched.
+    pub fn is_range_too_large(&self) -> bool {
+        const MESSAGES: [&str; 5] = [
+            "query returned more than",
+            "log response size exceeded",
+            "block range is too wide",
+            "exceed maximum block range",
+            "eth_getlogs is limited to",
+        ];
+
+        let message = self.message.to_lowercase();
+        MESSAGES.iter().any(|pattern| message.contains(pattern))
+    }
+
+    /// Returns true if the node throttles the requests, either with the -32005 "limit exceeded"
+    /// code of EIP-1474, a 429 code or a known message.
+    pub fn is_rate_limited(&self) -> bool {
+        const MESSAGES: [&str; 3] =
+            ["rate limit", "too many requests", "exceeded its compute units per second"];
+
+        let message = self.message.to_lowercase();
+        matches!(self.code, -32005 | 429)
+            || MESSAGES.iter().any(|pattern| message.contains(pattern))
+    }
+}
+
+impl From<JsonRpcError> for RpcProviderError {
+    fn from(error: JsonRpcError) -> Self {
+        if error.is_range_too_large() {
+            RpcProviderError::RangeTooLarge
+        } else if error.is_rate_limited() {
+            RpcProviderError::RateLimited(error.message)
+        } else {
+            match error.code {
+                -32700 | -32600 => RpcProviderError::InvalidRequest(error.message),
+                -32601 => RpcProviderError::MethodNotFound(error.message),
+                -32602 => RpcProviderError::InvalidParams(error.message),
+                code => RpcProviderError::Rpc { code, message: error.message },
+            }
+        }
+    }
+}
+
+/// Retries, timeouts and rate limit of an [RpcTransport].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RpcTransportOptions {
+    /// Number of times every endpoint is tried again after all of them failed.
+    pub max_retries: u32,
+    /// Wait in milliseconds before the first retry, doubled on every retry.
+    pub initial_backoff_ms: u64,
+    /// Maximum wait in milliseconds between two retries.
+    pub max_backoff_ms: u64,
+    /// Timeout in milliseconds of a single http request.
+    pub timeout_ms: u64,
+    /// Maximum number of http requests per second sent to each endpoint, unlimited if `None`.
+    pub requests_per_second: Option<u32>,
+}
+
+impl Default for RpcTransportOptions {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+            timeout_ms: 30_000,
+            requests_per_second: None,
+        }
+    }
+}
+
+/// Sends JSON-RPC requests to a list of endpoints.
+///
+/// Requests go to the last endpoint that answered. A failure that may be temporary moves on to
+/// the next endpoint, once all of them failed the transport backs off exponentially and tries
+/// them again, up to [RpcTransportOptions::max_retries] times. Errors of the request itself are
+/// returned right away.
+#[derive(Debug)]
+pub struct RpcTransport {
+    client: reqwest::Client,
+    endpoints: Vec<Endpoint>,
+    /// Index of the endpoint requests are sent to first.
+    preferred: AtomicUsize,
+    options: RpcTransportOptions,
+}
+
+impl RpcTransport {
+    pub fn new(
+        urls: impl IntoIterator<Item = String>,
+        options: RpcTransportOptions,
+    ) -> Result<Self, RpcProviderError> {
+        let endpoints = urls
+            .into_iter()
+            .map(|url| Endpoint { url, limiter: options.requests_per_second.map(RateLimiter::new) })
+            .collect();
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(options.timeout_ms))
+            .build()
+            .map_err(|err| RpcProviderError::HttpClient(err.to_string()))?;
+
+        Ok(Self { client, endpoints, preferred: AtomicUsize::new(0), options })
+    }
+
+    /// Sends a single request.
+    pub async fn send<T: DeserializeOwned>(
+        &self,
+        request: &JsonRpcRequest,
+    ) -> Result<GenericNodeResponse<T>, RpcProviderError> {
+        self.call(request, |response: JsonRpcResponse<T>| {
+            let (jsonrpc, id) = (response.jsonrpc.clone(), response.id);
+            Ok(GenericNodeResponse { jsonrpc, id, result: response.into_result()? })
+        })
+        .await
+    }
+
+    /// Sends the requests in a single batch array and returns their results in the order of the
+    /// requests.
+    ///
+    /// Every request needs a distinct id, nodes are free to answer a batch in any order.
+    pub async fn send_batch<T: DeserializeOwned>(
+        &self,
+        requests: &[JsonRpcRequest],
+    ) -> Result<Vec<T>, RpcProviderError> {
+        if requests.is_empty() {
+            return Ok(vec![])
+        }
+
+        self.call(requests, |responses| order_batch(requests, responses)).await
+    }
+
+    /// Posts `body` until `handle` accepts the response or fails with an error that is not
+    /// retryable.
+    async fn call<B, R, O>(
+        &self,
+        body: &B,
+        handle: impl Fn(R) -> Result<O, RpcProviderError>,
+    ) -> Result<O, RpcProviderError>
+    where
+        B: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        if self.endpoints.is_empty() {
+            return Err(RpcProviderError::InvalidRequest("no rpc endpoint configured".to_string()))
+        }
+
+        let mut backoff = Duration::from_millis(self.options.initial_backoff_ms);
+        let mut retries = 0;
+
+        loop {
+            let first = self.preferred.load(Ordering::Relaxed);
+            let mut last_error = None;
+
+            for offset in 0..self.endpoints.len() {
+                let index = (first + offset) % self.endpoints.len();
+                let endpoint = &self.endpoints[index];
+
+                match endpoint.post(&self.client, body).await.and_then(&handle) {
+                    Ok(output) => {
+                        self.preferred.store(index, Ordering::Relaxed);
+                        return Ok(output)
+                    }
+                    Err(err) if err.is_retryable() => {
+                        warn!(target: "chain::rpc", url = %endpoint.url, %err, "Rpc request failed");
+                        last_error = Some(err);
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+
+            if retries >= self.options.max_retries {
+                return Err(last_error.expect("at least one endpoint was tried"))
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(Duration::from_millis(self.options.max_backoff_ms));
+            retries += 1;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    url: String,
+    limiter: Option<RateLimiter>,
+}
+
+impl Endpoint {
+    async fn post<B: Serialize + ?Sized, R: DeserializeOwned>(
+        &self,
+        client: &reqwest::Client,
+        body: &B,
+    ) -> Result<R, RpcProviderError> {
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire().await;
+        }
+
+        let response = client.post(&self.url).json(body).send().await.map_err(request_error)?;
+
+        let status = response.status();
+        let response = response.text().await.map_err(request_error)?;
+
+        // nodes may answer an error object with an error status, prefer the error object
+        match serde_json::from_str(&response) {
+            Ok(response) => Ok(response),
+            Err(_) if status.as_u16() == 429 => Err(RpcProviderError::RateLimited(response)),
+            Err(_) if !status.is_success() => Err(RpcProviderError::HttpStatus(status.as_u16())),
+            Err(err) => Err(RpcProviderError::InvalidResponse(format!(
+                "could not parse {response}: {err}"
+            ))),
+        }
+    }
+}
+
+fn request_error(err: reqwest::Error) -> RpcProviderError {
+    if err.is_timeout() {
+        RpcProviderError::Timeout(err.to_string())
+    } else {
+        RpcProviderError::SocketError(err.to_string())
+    }
+}
+
+/// Token bucket allowing `requests_per_second` requests per second, in bursts of up to one
+/// second worth of requests.
+#[derive(Debug)]
+struct RateLimiter {
+    requests_per_second: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    fn new(requests_per_second: u32) -> Self {
+        let requests_per_second = f64::from(requests_per_second.max(1));
+
+        Self {
+            requests_per_second,
+            bucket: Mutex::new(Bucket { tokens: requests_per_second, refilled_at: Instant::now() }),
+        }
+    }
+
+    /// Waits until a token is available and takes it.
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().expect("rate limiter poisoned");
+                let now = Instant::now();
+                let refill = now.duration_since(bucket.refilled_at).as_secs_f64() * self.requests_per_second;
+
+                bucket.tokens = (bucket.tokens + refill).min(self.requests_per_second);
+                bucket.refilled_at = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return
+                }
+
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.requests_per_second)
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Matches the responses of a batch with its requests by id.
+fn order_batch<T>(
+    requests: &[JsonRpcRequest],
+    mut responses: Vec<JsonRpcResponse<T>>,
+) -> Result<Vec<T>, RpcProviderError> {
+    if responses.len() != requests.len() {
+        return Err(RpcProviderError::InvalidResponse(format!(
+            "batch of {} requests got {} responses",
+            requests.len(),
+            responses.len()
+        )))
+    }
+
+    requests
+        .iter()
+        .map(|request| {
+            let position = responses
+                .iter()
+                .position(|response| response.id == request.id)
+                .ok_or_else(|| {
+                    RpcProviderError::InvalidResponse(format!("no response to request {}", request.id))
+                })?;
+
+            responses.swap_remove(position).into_result()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_batch_responses() {
+        let requests = (0..3)
+            .map(|id| JsonRpcRequest::new(id, "eth_getBlockByNumber", serde_json::json!([])))
+            .collect::<Vec<_>>();
+
+        let responses: Vec<JsonRpcResponse<u64>> = serde_json::from_str(
+            r#"[
+                {"jsonrpc":"2.0","id":2,"result":12},
+                {"jsonrpc":"2.0","id":0,"result":10},
+                {"jsonrpc":"2.0","id":1,"result":11}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(order_batch(&requests, responses).unwrap(), vec![10, 11, 12]);
+
+        let responses: Vec<JsonRpcResponse<u64>> = serde_json::from_str(
+            r#"[
+                {"jsonrpc":"2.0","id":0,"result":10},
+                {"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"query returned more than 10000 results"}},
+                {"jsonrpc":"2.0","id":2,"result":12}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(order_batch(&requests, responses), Err(RpcProviderError::RangeTooLarge));
+
+        let responses: Vec<JsonRpcResponse<u64>> =
+            serde_json::from_str(r#"[{"jsonrpc":"2.0","id":0,"result":10}]"#).unwrap();
+        assert!(matches!(
+            order_batch(&requests, responses),
+            Err(RpcProviderError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn typed_rpc_errors() {
+        let error = |code: i64, message: &str| -> RpcProviderError {
+            JsonRpcError { code, message: message.to_string() }.into()
+        };
+
+        assert_eq!(
+            error(-32005, "Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range"),
+            RpcProviderError::RangeTooLarge
+        );
+        assert_eq!(error(-32000, "query returned more than 10000 results"), RpcProviderError::RangeTooLarge);
+
+        assert_eq!(error(-32602, "Log response size exceeded"), RpcProviderError::RangeTooLarge);
+
+        let rate_limited = error(-32005, "project ID request rate exceeded");
+        assert!(matches!(rate_limited, RpcProviderError::RateLimited(_)));
+        assert!(rate_limited.is_retryable());
+        let throttled = error(429, "Your app has exceeded its compute units per second capacity");
+        assert!(matches!(throttled, RpcProviderError::RateLimited(_)));
+
+        // errors of the request itself are not retried, whatever their message
+        let reverted = error(-32000, "execution reverted: gas limit exceeded");
+        assert!(matches!(reverted, RpcProviderError::Rpc { code: -32000, .. }));
+        assert!(!reverted.is_retryable());
+
+        let params = error(-32602, "invalid argument 0: block range exceeded");
+        assert!(matches!(params, RpcProviderError::InvalidParams(_)));
+        assert!(!params.is_retryable());
+
+        assert!(error(-32603, "internal error").is_retryable());
+
+        let method = error(-32601, "the method trace_block does not exist/is not available");
+        assert!(matches!(method, RpcProviderError::MethodNotFound(_)));
+        assert!(!method.is_retryable());
+        assert!(!RpcProviderError::RangeTooLarge.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn fail_over_to_next_endpoint() {
+        let mut down = mockito::Server::new_async().await;
+        let mut up = mockito::Server::new_async().await;
+
+        let down_mock = down.mock("POST", "/").with_status(503).expect(1).create_async().await;
+        let up_mock = up
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"0x10"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let options = RpcTransportOptions { max_retries: 0, ..Default::default() };
+        let transport = RpcTransport::new([down.url(), up.url()], options).unwrap();
+        let request = JsonRpcRequest::new(1, "eth_blockNumber", serde_json::json!([]));
+
+        let response = transport.send::<String>(&request).await.unwrap();
+        assert_eq!(response.result, "0x10");
+
+        // the endpoint that answered is tried first from now on
+        transport.send::<String>(&request).await.unwrap();
+
+        down_mock.assert_async().await;
+        up_mock.assert_async().await;
+
+        let transport = RpcTransport::new([down.url()], options).unwrap();
+        let error = transport.send::<String>(&request).await;
+        assert_eq!(error, Err(RpcProviderError::HttpStatus(503)));
+    }
+}
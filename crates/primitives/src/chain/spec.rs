@@ -1,9 +1,19 @@
-use super::{error::RpcProviderError, ChainRpcProvider};
-use crate::{chain::GenericNodeResponse, Chain};
-use once_cell::sync::Lazy;
-use reqwest;
+use super::{
+    error::RpcProviderError, ChainRpcProvider, JsonRpcRequest, NewHeadsStream, RpcTransport,
+    RpcTransportOptions,
+};
+use crate::{chain::GenericNodeResponse, BlockNumber, Chain, TxHash, U64};
+use once_cell::sync::{Lazy, OnceCell};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::sync::Arc;
+use tracing::debug;
+
+/// The default number of requests sent in a single JSON-RPC batch.
+pub const DEFAULT_RPC_BATCH_SIZE: u64 = 100;
+
+/// The default number of blocks below the head of the chain after which a block is final, two
+/// beacon chain epochs.
+pub const DEFAULT_CONFIRMATION_DEPTH: u64 = 64;
 
 /// The Ethereum mainnet spec
 pub static MAINNET: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
@@ -13,6 +23,12 @@ pub static MAINNET: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         computation_engine: ComputationEngine::EVM,
         mint_time: 12000,
         confirmation_block_time: 12,
+        confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
+        rpc_batch_size: DEFAULT_RPC_BATCH_SIZE,
+        ws_connection: None,
+        rpc_fallbacks: Vec::new(),
+        rpc_transport: Default::default(),
+        transport: Default::default(),
     }
     .into()
 });
@@ -25,6 +41,12 @@ pub static GOERLI: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         computation_engine: ComputationEngine::EVM,
         mint_time: 4000,
         confirmation_block_time: 15,
+        confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
+        rpc_batch_size: DEFAULT_RPC_BATCH_SIZE,
+        ws_connection: None,
+        rpc_fallbacks: Vec::new(),
+        rpc_transport: Default::default(),
+        transport: Default::default(),
     }
     .into()
 });
@@ -37,6 +59,12 @@ pub static SEPOLIA: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
         computation_engine: ComputationEngine::EVM,
         mint_time: 4000,
         confirmation_block_time: 15,
+        confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
+        rpc_batch_size: DEFAULT_RPC_BATCH_SIZE,
+        ws_connection: None,
+        rpc_fallbacks: Vec::new(),
+        rpc_transport: Default::default(),
+        transport: Default::default(),
     }
     .into()
 });
@@ -72,8 +100,26 @@ pub struct ChainSpec {
     pub computation_engine: ComputationEngine,
     /// Approximate mint time of a new block in millisecond
     pub mint_time: u64,
-    /// Number of blocks to confirm a given block
+    /// Approximate time in seconds to confirm a given block
     pub confirmation_block_time: u64,
+    /// Number of blocks below the head of the chain after which a block can't be reorganised
+    #[serde(default = "default_confirmation_depth")]
+    pub confirmation_depth: u64,
+    /// Number of requests sent in a single JSON-RPC batch when downloading a range of blocks
+    #[serde(default = "default_rpc_batch_size")]
+    pub rpc_batch_size: u64,
+    /// Websocket endpoint the new heads are subscribed to, they are polled over http if `None`
+    #[serde(default)]
+    pub ws_connection: Option<String>,
+    /// Endpoints the requests fail over to when `rpc_connection` keeps failing
+    #[serde(default)]
+    pub rpc_fallbacks: Vec<String>,
+    /// Retries, timeouts and rate limit of the requests to the endpoints
+    #[serde(default)]
+    pub rpc_transport: RpcTransportOptions,
+    /// Transport to the endpoints, built on the first request
+    #[serde(skip)]
+    transport: OnceCell<Arc<RpcTransport>>,
 }
 
 impl Default for ChainSpec {
@@ -84,10 +130,24 @@ impl Default for ChainSpec {
             mint_time: Default::default(),
             computation_engine: Default::default(),
             confirmation_block_time: Default::default(),
+            confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
+            rpc_batch_size: DEFAULT_RPC_BATCH_SIZE,
+            ws_connection: Default::default(),
+            rpc_fallbacks: Default::default(),
+            rpc_transport: Default::default(),
+            transport: Default::default(),
         }
     }
 }
 
+fn default_rpc_batch_size() -> u64 {
+    DEFAULT_RPC_BATCH_SIZE
+}
+
+fn default_confirmation_depth() -> u64 {
+    DEFAULT_CONFIRMATION_DEPTH
+}
+
 impl ChainSpec {
     /// Get information about the chain itself
     pub fn chain(&self) -> Chain {
@@ -102,6 +162,17 @@ impl ChainSpec {
         self.confirmation_block_time
     }
 
+    pub fn confirmation_depth(&self) -> u64 {
+        self.confirmation_depth
+    }
+
+    /// Returns the highest block considered final when `head` is the latest block of the chain.
+    ///
+    /// Blocks above it are still inside the confirmation window and may be reorganised.
+    pub fn finalized_block(&self, head: BlockNumber) -> BlockNumber {
+        head.saturating_sub(self.confirmation_depth)
+    }
+
     pub fn is_evm(&self) -> bool {
         self.computation_engine == ComputationEngine::EVM
     }
@@ -113,6 +184,26 @@ impl ChainSpec {
     pub fn rpc_connection(&self) -> String {
         self.rpc_connection.clone()
     }
+
+    /// Returns the transport to `rpc_connection` and its fallbacks.
+    ///
+    /// It is built on the first request, later changes to the rpc settings are ignored.
+    pub fn transport(&self) -> Result<&RpcTransport, RpcProviderError> {
+        let transport = self.transport.get_or_try_init(|| {
+            let urls = std::iter::once(self.rpc_connection.clone())
+                .chain(self.rpc_fallbacks.iter().cloned())
+                .filter(|url| !url.is_empty());
+
+            RpcTransport::new(urls, self.rpc_transport).map(Arc::new)
+        })?;
+
+        Ok(transport)
+    }
+
+    /// Number of requests sent in a single JSON-RPC batch, at least one.
+    pub fn rpc_batch_size(&self) -> u64 {
+        self.rpc_batch_size.max(1)
+    }
     // /// Get an iterator of all hardforks with their respective activation conditions.
     // pub fn forks_iter(&self) -> impl Iterator<Item = (Hardfork, ForkCondition)> + '_ {
     //     self.hardforks.iter().map(|(f, b)| (*f, *b))
@@ -126,32 +217,27 @@ impl ChainSpec {
 
 #[async_trait::async_trait]
 impl ChainRpcProvider for ChainSpec {
+    async fn get_block_number(&self) -> Result<BlockNumber, RpcProviderError> {
+        match self.chain_type() {
+            ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
+                let request = JsonRpcRequest::new(1, "eth_blockNumber", serde_json::json!([]));
+                let response = self.transport()?.send::<U64>(&request).await?;
+
+                Ok(response.result.as_u64())
+            }
+            _ => Err(RpcProviderError::InvalidRequest(
+                "Invalid chain type".to_string(),
+            )),
+        }
+    }
+
     async fn get_block_header<T: Serialize + DeserializeOwned + Send>(
         &self,
         block_number: u64,
     ) -> Result<GenericNodeResponse<T>, RpcProviderError> {
-        let client = reqwest::Client::new();
-
         match self.chain_type() {
             ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
-                let query = serde_json::to_string(&serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "id": 1,
-                    "method": "eth_getBlockByNumber",
-                    "params": [&format!("0x{:x}", block_number), false],
-                }))
-                .unwrap();
-
-                let request = client
-                    .post(self.rpc_connection())
-                    .body(query)
-                    .send()
-                    .await
-                    .unwrap();
-
-                let response = request.text().await.unwrap();
-                let data: GenericNodeResponse<T> = serde_json::from_str(&response).unwrap();
-                Ok(data)
+                self.transport()?.send(&block_request(1, block_number, false)).await
             }
             _ => Err(RpcProviderError::InvalidRequest(
                 "Invalid chain type".to_string(),
@@ -163,31 +249,49 @@ impl ChainRpcProvider for ChainSpec {
         &self,
         block_number: u64,
     ) -> Result<GenericNodeResponse<Vec<T>>, RpcProviderError> {
-        let client = reqwest::Client::new();
+        match self.chain_type() {
+            ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
+                self.transport()?.send(&logs_request(1, block_number, block_number)).await
+            }
+            _ => Err(RpcProviderError::InvalidRequest(
+                "Invalid chain type".to_string(),
+            )),
+        }
+    }
 
+    async fn get_block_receipts<T: Serialize + DeserializeOwned + Send>(
+        &self,
+        block_number: u64,
+    ) -> Result<GenericNodeResponse<Vec<T>>, RpcProviderError> {
         match self.chain_type() {
             ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
-                let query = serde_json::to_string(&serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "id": 1,
-                    "method": "eth_getLogs",
-                    "params": [{
-                        "fromBlock": &format!("0x{:x}", block_number),
-                        "toBlock": &format!("0x{:x}", block_number),
-                    }],
-                }))
-                .unwrap();
-
-                let request = client
-                    .post(self.rpc_connection())
-                    .body(query)
-                    .send()
-                    .await
-                    .unwrap();
-
-                let response = request.text().await.unwrap();
-                let data: GenericNodeResponse<Vec<T>> = serde_json::from_str(&response).unwrap();
-                Ok(data)
+                let request = JsonRpcRequest::new(
+                    1,
+                    "eth_getBlockReceipts",
+                    serde_json::json!([format!("0x{:x}", block_number)]),
+                );
+
+                match self.transport()?.send(&request).await {
+                    Err(RpcProviderError::MethodNotFound(_)) => {
+                        debug!(
+                            target: "chain::rpc",
+                            block_number,
+                            "eth_getBlockReceipts not served, fetching the receipts per transaction"
+                        );
+
+                        let response: GenericNodeResponse<BlockTransactions<TxHash>> =
+                            self.transport()?.send(&block_request(1, block_number, false)).await?;
+                        let receipts =
+                            self.get_transactions_receipts(&response.result.transactions).await?;
+
+                        Ok(GenericNodeResponse {
+                            jsonrpc: response.jsonrpc,
+                            id: response.id,
+                            result: receipts,
+                        })
+                    }
+                    response => response,
+                }
             }
             _ => Err(RpcProviderError::InvalidRequest(
                 "Invalid chain type".to_string(),
@@ -199,30 +303,35 @@ impl ChainRpcProvider for ChainSpec {
         &self,
         block_number: u64,
     ) -> Result<GenericNodeResponse<Vec<T>>, RpcProviderError> {
-        let client = reqwest::Client::new();
         match self.chain_type() {
             ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
-                let query = serde_json::to_string(&serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "id": 1,
-                    "method": "trace_block",
-                    "params": [{
-                        "fromBlock": &format!("0x{:x}", block_number),
-                        "toBlock": &format!("0x{:x}", block_number),
-                    }],
-                }))
-                .unwrap();
-
-                let request = client
-                    .post(self.rpc_connection())
-                    .body(query)
-                    .send()
-                    .await
-                    .unwrap();
-
-                let response = request.text().await.unwrap();
-                let data: GenericNodeResponse<Vec<T>> = serde_json::from_str(&response).unwrap();
-                Ok(data)
+                let request = JsonRpcRequest::new(
+                    1,
+                    "trace_block",
+                    serde_json::json!([format!("0x{:x}", block_number)]),
+                );
+
+                self.transport()?.send(&request).await
+            }
+            _ => Err(RpcProviderError::InvalidRequest(
+                "Invalid chain type".to_string(),
+            )),
+        }
+    }
+
+    async fn get_block_call_traces<T: Serialize + DeserializeOwned + Send>(
+        &self,
+        block_number: u64,
+    ) -> Result<GenericNodeResponse<Vec<T>>, RpcProviderError> {
+        match self.chain_type() {
+            ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
+                let request = JsonRpcRequest::new(
+                    1,
+                    "debug_traceBlockByNumber",
+                    serde_json::json!([format!("0x{:x}", block_number), {"tracer": "callTracer"}]),
+                );
+
+                self.transport()?.send(&request).await
             }
             _ => Err(RpcProviderError::InvalidRequest(
                 "Invalid chain type".to_string(),
@@ -234,27 +343,16 @@ impl ChainRpcProvider for ChainSpec {
         &self,
         block_number: u64,
     ) -> Result<GenericNodeResponse<Vec<T>>, RpcProviderError> {
-        let client = reqwest::Client::new();
         match self.chain_type() {
             ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
-                let query = serde_json::to_string(&serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "id": 1,
-                    "method": "eth_getBlockByNumber",
-                    "params": [&format!("0x{:x}", block_number), true],
-                }))
-                .unwrap();
-
-                let request = client
-                    .post(self.rpc_connection())
-                    .body(query)
-                    .send()
-                    .await
-                    .unwrap();
-
-                let response = request.text().await.unwrap();
-                let data: GenericNodeResponse<Vec<T>> = serde_json::from_str(&response).unwrap();
-                Ok(data)
+                let response: GenericNodeResponse<BlockTransactions<T>> =
+                    self.transport()?.send(&block_request(1, block_number, true)).await?;
+
+                Ok(GenericNodeResponse {
+                    jsonrpc: response.jsonrpc,
+                    id: response.id,
+                    result: response.result.transactions,
+                })
             }
             _ => Err(RpcProviderError::InvalidRequest(
                 "Invalid chain type".to_string(),
@@ -269,13 +367,7 @@ impl ChainRpcProvider for ChainSpec {
     ) -> Result<Vec<T>, RpcProviderError> {
         match self.chain_type() {
             ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
-                let mut headers = vec![];
-
-                for bn in from..=to {
-                    let header = self.get_block_header(bn).await.unwrap();
-                    headers.push(header.result);
-                }
-                Ok(headers)
+                self.get_blocks(from, to, false).await
             }
             _ => Err(RpcProviderError::InvalidRequest(
                 "Invalid chain type".to_string(),
@@ -290,12 +382,21 @@ impl ChainRpcProvider for ChainSpec {
     ) -> Result<Vec<T>, RpcProviderError> {
         match self.chain_type() {
             ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
-                // by block for now and find better way to get more blocks per call
                 let mut logs = vec![];
-
-                for bn in from..=to {
-                    let log = self.get_block_logs(bn).await.unwrap();
-                    logs.extend(log.result);
+                // ranges left to download, the next one last
+                let mut ranges = vec![(from, to)];
+
+                while let Some((start, end)) = ranges.pop() {
+                    match self.transport()?.send::<Vec<T>>(&logs_request(1, start, end)).await {
+                        Ok(response) => logs.extend(response.result),
+                        // the node caps the results of a query, split the range in halves
+                        Err(RpcProviderError::RangeTooLarge) if start < end => {
+                            let middle = start + (end - start) / 2;
+                            ranges.push((middle + 1, end));
+                            ranges.push((start, middle));
+                        }
+                        Err(err) => return Err(err),
+                    }
                 }
 
                 Ok(logs)
@@ -313,14 +414,9 @@ impl ChainRpcProvider for ChainSpec {
     ) -> Result<Vec<T>, RpcProviderError> {
         match self.chain_type() {
             ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
-                let mut txs = vec![];
-
-                for bn in from..=to {
-                    let tx = self.get_block_txs(bn).await.unwrap();
-                    txs.extend(tx.result);
-                }
+                let blocks: Vec<BlockTransactions<T>> = self.get_blocks(from, to, true).await?;
 
-                Ok(txs)
+                Ok(blocks.into_iter().flat_map(|block| block.transactions).collect())
             }
             _ => Err(RpcProviderError::InvalidRequest(
                 "Invalid chain type".to_string(),
@@ -328,22 +424,9 @@ impl ChainRpcProvider for ChainSpec {
         }
     }
 
-    async fn subscribe_block<T: Serialize + DeserializeOwned + Send>(
-        &self
-    ) -> Result<T, RpcProviderError> {
+    fn subscribe_block(&self) -> Result<NewHeadsStream, RpcProviderError> {
         match self.chain_type() {
-            ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
-                // (
-                //     "subscribeNewHeads".to_string(),
-                //     serde_json::json!({
-                //         "jsonrpc": "2.0",
-                //         "id": 1,
-                //         "method": "eth_subscribe",
-                //         "params": ["newHeads", {}]
-                //     })
-                // )
-                unimplemented!()
-            }
+            ComputationEngine::EVM | ComputationEngine::EVMCompatible => self.new_heads(),
             _ => Err(RpcProviderError::InvalidRequest(
                 "Invalid chain type".to_string(),
             )),
@@ -351,6 +434,89 @@ impl ChainRpcProvider for ChainSpec {
     }
 }
 
+impl ChainSpec {
+    /// Downloads the blocks `from..=to` in batches of [ChainSpec::rpc_batch_size] requests.
+    async fn get_blocks<T: DeserializeOwned + Send>(
+        &self,
+        from: u64,
+        to: u64,
+        full_transactions: bool,
+    ) -> Result<Vec<T>, RpcProviderError> {
+        let mut blocks = Vec::with_capacity(to.saturating_sub(from) as usize + 1);
+        let mut start = from;
+
+        while start <= to {
+            let end = to.min(start.saturating_add(self.rpc_batch_size() - 1));
+            let requests = (start..=end)
+                .map(|block_number| block_request(block_number, block_number, full_transactions))
+                .collect::<Vec<_>>();
+
+            blocks.extend(self.transport()?.send_batch::<T>(&requests).await?);
+
+            if end == u64::MAX {
+                break
+            }
+            start = end + 1;
+        }
+
+        Ok(blocks)
+    }
+
+    /// Downloads the receipts of the transactions with `eth_getTransactionReceipt`, in batches of
+    /// [ChainSpec::rpc_batch_size] requests.
+    async fn get_transactions_receipts<T: DeserializeOwned + Send>(
+        &self,
+        hashes: &[TxHash],
+    ) -> Result<Vec<T>, RpcProviderError> {
+        let mut receipts = Vec::with_capacity(hashes.len());
+
+        for chunk in hashes.chunks(self.rpc_batch_size() as usize) {
+            let requests = chunk
+                .iter()
+                .enumerate()
+                .map(|(id, hash)| {
+                    JsonRpcRequest::new(
+                        id as u64,
+                        "eth_getTransactionReceipt",
+                        serde_json::json!([hash]),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            receipts.extend(self.transport()?.send_batch::<T>(&requests).await?);
+        }
+
+        Ok(receipts)
+    }
+}
+
+/// The transactions of a block downloaded with `eth_getBlockByNumber`.
+#[derive(Debug, Deserialize)]
+struct BlockTransactions<T> {
+    transactions: Vec<T>,
+}
+
+/// `eth_getBlockByNumber` request of a block, with the full transactions or only their hashes.
+fn block_request(id: u64, block_number: u64, full_transactions: bool) -> JsonRpcRequest {
+    JsonRpcRequest::new(
+        id,
+        "eth_getBlockByNumber",
+        serde_json::json!([format!("0x{:x}", block_number), full_transactions]),
+    )
+}
+
+/// `eth_getLogs` request of every log of the blocks `from..=to`.
+fn logs_request(id: u64, from: u64, to: u64) -> JsonRpcRequest {
+    JsonRpcRequest::new(
+        id,
+        "eth_getLogs",
+        serde_json::json!([{
+            "fromBlock": format!("0x{:x}", from),
+            "toBlock": format!("0x{:x}", to),
+        }]),
+    )
+}
+
 /// A helper to build custom chain specs
 #[derive(Debug, Default)]
 pub struct ChainSpecBuilder {
@@ -391,3 +557,57 @@ impl From<&Arc<ChainSpec>> for ChainSpecBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use serde_json::{json, Value};
+
+    #[tokio::test]
+    async fn fall_back_to_transaction_receipts() {
+        let mut server = mockito::Server::new_async().await;
+        let hashes = [format!("0x{:064x}", 1), format!("0x{:064x}", 2)];
+
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJsonString(r#"{"method":"eth_getBlockReceipts"}"#.to_string()))
+            .with_body(
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "error": {"code": -32601, "message": "the method eth_getBlockReceipts does not exist"},
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJsonString(r#"{"method":"eth_getBlockByNumber"}"#.to_string()))
+            .with_body(json!({"jsonrpc": "2.0", "id": 1, "result": {"transactions": hashes}}).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::Regex(r#"^\[.*"eth_getTransactionReceipt""#.to_string()))
+            .with_body(
+                json!([
+                    {"jsonrpc": "2.0", "id": 1, "result": {"transactionHash": hashes[1]}},
+                    {"jsonrpc": "2.0", "id": 0, "result": {"transactionHash": hashes[0]}},
+                ])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut chain = ChainSpec::default();
+        chain.rpc_connection = server.url();
+
+        let receipts = chain.get_block_receipts::<Value>(10).await.unwrap().result;
+        let receipt_hashes =
+            receipts.iter().map(|receipt| receipt["transactionHash"].clone()).collect::<Vec<_>>();
+
+        assert_eq!(receipt_hashes, vec![json!(hashes[0]), json!(hashes[1])]);
+    }
+}
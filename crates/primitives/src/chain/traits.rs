@@ -1,13 +1,23 @@
 use serde::{Serialize,de::DeserializeOwned};
 
-use super::{error::RpcProviderError, GenericNodeResponse};
+use super::{error::RpcProviderError, GenericNodeResponse, NewHeadsStream};
+use crate::BlockNumber;
 
 #[async_trait::async_trait]
 pub trait ChainRpcProvider: Send + Sync {
+    async fn get_block_number(&self) -> Result<BlockNumber, RpcProviderError>;
     async fn get_block_header<T: Serialize + DeserializeOwned + Send>(&self, block_number: u64) -> Result<GenericNodeResponse<T>, RpcProviderError>;
     async fn get_block_txs<T: Serialize + DeserializeOwned + Send>(&self, block_number: u64) -> Result<GenericNodeResponse<Vec<T>>, RpcProviderError>;
     async fn get_block_logs<T: Serialize + DeserializeOwned + Send>(&self, block_number: u64) -> Result<GenericNodeResponse<Vec<T>>, RpcProviderError>;
+    /// Receipts of the transactions of a block, in the order of the transactions.
+    ///
+    /// Uses `eth_getBlockReceipts`, or one `eth_getTransactionReceipt` per transaction on nodes
+    /// that don't serve it.
+    async fn get_block_receipts<T: Serialize + DeserializeOwned + Send>(&self, block_number: u64) -> Result<GenericNodeResponse<Vec<T>>, RpcProviderError>;
     async fn get_block_traces<T: Serialize + DeserializeOwned + Send>(&self, block_number: u64) -> Result<GenericNodeResponse<Vec<T>>, RpcProviderError>;
+    /// Call traces of the transactions of a block, from `debug_traceBlockByNumber` with the
+    /// `callTracer`.
+    async fn get_block_call_traces<T: Serialize + DeserializeOwned + Send>(&self, block_number: u64) -> Result<GenericNodeResponse<Vec<T>>, RpcProviderError>;
     async fn get_blocks_headers<T: Serialize + DeserializeOwned + Send>(&self, from: u64, to: u64) -> Result<Vec<T>, RpcProviderError>;
     async fn get_blocks_txs<T: Serialize + DeserializeOwned + Send>(&self, from: u64, to: u64) -> Result<Vec<T>, RpcProviderError>;
     async fn get_blocks_logs<T: Serialize + DeserializeOwned + Send>(&self, from: u64, to: u64) -> Result<Vec<T>, RpcProviderError>;
@@ -15,5 +25,9 @@ pub trait ChainRpcProvider: Send + Sync {
     // fn get_blocks_headers_iter<T>(&self, from: u64, to: u64) -> impl Iterator<Item = T> + '_;
     // fn get_blocks_txs_iter<T>(&self, from: u64, to: u64) -> impl Iterator<Item = T> + '_;
     // fn get_blocks_logs_iter<T>(&self, from: u64, to: u64) -> impl Iterator<Item = T> + '_;
-    async fn subscribe_block<T: Serialize + DeserializeOwned + Send>(&self) -> Result<T, RpcProviderError>;
+    /// Streams the headers of the blocks added to the chain, over the websocket endpoint if there
+    /// is one and by polling the tip otherwise.
+    ///
+    /// Blocks missed while reconnecting are filled in. Has to be called within a tokio runtime.
+    fn subscribe_block(&self) -> Result<NewHeadsStream, RpcProviderError>;
 }
\ No newline at end of file
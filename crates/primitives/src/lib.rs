@@ -6,7 +6,8 @@ pub use revm_primitives::{B160 as H160, B256 as H256, U256};
 
 mod chain;
 pub use chain::{
-    Chain, ChainSpec, ChainSpecBuilder, DEV, GOERLI, MAINNET, SEPOLIA, ComputationEngine, ChainRpcProvider
+    Chain, ChainSpec, ChainSpecBuilder, DEV, GOERLI, MAINNET, SEPOLIA, ComputationEngine, ChainRpcProvider,
+    DEFAULT_CONFIRMATION_DEPTH, DEFAULT_RPC_BATCH_SIZE, NewHeadsStream, RpcTransport, RpcTransportOptions
 };
 
 mod stage;
@@ -33,10 +34,16 @@ mod bits;
 pub use bits::H512;
 
 mod log;
-pub use log::{Log, StoredDecodedData, StoredLog, DecodedData};
+pub use log::{Log, StoredDecodedData, StoredLog, StoredOrDecodedLog, DecodedData};
 
 pub mod constants;
 
+mod receipt;
+pub use receipt::Receipt;
+
+mod trace;
+pub use trace::{CallFrame, CallType, ParityAction, ParityResult, ParityTrace, Trace, TraceMethod, TransactionCallFrame};
+
 mod storage;
 pub use storage::StorageEntry;
 
@@ -1,6 +1,6 @@
 use crate::{Address, Bytes, H256};
 use rlp::{RlpDecodable, RlpEncodable};
-use serde::{de::Error, Deserializer, Deserialize};
+use serde::{de::Error, Deserializer, Deserialize, Serialize};
 use sip_codecs::{main_codec, Compact};
 
 /// Ethereum Log
@@ -89,3 +89,11 @@ pub struct DecodedData {
     pub indexed: bool,
     pub signature: H256,
 }
+
+/// A log read from the database, either as emitted or as decoded with the abi of its contract.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StoredOrDecodedLog {
+    Raw(StoredLog),
+    Decoded(StoredDecodedData),
+}
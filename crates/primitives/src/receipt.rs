@@ -0,0 +1,114 @@
+use crate::{
+    compression::{RECEIPT_COMPRESSOR, RECEIPT_DECOMPRESSOR},
+    serde_helper::{u128_hex, u64_hex},
+    Address,
+};
+use sip_codecs::{main_codec, Compact, CompactZstd};
+
+/// Receipt of a transaction, as returned by `eth_getBlockReceipts` and
+/// `eth_getTransactionReceipt`.
+///
+/// Stored compressed with the receipt dictionary, see [RECEIPT_COMPRESSOR].
+#[main_codec(zstd)]
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+    /// Whether the transaction succeeded.
+    ///
+    /// Receipts from before Byzantium carry a state root instead of a status, they are read as
+    /// successful.
+    #[serde(rename = "status", default = "status::pre_byzantium", with = "status")]
+    pub success: bool,
+    /// Gas used in the block by this transaction and the ones before it.
+    #[serde(with = "u64_hex")]
+    pub cumulative_gas_used: u64,
+    /// Gas used by this transaction.
+    #[serde(with = "u64_hex")]
+    pub gas_used: u64,
+    /// Price paid per unit of gas, base fee included. Missing from the receipts of nodes
+    /// predating London, it is then 0.
+    #[serde(default, with = "u128_hex")]
+    pub effective_gas_price: u128,
+    /// Contract created by the transaction, if it is a contract creation.
+    pub contract_address: Option<Address>,
+}
+
+/// serde functions for the `status` of a receipt, `0x1` on success and `0x0` on failure.
+mod status {
+    use crate::U64;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn pre_byzantium() -> bool {
+        true
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        U64::deserialize(deserializer).map(|status| !status.is_zero())
+    }
+
+    pub(super) fn serialize<S: Serializer>(value: &bool, s: S) -> Result<S::Ok, S::Error> {
+        U64::from(*value as u64).serialize(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn deserialize_rpc_receipt() {
+        let receipt: Receipt = serde_json::from_value(json!({
+            "status": "0x0",
+            "cumulativeGasUsed": "0x1d4c0",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x3b9aca00",
+            "contractAddress": null,
+            "transactionHash": format!("0x{:064x}", 1),
+        }))
+        .unwrap();
+
+        assert_eq!(
+            receipt,
+            Receipt {
+                success: false,
+                cumulative_gas_used: 120_000,
+                gas_used: 21_000,
+                effective_gas_price: 1_000_000_000,
+                contract_address: None,
+            }
+        );
+
+        // before Byzantium and London
+        let receipt: Receipt = serde_json::from_value(json!({
+            "root": format!("0x{:064x}", 2),
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "contractAddress": format!("0x{:040x}", 3),
+        }))
+        .unwrap();
+
+        assert!(receipt.success);
+        assert_eq!(receipt.effective_gas_price, 0);
+        assert!(receipt.contract_address.is_some());
+    }
+
+    #[test]
+    fn compact_roundtrip_compressed() {
+        let receipt = Receipt {
+            success: true,
+            cumulative_gas_used: 1_234_567,
+            gas_used: 54_321,
+            effective_gas_price: 30_000_000_000,
+            contract_address: Some(Address::repeat_byte(0x11)),
+        };
+
+        let mut buf = vec![];
+        let len = receipt.clone().to_compact(&mut buf);
+
+        assert_eq!(Receipt::from_compact(&buf, len).0, receipt);
+    }
+}
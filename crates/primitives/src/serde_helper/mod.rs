@@ -32,6 +32,44 @@ pub mod u64_hex {
     }
 }
 
+/// serde functions for handling primitive `u128` as [U128](ethers_core::types::U128)
+pub mod u128_hex {
+    use ethers_core::types::U128;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Deserializes an `u128` from [U128] accepting a hex quantity string with optional 0x prefix
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u128, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        U128::deserialize(deserializer).map(|val| val.as_u128())
+    }
+
+    /// Serializes u128 as hex string
+    pub fn serialize<S: Serializer>(value: &u128, s: S) -> Result<S::Ok, S::Error> {
+        U128::from(*value).serialize(s)
+    }
+}
+
+/// serde functions for handling `U256` as decimal strings
+pub mod u256_decimal {
+    use crate::U256;
+    use serde::{Deserializer, Serializer};
+
+    /// Deserializes an `U256` from a decimal or hex string, or a number
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<U256, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize_json_u256(deserializer)
+    }
+
+    /// Serializes `U256` as decimal string
+    pub fn serialize<S: Serializer>(value: &U256, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(value)
+    }
+}
+
 /// serde functions for handling bytes as hex strings, such as [bytes::Bytes]
 pub mod hex_bytes {
     use serde::{Deserialize, Deserializer, Serializer};
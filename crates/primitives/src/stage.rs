@@ -6,6 +6,8 @@ pub enum StageId {
     Headers,
     // index the block
     BlockIndexing,
+    // index the call traces of the transactions
+    Traces,
     // updated the market snapshots
     SnapshotsIndexing,
     // finish loop
@@ -14,9 +16,10 @@ pub enum StageId {
 }
 
 impl StageId {
-    pub const ALL: [StageId; 4] = [
+    pub const ALL: [StageId; 5] = [
         StageId::Headers,
         StageId::BlockIndexing,
+        StageId::Traces,
         StageId::SnapshotsIndexing,
         StageId::Finish,
     ];
@@ -25,6 +28,7 @@ impl StageId {
         match self {
             StageId::Headers => "Headers",
             StageId::BlockIndexing => "BlockIndexing",
+            StageId::Traces => "Traces",
             StageId::SnapshotsIndexing => "SnapshotsIndexing",
             StageId::Finish => "Finish",
             StageId::Other(name) => name,
@@ -36,7 +40,7 @@ impl StageId {
     }
 
     pub fn is_downloading_stage(&self) -> bool {
-        matches!(self, StageId::BlockIndexing | StageId::Headers)
+        matches!(self, StageId::BlockIndexing | StageId::Headers | StageId::Traces)
     }
 }
 
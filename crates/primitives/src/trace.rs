@@ -0,0 +1,388 @@
+use crate::{Address, Bytes, TxHash, U256, U64};
+use serde::{Deserialize, Serialize};
+use sip_codecs::{main_codec, Compact};
+
+/// The rpc method the traces of a block are downloaded with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceMethod {
+    /// `trace_block`, served by Erigon, Nethermind and Reth.
+    #[default]
+    TraceBlock,
+    /// `debug_traceBlockByNumber` with the `callTracer`, served by Geth.
+    DebugTraceBlock,
+}
+
+/// Kind of call of a [Trace].
+#[main_codec]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CallType {
+    #[default]
+    Call,
+    StaticCall,
+    DelegateCall,
+    CallCode,
+    Create,
+    Create2,
+    SelfDestruct,
+}
+
+impl CallType {
+    /// Parses the call type as named by the tracers, e.g. `CALL` for `callTracer` or `call` for
+    /// `trace_block`.
+    pub fn from_tracer(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "call" => Some(CallType::Call),
+            "staticcall" => Some(CallType::StaticCall),
+            "delegatecall" => Some(CallType::DelegateCall),
+            "callcode" => Some(CallType::CallCode),
+            "create" => Some(CallType::Create),
+            "create2" => Some(CallType::Create2),
+            "selfdestruct" | "suicide" => Some(CallType::SelfDestruct),
+            _ => None,
+        }
+    }
+}
+
+/// A call made while executing a transaction, the transaction itself or one of its internal
+/// calls.
+#[main_codec]
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Trace {
+    pub call_type: CallType,
+    pub from: Address,
+    /// Callee, the created contract for creations and the beneficiary for self destructs.
+    /// Missing if a creation failed.
+    pub to: Option<Address>,
+    pub value: U256,
+    /// Gas given to the call.
+    pub gas: u64,
+    pub gas_used: u64,
+    /// Position of the call in the call tree, empty for the transaction itself. `[1, 0]` is the
+    /// first call made by the second call of the transaction.
+    pub trace_address: Vec<u64>,
+    /// Reason the call failed, empty if it succeeded.
+    pub error: Vec<u8>,
+    /// Returned data, or the code of the created contract.
+    pub output: Option<Bytes>,
+    /// Call data, or the init code of a creation.
+    pub input: Bytes,
+}
+
+impl Trace {
+    /// Whether the call was made by a contract rather than being the transaction itself.
+    pub fn is_internal(&self) -> bool {
+        !self.trace_address.is_empty()
+    }
+
+    /// Whether the call succeeded. Its changes may still be reverted by a failing parent call.
+    pub fn is_success(&self) -> bool {
+        self.error.is_empty()
+    }
+
+    /// The addresses taking part in the call.
+    pub fn addresses(&self) -> impl Iterator<Item = Address> {
+        std::iter::once(self.from).chain(self.to)
+    }
+}
+
+/// A call frame returned by `debug_traceBlockByNumber` with the `callTracer`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub from: Address,
+    #[serde(default)]
+    pub to: Option<Address>,
+    #[serde(default)]
+    pub value: Option<U256>,
+    #[serde(default)]
+    pub gas: U64,
+    #[serde(default)]
+    pub gas_used: U64,
+    #[serde(default)]
+    pub input: Bytes,
+    #[serde(default)]
+    pub output: Option<Bytes>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub calls: Vec<CallFrame>,
+}
+
+impl CallFrame {
+    /// Flattens the frame and its calls depth first, the order `trace_block` lists them in.
+    pub fn into_traces(self) -> Vec<Trace> {
+        let mut traces = vec![];
+        self.flatten(vec![], &mut traces);
+        traces
+    }
+
+    fn flatten(mut self, trace_address: Vec<u64>, traces: &mut Vec<Trace>) {
+        let calls = std::mem::take(&mut self.calls);
+
+        traces.push(Trace {
+            call_type: CallType::from_tracer(&self.call_type).unwrap_or_default(),
+            from: self.from,
+            to: self.to,
+            value: self.value.unwrap_or_default(),
+            gas: self.gas.as_u64(),
+            gas_used: self.gas_used.as_u64(),
+            trace_address: trace_address.clone(),
+            error: self.error.map(String::into_bytes).unwrap_or_default(),
+            output: self.output,
+            input: self.input,
+        });
+
+        for (index, call) in calls.into_iter().enumerate() {
+            let mut call_address = trace_address.clone();
+            call_address.push(index as u64);
+            call.flatten(call_address, traces);
+        }
+    }
+}
+
+/// The trace of a transaction returned by `debug_traceBlockByNumber`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionCallFrame {
+    /// Only returned by recent nodes.
+    #[serde(default)]
+    pub tx_hash: Option<TxHash>,
+    pub result: CallFrame,
+}
+
+/// A trace returned by `trace_block`, in the format of the `trace` namespace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParityTrace {
+    pub action: ParityAction,
+    #[serde(default)]
+    pub result: Option<ParityResult>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub trace_address: Vec<u64>,
+    /// Position of the transaction in the block, missing for block rewards.
+    #[serde(default)]
+    pub transaction_position: Option<u64>,
+    /// `call`, `create`, `suicide` or `reward`.
+    #[serde(rename = "type")]
+    pub trace_type: String,
+}
+
+/// The fields of every kind of `trace_block` action.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ParityAction {
+    pub call_type: Option<String>,
+    pub creation_method: Option<String>,
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub value: Option<U256>,
+    pub gas: Option<U64>,
+    pub input: Option<Bytes>,
+    pub init: Option<Bytes>,
+    /// The self destructed contract.
+    pub address: Option<Address>,
+    pub refund_address: Option<Address>,
+    pub balance: Option<U256>,
+}
+
+/// The fields of every kind of `trace_block` result.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ParityResult {
+    pub gas_used: Option<U64>,
+    pub output: Option<Bytes>,
+    /// The created contract.
+    pub address: Option<Address>,
+    pub code: Option<Bytes>,
+}
+
+impl ParityTrace {
+    /// Returns the position of the transaction in the block with the trace, `None` for block
+    /// rewards.
+    pub fn into_trace(self) -> Option<(u64, Trace)> {
+        let position = self.transaction_position?;
+        let action = self.action;
+        let result = self.result.unwrap_or_default();
+
+        let (call_type, from, to, value, input, output) = match self.trace_type.as_str() {
+            "call" => (
+                action.call_type.as_deref().and_then(CallType::from_tracer).unwrap_or_default(),
+                action.from,
+                action.to,
+                action.value,
+                action.input,
+                result.output,
+            ),
+            "create" => (
+                action.creation_method.as_deref().and_then(CallType::from_tracer).unwrap_or(CallType::Create),
+                action.from,
+                result.address,
+                action.value,
+                action.init,
+                result.code,
+            ),
+            "suicide" => (
+                CallType::SelfDestruct,
+                action.address,
+                action.refund_address,
+                action.balance,
+                None,
+                None,
+            ),
+            _ => return None,
+        };
+
+        Some((
+            position,
+            Trace {
+                call_type,
+                from: from.unwrap_or_default(),
+                to,
+                value: value.unwrap_or_default(),
+                gas: action.gas.unwrap_or_default().as_u64(),
+                gas_used: result.gas_used.unwrap_or_default().as_u64(),
+                trace_address: self.trace_address,
+                error: self.error.map(String::into_bytes).unwrap_or_default(),
+                output,
+                input: input.unwrap_or_default(),
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn address(byte: u8) -> String {
+        format!("0x{}", hex::encode([byte; 20]))
+    }
+
+    #[test]
+    fn flatten_call_frames() {
+        let frame: CallFrame = serde_json::from_value(json!({
+            "type": "CALL",
+            "from": address(1),
+            "to": address(2),
+            "value": "0x10",
+            "gas": "0x5208",
+            "gasUsed": "0x5000",
+            "input": "0x",
+            "calls": [
+                {
+                    "type": "STATICCALL",
+                    "from": address(2),
+                    "to": address(3),
+                    "gas": "0x100",
+                    "gasUsed": "0x10",
+                    "input": "0x01",
+                    "output": "0x02",
+                },
+                {
+                    "type": "CREATE2",
+                    "from": address(2),
+                    "to": address(4),
+                    "value": "0x1",
+                    "gas": "0x200",
+                    "gasUsed": "0x200",
+                    "input": "0x03",
+                    "error": "out of gas",
+                    "calls": [{"type": "SELFDESTRUCT", "from": address(4), "to": address(1)}],
+                },
+            ],
+        }))
+        .unwrap();
+
+        let traces = frame.into_traces();
+
+        let call_types = traces.iter().map(|trace| trace.call_type).collect::<Vec<_>>();
+        assert_eq!(
+            call_types,
+            vec![CallType::Call, CallType::StaticCall, CallType::Create2, CallType::SelfDestruct]
+        );
+
+        let trace_addresses = traces.iter().map(|trace| trace.trace_address.clone()).collect::<Vec<_>>();
+        assert_eq!(trace_addresses, vec![vec![], vec![0], vec![1], vec![1, 0]]);
+
+        assert_eq!(traces[0].value, U256::from(16));
+        assert!(!traces[0].is_internal());
+        assert_eq!(traces[1].output, Some(Bytes::from(vec![2])));
+        assert!(!traces[2].is_success());
+        assert_eq!(traces[2].error, b"out of gas".to_vec());
+    }
+
+    #[test]
+    fn convert_parity_traces() {
+        let traces: Vec<ParityTrace> = serde_json::from_value(json!([
+            {
+                "action": {"callType": "delegatecall", "from": address(1), "to": address(2), "gas": "0x10", "input": "0x01", "value": "0x0"},
+                "result": {"gasUsed": "0x8", "output": "0x02"},
+                "subtraces": 0,
+                "traceAddress": [0],
+                "transactionPosition": 3,
+                "type": "call",
+            },
+            {
+                "action": {"from": address(1), "gas": "0x10", "init": "0x60", "value": "0x5"},
+                "result": {"gasUsed": "0x10", "address": address(5), "code": "0x61"},
+                "subtraces": 0,
+                "traceAddress": [],
+                "transactionPosition": 4,
+                "type": "create",
+            },
+            {
+                "action": {"author": address(6), "rewardType": "block", "value": "0x1"},
+                "result": null,
+                "subtraces": 0,
+                "traceAddress": [],
+                "type": "reward",
+            },
+        ]))
+        .unwrap();
+
+        let traces = traces.into_iter().filter_map(ParityTrace::into_trace).collect::<Vec<_>>();
+        assert_eq!(traces.len(), 2);
+
+        let (position, call) = &traces[0];
+        assert_eq!(*position, 3);
+        assert_eq!(call.call_type, CallType::DelegateCall);
+        assert_eq!(call.trace_address, vec![0]);
+        assert_eq!(call.gas_used, 8);
+
+        let (position, create) = &traces[1];
+        assert_eq!(*position, 4);
+        assert_eq!(create.call_type, CallType::Create);
+        assert_eq!(create.to, Some(Address::repeat_byte(5)));
+        assert_eq!(create.input, Bytes::from(vec![0x60]));
+        assert_eq!(create.output, Some(Bytes::from(vec![0x61])));
+    }
+
+    #[test]
+    fn compact_roundtrip() {
+        let trace = Trace {
+            call_type: CallType::DelegateCall,
+            from: Address::repeat_byte(1),
+            to: Some(Address::repeat_byte(2)),
+            value: U256::from(7),
+            gas: 100_000,
+            gas_used: 21_000,
+            trace_address: vec![0, 2],
+            error: b"execution reverted".to_vec(),
+            output: Some(Bytes::from(vec![1, 2, 3])),
+            input: Bytes::from(vec![4, 5, 6]),
+        };
+
+        let mut buf = vec![];
+        let len = trace.clone().to_compact(&mut buf);
+
+        assert_eq!(Trace::from_compact(&buf, len).0, trace);
+    }
+}
@@ -4,6 +4,9 @@ use address_balance::AddressBalance;
 pub mod keys;
 pub use keys::*;
 
+mod timeframe;
+pub use timeframe::Timeframe;
+
 use crate::{H256, U256};
 use rlp::{RlpDecodable, RlpEncodable};
 use sip_codecs::{ main_codec, Compact};
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+/// Declares the [Timeframe]s volumes are aggregated over, together with the id prefixing their
+/// keys in the volumetric tables, their serialized name and their length in seconds. Ids are
+/// persisted, they must never be reused.
+macro_rules! timeframes {
+    ($(($timeframe:ident, $id:literal, $name:literal, $seconds:literal)),* $(,)?) => {
+        /// Period a volume is aggregated over.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        pub enum Timeframe {
+            $(
+                #[doc = concat!("Volumes aggregated by ", stringify!($timeframe))]
+                #[serde(rename = $name)]
+                $timeframe,
+            )*
+        }
+
+        impl Timeframe {
+            /// Every registered timeframe.
+            pub const ALL: &'static [Timeframe] = &[$(Timeframe::$timeframe,)*];
+
+            /// Id prefixing the keys of the timeframe in the volumetric tables.
+            pub const fn id(&self) -> u8 {
+                match self {
+                    $(Timeframe::$timeframe => $id,)*
+                }
+            }
+
+            /// Returns the timeframe registered with `id`.
+            pub const fn from_id(id: u8) -> Option<Self> {
+                match id {
+                    $($id => Some(Timeframe::$timeframe),)*
+                    _ => None,
+                }
+            }
+
+            /// Short name of the timeframe, e.g. `5m`.
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    $(Timeframe::$timeframe => $name,)*
+                }
+            }
+
+            /// Length of a period in seconds.
+            pub const fn seconds(&self) -> u64 {
+                match self {
+                    $(Timeframe::$timeframe => $seconds,)*
+                }
+            }
+        }
+    };
+}
+
+/// Calls the macro `$callback` with every registered timeframe, as `(Variant, id, "name",
+/// seconds)` tuples. New timeframes are registered here, ids are persisted and must never be
+/// reused.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! for_each_timeframe {
+    ($($callback:tt)+) => {
+        $($callback)+!(
+            (FiveMinute, 0, "5m", 300),
+            (Hourly, 1, "1h", 3_600),
+            (Daily, 2, "1d", 86_400),
+        )
+    };
+}
+
+for_each_timeframe!(timeframes);
+
+/// Expands to the serialized names of the registered timeframes as a string literal, e.g.
+/// "`5m`, `1h`", so docs listing them follow the registry.
+#[macro_export]
+macro_rules! timeframe_names {
+    () => {
+        $crate::for_each_timeframe!($crate::__timeframe_names)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __timeframe_names {
+    (($first:ident, $first_id:literal, $first_name:literal, $first_seconds:literal),
+        $(($timeframe:ident, $id:literal, $name:literal, $seconds:literal)),* $(,)?) => {
+        concat!("`", $first_name, "`" $(, ", `", $name, "`")*)
+    };
+}
+
+impl Timeframe {
+    /// Returns the start of the period `timestamp` falls in.
+    pub const fn period_start(&self, timestamp: u64) -> u64 {
+        timestamp - timestamp % self.seconds()
+    }
+}
+
+impl std::fmt::Display for Timeframe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -0,0 +1,60 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
+use simp_primitives::BlockNumber;
+
+/// Checkpoint of a stage of the pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageCheckpoint {
+    /// Name of the stage, e.g. `Headers`.
+    pub stage: String,
+    /// The block the stage is synced up to, `null` if it never ran.
+    pub checkpoint: Option<BlockNumber>,
+}
+
+/// State of the running pipeline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineStatus {
+    /// Whether the stages are paused.
+    pub paused: bool,
+    /// The block the pipeline stops at, `null` if it runs forever.
+    pub max_block: Option<BlockNumber>,
+    /// The lowest block reached by a stage since the pipeline started.
+    pub minimum_block_number: Option<BlockNumber>,
+    /// The highest block reached by a stage since the pipeline started.
+    pub maximum_block_number: Option<BlockNumber>,
+}
+
+/// Admin namespace rpc interface that reports and steers the sync pipeline.
+///
+/// Commands are applied between two runs of the stages and fail if no pipeline runs next to the
+/// rpc server.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait AdminApi {
+    /// Returns the checkpoint of every stage.
+    #[method(name = "stageCheckpoints")]
+    fn stage_checkpoints(&self) -> RpcResult<Vec<StageCheckpoint>>;
+
+    /// Returns the status of the pipeline.
+    #[method(name = "pipelineStatus")]
+    fn pipeline_status(&self) -> RpcResult<PipelineStatus>;
+
+    /// Pauses the pipeline once the running stages are done.
+    #[method(name = "pause")]
+    fn pause(&self) -> RpcResult<()>;
+
+    /// Resumes a paused pipeline.
+    #[method(name = "resume")]
+    fn resume(&self) -> RpcResult<()>;
+
+    /// Changes the block the pipeline stops at, `null` runs it forever.
+    ///
+    /// The node shuts down once every stage reached it.
+    #[method(name = "setMaxBlock")]
+    fn set_max_block(&self, max_block: Option<BlockNumber>) -> RpcResult<()>;
+
+    /// Unwinds every stage to the given block.
+    #[method(name = "unwind")]
+    fn unwind(&self, block: BlockNumber) -> RpcResult<()>;
+}
@@ -0,0 +1,55 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use simp_primitives::{
+    Address, Block, BlockNumber, StoredOrDecodedLog, TransactionSigned, TxHash, H256,
+};
+
+use crate::Page;
+
+/// Simp namespace rpc interface that gives access to the indexed blocks, transactions and logs.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "simp"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "simp"))]
+pub trait DataApi {
+    /// Returns the block with the given number, `null` if it is not indexed.
+    #[method(name = "getBlockByNumber")]
+    fn block_by_number(&self, number: BlockNumber) -> RpcResult<Option<Block>>;
+
+    /// Returns the block with the given hash, `null` if it is not indexed.
+    #[method(name = "getBlockByHash")]
+    fn block_by_hash(&self, hash: H256) -> RpcResult<Option<Block>>;
+
+    /// Returns the transaction with the given hash, `null` if it is not indexed.
+    #[method(name = "getTransactionByHash")]
+    fn transaction_by_hash(&self, hash: TxHash) -> RpcResult<Option<TransactionSigned>>;
+
+    /// Returns the logs emitted by a transaction, decoded with the abi of the emitting contract
+    /// if `decoded` is set.
+    #[method(name = "getLogsByTransaction")]
+    fn logs_by_transaction(
+        &self,
+        hash: TxHash,
+        decoded: Option<bool>,
+    ) -> RpcResult<Option<Vec<StoredOrDecodedLog>>>;
+
+    /// Returns the logs emitted between two blocks, both inclusive.
+    #[method(name = "getLogsByBlockRange")]
+    fn logs_by_block_range(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        decoded: Option<bool>,
+    ) -> RpcResult<Vec<StoredOrDecodedLog>>;
+
+    /// Returns the logs emitted by a contract, optionally between two blocks.
+    ///
+    /// `next` is the offset to pass to read the following page.
+    #[method(name = "getLogsByAddress")]
+    fn logs_by_address(
+        &self,
+        address: Address,
+        from: Option<BlockNumber>,
+        to: Option<BlockNumber>,
+        decoded: Option<bool>,
+        offset: Option<u64>,
+        limit: Option<usize>,
+    ) -> RpcResult<Page<StoredOrDecodedLog>>;
+}
@@ -1,11 +1,27 @@
+mod admin;
+mod data;
 mod hello;
+mod pubsub;
+mod registry;
+mod volumes;
+
+pub use admin::{PipelineStatus, StageCheckpoint};
+pub use pubsub::{LogsFilter, Params, SubscriptionItem, SubscriptionKind, VolumesFilter};
+pub use volumes::{Page, TokenVolume, VolumeCandle};
 
 /// re-export of all server traits
 pub use servers::*;
 
 /// Aggregates all server traits.
 pub mod servers {
-    pub use crate::hello::HelloApiServer;
+    pub use crate::{
+        admin::AdminApiServer,
+        data::DataApiServer,
+        hello::HelloApiServer,
+        pubsub::SimpPubSubApiServer,
+        registry::{RegistryAdminApiServer, RegistryApiServer},
+        volumes::VolumesApiServer,
+    };
 }
 
 /// re-export of all client traits
@@ -15,5 +31,12 @@ pub use clients::*;
 /// Aggregates all client traits.
 #[cfg(feature = "client")]
 pub mod clients {
-    pub use crate::hello::HelloApiClient;
+    pub use crate::{
+        admin::AdminApiClient,
+        data::DataApiClient,
+        hello::HelloApiClient,
+        pubsub::SimpPubSubApiClient,
+        registry::{RegistryAdminApiClient, RegistryApiClient},
+        volumes::VolumesApiClient,
+    };
 }
@@ -0,0 +1,127 @@
+use jsonrpsee::proc_macros::rpc;
+use serde::{Deserialize, Serialize};
+use simp_primitives::{Address, Header, StoredLog, Timeframe, H256};
+
+use crate::VolumeCandle;
+
+/// Topics of `simp_subscribe`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+    /// Headers of the blocks committed by the headers stage.
+    NewHeads,
+    /// Logs of the blocks committed by the block indexing stage, see [LogsFilter].
+    Logs,
+    /// Candles of a market closed by the indexed blocks, see [VolumesFilter].
+    Volumes,
+}
+
+/// Filter of a `logs` subscription.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogsFilter {
+    /// Contracts the logs are emitted by, any contract if empty.
+    #[serde(default)]
+    pub address: Vec<Address>,
+    /// Topics matched by position, `null` matches any topic.
+    #[serde(default)]
+    pub topics: Vec<Option<H256>>,
+}
+
+impl LogsFilter {
+    /// Returns true if the log is emitted by one of the addresses and has the filtered topics.
+    pub fn matches(&self, log: &StoredLog) -> bool {
+        let address_matches = self.address.is_empty() || self.address.contains(&log.address);
+        let topics_match = self.topics.iter().enumerate().all(|(position, topic)| match topic {
+            Some(topic) => log.topics.get(position) == Some(topic),
+            None => true,
+        });
+
+        address_matches && topics_match
+    }
+}
+
+/// Filter of a `volumes` subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumesFilter {
+    pub market_address: H256,
+    pub timeframe: Timeframe,
+}
+
+/// Parameters of `simp_subscribe`, depending on the [SubscriptionKind].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Params {
+    /// Market and timeframe of a `volumes` subscription.
+    Volumes(VolumesFilter),
+    /// Filter of a `logs` subscription.
+    Logs(LogsFilter),
+}
+
+/// Item sent to a subscriber.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SubscriptionItem {
+    Header(Box<Header>),
+    Log(Box<StoredLog>),
+    Volume(Box<VolumeCandle>),
+}
+
+/// Simp pubsub rpc interface, pushing the data indexed by the sync pipeline as it is committed.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "simp"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "simp"))]
+pub trait SimpPubSubApi {
+    /// Creates a subscription to the given topic.
+    ///
+    /// `logs` takes an optional [LogsFilter], `volumes` requires a [VolumesFilter].
+    #[subscription(
+        name = "subscribe" => "subscription",
+        unsubscribe = "unsubscribe",
+        item = SubscriptionItem
+    )]
+    async fn subscribe(
+        &self,
+        kind: SubscriptionKind,
+        params: Option<Params>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_subscription_params() {
+        let params: Params = serde_json::from_str(
+            r#"{"marketAddress":"0x0000000000000000000000000000000000000000000000000000000000000001","timeframe":"1h"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            params,
+            Params::Volumes(VolumesFilter {
+                market_address: H256::from_low_u64_be(1),
+                timeframe: Timeframe::Hourly
+            })
+        );
+
+        let params: Params = serde_json::from_str(
+            r#"{"address":["0x0000000000000000000000000000000000000002"],"topics":[null,"0x0000000000000000000000000000000000000000000000000000000000000003"]}"#,
+        )
+        .unwrap();
+        let filter = match params {
+            Params::Logs(filter) => filter,
+            _ => panic!("expected a logs filter"),
+        };
+
+        let mut log = StoredLog {
+            address: Address::from_low_u64_be(2),
+            topics: vec![H256::zero(), H256::from_low_u64_be(3)],
+            ..Default::default()
+        };
+        assert!(filter.matches(&log));
+
+        log.topics.pop();
+        assert!(!filter.matches(&log));
+        assert!(LogsFilter::default().matches(&log));
+    }
+}
@@ -0,0 +1,48 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use simp_primitives::{Market, Protocol, H256};
+
+/// Registry namespace rpc interface that gives access to the protocols and markets indexed by
+/// the node.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "registry"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "registry"))]
+pub trait RegistryApi {
+    /// Returns every protocol with its sync status, only the synced ones if `synced` is set.
+    #[method(name = "getProtocols")]
+    fn protocols(&self, synced: Option<bool>) -> RpcResult<Vec<Protocol>>;
+
+    /// Returns the protocol with the given id, `null` if it does not exist.
+    #[method(name = "getProtocol")]
+    fn protocol(&self, protocol_id: u64) -> RpcResult<Option<Protocol>>;
+
+    /// Returns the market at the given address with its input tokens, `null` if it does not
+    /// exist.
+    #[method(name = "getMarket")]
+    fn market(&self, market_address: H256) -> RpcResult<Option<Market>>;
+
+    /// Returns the address of every market the token is an input of.
+    #[method(name = "getTokenMarkets")]
+    fn token_markets(&self, token_address: H256) -> RpcResult<Vec<H256>>;
+}
+
+/// Registry admin rpc interface that registers and removes protocols and markets.
+///
+/// It writes to the database and is only served when explicitly selected.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "registry"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "registry"))]
+pub trait RegistryAdminApi {
+    /// Registers the factory of a protocol and returns the created protocol.
+    #[method(name = "createProtocol")]
+    fn create_protocol(&self, factory_address: H256) -> RpcResult<Protocol>;
+
+    /// Deletes a protocol, returns `false` if it did not exist.
+    #[method(name = "deleteProtocol")]
+    fn delete_protocol(&self, protocol_id: u64) -> RpcResult<bool>;
+
+    /// Registers a market of an existing protocol, returns `false` if the market already exists.
+    #[method(name = "createMarket")]
+    fn create_market(&self, market_address: H256, market: Market) -> RpcResult<bool>;
+
+    /// Deletes a market, returns `false` if it did not exist.
+    #[method(name = "deleteMarket")]
+    fn delete_market(&self, market_address: H256) -> RpcResult<bool>;
+}
@@ -0,0 +1,136 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
+use simp_primitives::{
+    serde_helper::u256_decimal, volumetric::address_balance::AddressBalance, Timeframe, Volumetric,
+    H256, U256,
+};
+
+/// Amount of a token moved in a market during a candle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenVolume {
+    /// Address of the token.
+    pub token: H256,
+    /// Amount moved, serialized as a decimal string.
+    #[serde(with = "u256_decimal")]
+    pub amount: U256,
+}
+
+impl From<AddressBalance> for TokenVolume {
+    fn from(balance: AddressBalance) -> Self {
+        TokenVolume { token: balance.address, amount: balance.balance }
+    }
+}
+
+/// Volumes of a market over one timeframe period, starting at `timestamp`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeCandle {
+    pub timestamp: u64,
+    pub market_address: H256,
+    pub swaps_out: Vec<TokenVolume>,
+    pub swaps_in: Vec<TokenVolume>,
+    pub withdrawal: Vec<TokenVolume>,
+    pub mint: Vec<TokenVolume>,
+    /// Amount of liquidity tokens transferred, serialized as a decimal string.
+    #[serde(with = "u256_decimal")]
+    pub transfer: U256,
+}
+
+impl From<Volumetric> for VolumeCandle {
+    fn from(volume: Volumetric) -> Self {
+        let into_tokens =
+            |balances: Vec<AddressBalance>| balances.into_iter().map(Into::into).collect();
+
+        VolumeCandle {
+            timestamp: volume.timestamp,
+            market_address: volume.market_address,
+            swaps_out: into_tokens(volume.swaps_out),
+            swaps_in: into_tokens(volume.swaps_in),
+            withdrawal: into_tokens(volume.withdrawal),
+            mint: into_tokens(volume.mint),
+            transfer: volume.transfer,
+        }
+    }
+}
+
+/// A page of results.
+///
+/// `next` is the cursor of the following page, `null` on the last page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<u64>,
+}
+
+/// Volumes namespace rpc interface that gives access to the market volumetrics timeseries.
+///
+#[doc = concat!("Timeframes are one of ", simp_primitives::timeframe_names!(), ".")]
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "volumes"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "volumes"))]
+pub trait VolumesApi {
+    /// Returns the candles of a market starting between `from` and `to`, both inclusive.
+    ///
+    /// `next` is the timestamp to pass as `from` to read the following page.
+    #[method(name = "getMarketVolumes")]
+    fn market_volumes(
+        &self,
+        market_address: H256,
+        timeframe: Timeframe,
+        from: u64,
+        to: u64,
+        limit: Option<usize>,
+    ) -> RpcResult<Page<VolumeCandle>>;
+
+    /// Returns the last candle of a market, `null` if the market has no volume.
+    #[method(name = "getLatestVolume")]
+    fn latest_volume(
+        &self,
+        market_address: H256,
+        timeframe: Timeframe,
+    ) -> RpcResult<Option<VolumeCandle>>;
+
+    /// Returns the candles of every market active in the period starting at `timestamp`.
+    ///
+    /// `next` is the offset to pass to read the following page.
+    #[method(name = "getVolumesAtTimestamp")]
+    fn volumes_at_timestamp(
+        &self,
+        timeframe: Timeframe,
+        timestamp: u64,
+        offset: Option<u64>,
+        limit: Option<usize>,
+    ) -> RpcResult<Page<VolumeCandle>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_registered_timeframes() {
+        let names =
+            Timeframe::ALL.iter().map(|timeframe| format!("`{timeframe}`")).collect::<Vec<_>>();
+
+        assert_eq!(simp_primitives::timeframe_names!(), names.join(", "));
+    }
+
+    #[test]
+    fn serialize_amounts_as_decimal_strings() {
+        let candle = VolumeCandle::from(Volumetric {
+            timestamp: 300,
+            swaps_in: vec![AddressBalance {
+                address: H256::zero(),
+                balance: U256::from(10u64).pow(U256::from(24u64)),
+            }],
+            transfer: U256::from(42u64),
+            ..Default::default()
+        });
+
+        let json = serde_json::to_value(&candle).unwrap();
+        assert_eq!(json["swapsIn"][0]["amount"], "1000000000000000000000000");
+        assert_eq!(json["transfer"], "42");
+
+        assert_eq!(serde_json::from_value::<VolumeCandle>(json).unwrap(), candle);
+        assert_eq!(serde_json::to_value(Timeframe::FiveMinute).unwrap(), "5m");
+    }
+}
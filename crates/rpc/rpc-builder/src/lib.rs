@@ -26,7 +26,14 @@ pub mod error;
 // Rpc server metrics
 mod metrics;
 
-use simp_rpc::{EthSubscriptionIdProvider, HelloApi};
+use simp_rpc::{
+    AdminApi, DataApi, EthSubscriptionIdProvider, HelloApi, RegistryAdminApi, RegistryApi,
+    SimpPubSub, VolumesApi,
+};
+use stages::{PipelineController, PipelineEventSubscriptions};
+use storage_provider::traits::{
+    BlockReader, MarketProvider, ProtocolProvider, StageCheckpointProvider, VolumetricReader,
+};
 
 pub use jsonrpsee::server::ServerBuilder;
 
@@ -244,32 +251,78 @@ impl RpcServerConfig {
 ///
 /// This is the main entrypoint and the easiest way to configure an RPC server.
 #[derive(Debug, Clone)]
-pub struct RpcModuleBuilder {}
+pub struct RpcModuleBuilder<Provider, Pipeline> {
+    /// The provider the modules read the indexed data from.
+    provider: Provider,
+    /// The pipeline running next to the server, its events are pushed to the subscriptions.
+    pipeline: Pipeline,
+}
 
 // === impl RpcBuilder ===
 
-pub struct BuildWithAuthServerResult(pub TransportRpcModules, pub SimpModuleRegistry);
+pub struct BuildWithAuthServerResult<Provider, Pipeline>(
+    pub TransportRpcModules,
+    pub SimpModuleRegistry<Provider, Pipeline>,
+);
 
-impl RpcModuleBuilder {
+impl<Provider, Pipeline> RpcModuleBuilder<Provider, Pipeline> {
     /// Create a new instance of the builder
-    pub fn new() -> Self {
-        Self { }
+    pub fn new(provider: Provider, pipeline: Pipeline) -> Self {
+        Self { provider, pipeline }
+    }
+
+    /// Configure the provider instance.
+    pub fn with_provider<P>(self, provider: P) -> RpcModuleBuilder<P, Pipeline>
+    where
+        P: BlockReader
+            + VolumetricReader
+            + MarketProvider
+            + ProtocolProvider
+            + StageCheckpointProvider
+            + Clone
+            + 'static,
+    {
+        let Self { pipeline, .. } = self;
+        RpcModuleBuilder { provider, pipeline }
     }
 
+    /// Configure the pipeline instance.
+    pub fn with_pipeline<P>(self, pipeline: P) -> RpcModuleBuilder<Provider, P>
+    where
+        P: PipelineEventSubscriptions + PipelineController + Clone + 'static,
+    {
+        let Self { provider, .. } = self;
+        RpcModuleBuilder { provider, pipeline }
+    }
+}
+
+impl<Provider, Pipeline> RpcModuleBuilder<Provider, Pipeline>
+where
+    Provider: BlockReader
+        + VolumetricReader
+        + MarketProvider
+        + ProtocolProvider
+        + StageCheckpointProvider
+        + Clone
+        + 'static,
+    Pipeline: PipelineEventSubscriptions + PipelineController + Clone + 'static,
+{
     pub fn build_with_auth_server (
         self,
         module_config: TransportRpcModuleConfig,
     ) -> (
         TransportRpcModules,
-        SimpModuleRegistry,
+        SimpModuleRegistry<Provider, Pipeline>,
     ) {
         let mut modules = TransportRpcModules::default();
 
-        let Self {} = self;
+        let Self { provider, pipeline } = self;
 
         let TransportRpcModuleConfig { http, ws, config } = module_config.clone();
 
         let mut registry = SimpModuleRegistry::new(
+            provider,
+            pipeline,
             config.unwrap_or_default(), 
         );
 
@@ -282,9 +335,9 @@ impl RpcModuleBuilder {
     }
 }
 
-impl Default for RpcModuleBuilder {
+impl Default for RpcModuleBuilder<(), ()> {
     fn default() -> Self {
-        RpcModuleBuilder::new()
+        RpcModuleBuilder::new((), ())
     }
 }
 
@@ -564,7 +617,22 @@ impl fmt::Debug for RpcServerHandle {
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "kebab-case")]
 pub enum SimpRpcModule {
+    /// `hello_` module
     Hello,
+    /// `simp_subscribe` module, pushing the data committed by the pipeline over WS
+    Pubsub,
+    /// `simp_` module, serving the indexed blocks, transactions and logs
+    Data,
+    /// `volumes_` module, serving the market volumetrics timeseries
+    Volumes,
+    /// `registry_` module, serving the protocols and markets
+    Registry,
+    /// `registry_` admin methods creating and deleting protocols and markets, never part of the
+    /// standard modules
+    RegistryAdmin,
+    /// `admin_` module, reporting and steering the sync pipeline, never part of the standard
+    /// modules
+    Admin,
 }
 
 // === impl SimpRpcModule ===
@@ -574,6 +642,11 @@ impl SimpRpcModule {
     pub const fn all_variants() -> &'static [&'static str] {
         Self::VARIANTS
     }
+
+    /// Returns true if the module can modify the state of the node.
+    pub const fn is_admin(&self) -> bool {
+        matches!(self, SimpRpcModule::RegistryAdmin | SimpRpcModule::Admin)
+    }
 }
 
 impl fmt::Display for SimpRpcModule {
@@ -594,7 +667,9 @@ impl Serialize for SimpRpcModule {
 
 /// A Helper type the holds instances of the configured modules.
 #[derive(Debug)]
-pub struct SimpModuleRegistry {
+pub struct SimpModuleRegistry<Provider, Pipeline> {
+    provider: Provider,
+    pipeline: Pipeline,
     /// Additional settings for handlers.
     _config: RpcModuleConfig,
     /// Contains the [Methods] of a module
@@ -603,12 +678,26 @@ pub struct SimpModuleRegistry {
 
 // === impl SimpModuleRegistry ===
 
-impl SimpModuleRegistry {
+impl<Provider, Pipeline> SimpModuleRegistry<Provider, Pipeline>
+where
+    Provider: BlockReader
+        + VolumetricReader
+        + MarketProvider
+        + ProtocolProvider
+        + StageCheckpointProvider
+        + Clone
+        + 'static,
+    Pipeline: PipelineEventSubscriptions + PipelineController + Clone + 'static,
+{
     /// Creates a new, empty instance.
     pub fn new(
+        provider: Provider,
+        pipeline: Pipeline,
         config: RpcModuleConfig,
     ) -> Self {
         Self {
+            provider,
+            pipeline,
             modules: Default::default(),
             _config: config,
         }
@@ -667,6 +756,28 @@ impl SimpModuleRegistry {
                         SimpRpcModule::Hello => {
                             HelloApi::new().into_rpc().into()
                         }
+                        SimpRpcModule::Pubsub => {
+                            SimpPubSub::new(self.provider.clone(), self.pipeline.clone())
+                                .into_rpc()
+                                .into()
+                        }
+                        SimpRpcModule::Data => {
+                            DataApi::new(self.provider.clone()).into_rpc().into()
+                        }
+                        SimpRpcModule::Volumes => {
+                            VolumesApi::new(self.provider.clone()).into_rpc().into()
+                        }
+                        SimpRpcModule::Registry => {
+                            RegistryApi::new(self.provider.clone()).into_rpc().into()
+                        }
+                        SimpRpcModule::RegistryAdmin => {
+                            RegistryAdminApi::new(self.provider.clone()).into_rpc().into()
+                        }
+                        SimpRpcModule::Admin => {
+                            AdminApi::new(self.provider.clone(), self.pipeline.clone())
+                                .into_rpc()
+                                .into()
+                        }
                     })
                     .clone()
             })
@@ -674,7 +785,17 @@ impl SimpModuleRegistry {
     }
 }
 
-impl SimpModuleRegistry {
+impl<Provider, Pipeline> SimpModuleRegistry<Provider, Pipeline>
+where
+    Provider: BlockReader
+        + VolumetricReader
+        + MarketProvider
+        + ProtocolProvider
+        + StageCheckpointProvider
+        + Clone
+        + 'static,
+    Pipeline: PipelineEventSubscriptions + PipelineController + Clone + 'static,
+{
     pub fn hello_api(&mut self) -> HelloApi {
         HelloApi::new()
     }
@@ -686,6 +807,90 @@ impl SimpModuleRegistry {
 
         self
     }
+
+    /// Returns an instance of [SimpPubSub]
+    pub fn pubsub_api(&self) -> SimpPubSub<Provider, Pipeline> {
+        SimpPubSub::new(self.provider.clone(), self.pipeline.clone())
+    }
+
+    /// Register the subscriptions of the Simp Namespace
+    pub fn register_pubsub(&mut self) -> &mut Self {
+        let pubsubapi = self.pubsub_api();
+
+        self.modules.insert(SimpRpcModule::Pubsub, pubsubapi.into_rpc().into());
+
+        self
+    }
+
+    /// Returns an instance of [DataApi]
+    pub fn data_api(&self) -> DataApi<Provider> {
+        DataApi::new(self.provider.clone())
+    }
+
+    /// Register Data Namespace
+    pub fn register_data(&mut self) -> &mut Self {
+        let dataapi = self.data_api();
+
+        self.modules.insert(SimpRpcModule::Data, dataapi.into_rpc().into());
+
+        self
+    }
+
+    /// Returns an instance of [VolumesApi]
+    pub fn volumes_api(&self) -> VolumesApi<Provider> {
+        VolumesApi::new(self.provider.clone())
+    }
+
+    /// Register Volumes Namespace
+    pub fn register_volumes(&mut self) -> &mut Self {
+        let volumesapi = self.volumes_api();
+
+        self.modules.insert(SimpRpcModule::Volumes, volumesapi.into_rpc().into());
+
+        self
+    }
+
+    /// Returns an instance of [RegistryApi]
+    pub fn registry_api(&self) -> RegistryApi<Provider> {
+        RegistryApi::new(self.provider.clone())
+    }
+
+    /// Register Registry Namespace
+    pub fn register_registry(&mut self) -> &mut Self {
+        let registryapi = self.registry_api();
+
+        self.modules.insert(SimpRpcModule::Registry, registryapi.into_rpc().into());
+
+        self
+    }
+
+    /// Returns an instance of [RegistryAdminApi]
+    pub fn registry_admin_api(&self) -> RegistryAdminApi<Provider> {
+        RegistryAdminApi::new(self.provider.clone())
+    }
+
+    /// Register the admin methods of the Registry Namespace
+    pub fn register_registry_admin(&mut self) -> &mut Self {
+        let registryadminapi = self.registry_admin_api();
+
+        self.modules.insert(SimpRpcModule::RegistryAdmin, registryadminapi.into_rpc().into());
+
+        self
+    }
+
+    /// Returns an instance of [AdminApi]
+    pub fn admin_api(&self) -> AdminApi<Provider, Pipeline> {
+        AdminApi::new(self.provider.clone(), self.pipeline.clone())
+    }
+
+    /// Register Admin Namespace
+    pub fn register_admin(&mut self) -> &mut Self {
+        let adminapi = self.admin_api();
+
+        self.modules.insert(SimpRpcModule::Admin, adminapi.into_rpc().into());
+
+        self
+    }
 }
 
 
@@ -853,9 +1058,9 @@ impl TransportRpcModules {
 /// ```
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
 pub enum RpcModuleSelection {
-    /// Use _all_ available modules.
+    /// Use _all_ available modules, except the admin ones.
     All,
-    /// The default modules `hello`
+    /// The default modules, see [RpcModuleSelection::STANDARD_MODULES]
     #[default]
     Standard,
     /// Only use the configured modules.
@@ -865,15 +1070,25 @@ pub enum RpcModuleSelection {
 // === impl RpcModuleSelection ===
 
 impl RpcModuleSelection {
-    /// The standard modules to instantiate by default `hello`
-    pub const STANDARD_MODULES: [SimpRpcModule; 1] =
-        [SimpRpcModule::Hello];
-
-    /// Returns a selection of [SimpRpcModule] with all [SimpRpcModule::VARIANTS].
+    /// The standard modules to instantiate by default `hello`, `simp`, `simp_subscribe`,
+    /// `volumes` and `registry`
+    pub const STANDARD_MODULES: [SimpRpcModule; 5] = [
+        SimpRpcModule::Hello,
+        SimpRpcModule::Data,
+        SimpRpcModule::Pubsub,
+        SimpRpcModule::Volumes,
+        SimpRpcModule::Registry,
+    ];
+
+    /// Returns a selection of [SimpRpcModule] with all [SimpRpcModule::VARIANTS], admin modules
+    /// have to be selected explicitly.
     pub fn all_modules() -> Vec<SimpRpcModule> {
         RpcModuleSelection::try_from_selection(SimpRpcModule::VARIANTS.iter().copied())
             .expect("valid selection")
             .into_selection()
+            .into_iter()
+            .filter(|module| !module.is_admin())
+            .collect()
     }
 
     /// Returns the [RpcModuleSelection::STANDARD_MODULES] as a selection.
@@ -1015,3 +1230,23 @@ impl fmt::Display for RpcModuleSelection {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn admin_modules_are_explicit() {
+        assert!(!RpcModuleSelection::All.iter_selection().any(|module| module.is_admin()));
+        assert!(!RpcModuleSelection::Standard.iter_selection().any(|module| module.is_admin()));
+
+        let selection: RpcModuleSelection = "registry, registry-admin, admin".parse().unwrap();
+        assert_eq!(
+            selection,
+            RpcModuleSelection::Selection(vec![
+                SimpRpcModule::Registry,
+                SimpRpcModule::RegistryAdmin,
+                SimpRpcModule::Admin
+            ])
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use jsonrpsee::core::RpcResult;
+use simp_primitives::{BlockNumber, StageId};
+use simp_rpc_api::{AdminApiServer, PipelineStatus, StageCheckpoint};
+use stages::{PipelineCommand, PipelineController};
+use storage_provider::traits::StageCheckpointProvider;
+use tracing::{info, trace};
+
+use crate::result::{internal_rpc_err, ToRpcResult};
+
+/// `admin` API implementation, reporting the checkpoints of the stages and steering the pipeline
+/// running next to the server.
+pub struct AdminApi<Provider, Pipeline> {
+    provider: Provider,
+    pipeline: Pipeline,
+}
+
+impl<Provider, Pipeline> AdminApi<Provider, Pipeline> {
+    pub fn new(provider: Provider, pipeline: Pipeline) -> Self {
+        AdminApi { provider, pipeline }
+    }
+}
+
+impl<Provider, Pipeline> AdminApi<Provider, Pipeline>
+where
+    Pipeline: PipelineController,
+{
+    fn send(&self, command: PipelineCommand) -> RpcResult<()> {
+        info!(target: "rpc::admin", ?command, "Sending pipeline command");
+        if self.pipeline.send_pipeline_command(command) {
+            Ok(())
+        } else {
+            Err(pipeline_not_running())
+        }
+    }
+}
+
+impl<Provider, Pipeline> AdminApiServer for AdminApi<Provider, Pipeline>
+where
+    Provider: StageCheckpointProvider + 'static,
+    Pipeline: PipelineController + 'static,
+{
+    fn stage_checkpoints(&self) -> RpcResult<Vec<StageCheckpoint>> {
+        trace!(target: "rpc::admin", "Serving admin_stageCheckpoints");
+        StageId::ALL
+            .into_iter()
+            .map(|stage_id| {
+                let checkpoint = self.provider.get_stage_checkpoint(stage_id).to_rpc_result()?;
+                Ok(StageCheckpoint { stage: stage_id.to_string(), checkpoint })
+            })
+            .collect()
+    }
+
+    fn pipeline_status(&self) -> RpcResult<PipelineStatus> {
+        trace!(target: "rpc::admin", "Serving admin_pipelineStatus");
+        let status = self.pipeline.pipeline_status().ok_or_else(pipeline_not_running)?;
+
+        Ok(PipelineStatus {
+            paused: status.paused,
+            max_block: status.max_block,
+            minimum_block_number: status.minimum_block_number,
+            maximum_block_number: status.maximum_block_number,
+        })
+    }
+
+    fn pause(&self) -> RpcResult<()> {
+        self.send(PipelineCommand::Pause)
+    }
+
+    fn resume(&self) -> RpcResult<()> {
+        self.send(PipelineCommand::Resume)
+    }
+
+    fn set_max_block(&self, max_block: Option<BlockNumber>) -> RpcResult<()> {
+        self.send(PipelineCommand::SetMaxBlock(max_block))
+    }
+
+    fn unwind(&self, block: BlockNumber) -> RpcResult<()> {
+        self.send(PipelineCommand::Unwind(block))
+    }
+}
+
+impl<Provider, Pipeline> std::fmt::Debug for AdminApi<Provider, Pipeline> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminApi").finish_non_exhaustive()
+    }
+}
+
+fn pipeline_not_running() -> jsonrpsee::core::Error {
+    internal_rpc_err("no pipeline is running next to the rpc server").into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::init_db;
+    use storage_provider::{options::AccessType, traits::StageCheckpointWriter, DatabaseProvider};
+
+    #[test]
+    fn reports_stage_checkpoints() {
+        let db = init_db(tempfile::TempDir::new().unwrap().into_path()).unwrap();
+        let provider = DatabaseProvider::new(db, AccessType::Primary);
+        provider.save_stage_checkpoint(StageId::Headers, 42).unwrap();
+
+        let api = AdminApi::new(provider, ());
+
+        let checkpoints = api.stage_checkpoints().unwrap();
+        assert_eq!(checkpoints.len(), StageId::ALL.len());
+        assert_eq!(
+            checkpoints[0],
+            StageCheckpoint { stage: StageId::Headers.to_string(), checkpoint: Some(42) }
+        );
+        assert_eq!(checkpoints[1].checkpoint, None);
+
+        // without a pipeline there is nothing to steer
+        assert!(api.pipeline_status().is_err());
+        assert!(api.pause().is_err());
+    }
+}
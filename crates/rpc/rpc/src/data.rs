@@ -0,0 +1,200 @@
+use jsonrpsee::core::RpcResult;
+use simp_primitives::{
+    Address, Block, BlockNumber, StoredOrDecodedLog, TransactionSigned, TxHash, H256,
+};
+use simp_rpc_api::{DataApiServer, Page};
+use storage_provider::traits::BlockReader;
+use tracing::trace;
+
+use crate::result::{invalid_params_rpc_err, ToRpcResult};
+
+/// Maximum number of blocks `simp_getLogsByBlockRange` reads in a single call, the one of the
+/// provider serving it.
+pub use storage_provider::traits::MAX_LOGS_BLOCK_RANGE;
+
+/// Number of logs returned by `simp_getLogsByAddress` when the request has no limit.
+pub const DEFAULT_LOGS_PAGE_SIZE: usize = 100;
+
+/// Maximum number of logs returned by `simp_getLogsByAddress` in a single page.
+pub const MAX_LOGS_PAGE_SIZE: usize = 1_000;
+
+/// `simp` API implementation, serving the data indexed by the sync pipeline.
+pub struct DataApi<Provider> {
+    provider: Provider,
+}
+
+impl<Provider> DataApi<Provider> {
+    pub fn new(provider: Provider) -> Self {
+        DataApi { provider }
+    }
+}
+
+impl<Provider> DataApiServer for DataApi<Provider>
+where
+    Provider: BlockReader + 'static,
+{
+    fn block_by_number(&self, number: BlockNumber) -> RpcResult<Option<Block>> {
+        trace!(target: "rpc::simp", number, "Serving simp_getBlockByNumber");
+        self.provider.block_by_number(number).to_rpc_result()
+    }
+
+    fn block_by_hash(&self, hash: H256) -> RpcResult<Option<Block>> {
+        trace!(target: "rpc::simp", ?hash, "Serving simp_getBlockByHash");
+        self.provider.block_by_hash(hash).to_rpc_result()
+    }
+
+    fn transaction_by_hash(&self, hash: TxHash) -> RpcResult<Option<TransactionSigned>> {
+        trace!(target: "rpc::simp", ?hash, "Serving simp_getTransactionByHash");
+        self.provider.transaction_by_hash(hash).to_rpc_result()
+    }
+
+    fn logs_by_transaction(
+        &self,
+        hash: TxHash,
+        decoded: Option<bool>,
+    ) -> RpcResult<Option<Vec<StoredOrDecodedLog>>> {
+        trace!(target: "rpc::simp", ?hash, ?decoded, "Serving simp_getLogsByTransaction");
+        self.provider.logs_by_tx_hash(hash, decoded.unwrap_or_default()).to_rpc_result()
+    }
+
+    fn logs_by_block_range(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        decoded: Option<bool>,
+    ) -> RpcResult<Vec<StoredOrDecodedLog>> {
+        trace!(target: "rpc::simp", from, to, ?decoded, "Serving simp_getLogsByBlockRange");
+        ensure_block_range(from, to)?;
+
+        self.provider.logs_by_block_range(from, to, decoded.unwrap_or_default()).to_rpc_result()
+    }
+
+    fn logs_by_address(
+        &self,
+        address: Address,
+        from: Option<BlockNumber>,
+        to: Option<BlockNumber>,
+        decoded: Option<bool>,
+        offset: Option<u64>,
+        limit: Option<usize>,
+    ) -> RpcResult<Page<StoredOrDecodedLog>> {
+        trace!(target: "rpc::simp", ?address, ?from, ?to, ?decoded, ?offset, "Serving simp_getLogsByAddress");
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(invalid_params_rpc_err(format!("invalid block range {from}..={to}")).into())
+            }
+        }
+
+        // one more log than the page tells whether there is a following page
+        let offset = offset.unwrap_or_default() as usize;
+        let limit = logs_page_size(limit);
+        let decoded = decoded.unwrap_or_default();
+        let mut logs = self
+            .provider
+            .logs_by_address_page(address, from, to, offset, limit + 1, decoded)
+            .to_rpc_result()?;
+
+        let next = (logs.len() > limit).then_some((offset + limit) as u64);
+        logs.truncate(limit);
+
+        Ok(Page { items: logs, next })
+    }
+}
+
+impl<Provider> std::fmt::Debug for DataApi<Provider> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataApi").finish_non_exhaustive()
+    }
+}
+
+/// Returns the number of logs of a page, capped to [MAX_LOGS_PAGE_SIZE].
+fn logs_page_size(limit: Option<usize>) -> usize {
+    limit.unwrap_or(DEFAULT_LOGS_PAGE_SIZE).clamp(1, MAX_LOGS_PAGE_SIZE)
+}
+
+/// Rejects reversed ranges and ranges spanning more than [MAX_LOGS_BLOCK_RANGE] blocks.
+fn ensure_block_range(from: BlockNumber, to: BlockNumber) -> RpcResult<()> {
+    if from > to {
+        return Err(invalid_params_rpc_err(format!("invalid block range {from}..={to}")).into())
+    }
+    if to - from >= MAX_LOGS_BLOCK_RANGE {
+        return Err(invalid_params_rpc_err(format!(
+            "block range {from}..={to} exceeds the maximum of {MAX_LOGS_BLOCK_RANGE} blocks"
+        ))
+        .into())
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::init_db;
+    use simp_primitives::{Header, Log};
+    use storage_provider::{
+        options::AccessType,
+        traits::{BlockHashWriter, BlockNumWriter, HeaderWriter, LogsWriter},
+        DatabaseProvider,
+    };
+
+    #[test]
+    fn serves_indexed_blocks() {
+        let db = init_db(tempfile::TempDir::new().unwrap().into_path()).unwrap();
+        let provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        let hash = H256::from_low_u64_be(1);
+        let header = Header { number: 1, hash, ..Default::default() };
+        provider.insert_header(&1, header.clone()).unwrap();
+        provider.insert_block_hash(1, hash).unwrap();
+        provider.insert_block_number(hash, 1).unwrap();
+
+        let api = DataApi::new(provider);
+
+        assert_eq!(api.block_by_number(1).unwrap().unwrap().header, header);
+        assert_eq!(api.block_by_hash(hash).unwrap().unwrap().header, header);
+        assert!(api.block_by_number(2).unwrap().is_none());
+        assert!(api.transaction_by_hash(H256::zero()).unwrap().is_none());
+    }
+
+    #[test]
+    fn serves_wide_log_ranges() {
+        let db = init_db(tempfile::TempDir::new().unwrap().into_path()).unwrap();
+        let api = DataApi::new(DatabaseProvider::new(db, AccessType::Primary));
+
+        assert!(api.logs_by_block_range(0, MAX_LOGS_BLOCK_RANGE - 1, None).unwrap().is_empty());
+        assert!(api.logs_by_block_range(1, MAX_LOGS_BLOCK_RANGE, Some(true)).unwrap().is_empty());
+        assert!(api.logs_by_block_range(0, MAX_LOGS_BLOCK_RANGE, None).is_err());
+    }
+
+    #[test]
+    fn pages_logs_by_address() {
+        let db = init_db(tempfile::TempDir::new().unwrap().into_path()).unwrap();
+        let provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        let address = Address::repeat_byte(0xaa);
+        let log = |block_number| Log { address, block_number, ..Default::default() };
+        provider
+            .insert_logs(vec![(1, vec![log(1), log(1), log(1)]), (2, vec![log(2), log(2)])])
+            .unwrap();
+
+        let api = DataApi::new(provider);
+
+        let page = api.logs_by_address(address, None, None, None, None, Some(2)).unwrap();
+        assert_eq!((page.items.len(), page.next), (2, Some(2)));
+
+        let page = api.logs_by_address(address, None, None, None, Some(4), Some(2)).unwrap();
+        assert_eq!((page.items.len(), page.next), (1, None));
+
+        let page = api.logs_by_address(address, Some(2), Some(2), None, None, None).unwrap();
+        assert_eq!((page.items.len(), page.next), (2, None));
+    }
+
+    #[test]
+    fn rejects_invalid_block_ranges() {
+        assert!(ensure_block_range(10, 10).is_ok());
+        assert!(ensure_block_range(11, 10).is_err());
+        assert!(ensure_block_range(0, MAX_LOGS_BLOCK_RANGE).is_err());
+        assert!(ensure_block_range(1, MAX_LOGS_BLOCK_RANGE).is_ok());
+    }
+}
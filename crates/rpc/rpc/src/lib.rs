@@ -1,6 +1,17 @@
+mod admin;
+mod data;
 mod hello;
+mod pubsub;
+mod registry;
+mod result;
+mod volumes;
 
 pub mod eth;
 
+pub use admin::AdminApi;
+pub use data::{DataApi, MAX_LOGS_BLOCK_RANGE};
 pub use hello::HelloApi;
-pub use eth::EthSubscriptionIdProvider;
\ No newline at end of file
+pub use pubsub::SimpPubSub;
+pub use registry::{RegistryAdminApi, RegistryApi};
+pub use volumes::{VolumesApi, DEFAULT_VOLUMES_PAGE_SIZE, MAX_VOLUMES_PAGE_SIZE};
+pub use eth::EthSubscriptionIdProvider;
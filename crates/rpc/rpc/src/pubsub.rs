@@ -0,0 +1,241 @@
+use std::ops::RangeInclusive;
+
+use jsonrpsee::{
+    core::SubscriptionResult, PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink,
+};
+use simp_primitives::{BlockNumber, StageId, StoredOrDecodedLog};
+use simp_rpc_api::{
+    LogsFilter, Params, SimpPubSubApiServer, SubscriptionItem, SubscriptionKind, VolumesFilter,
+};
+use stages::{PipelineEvent, PipelineEventSubscriptions};
+use storage_provider::traits::{BlockReader, VolumetricReader};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tracing::{trace, warn};
+
+use crate::result::invalid_params_rpc_err;
+
+/// `simp_subscribe` implementation, pushing the data committed by the stages of the pipeline.
+pub struct SimpPubSub<Provider, Events> {
+    provider: Provider,
+    events: Events,
+}
+
+impl<Provider, Events> SimpPubSub<Provider, Events> {
+    pub fn new(provider: Provider, events: Events) -> Self {
+        SimpPubSub { provider, events }
+    }
+}
+
+#[async_trait::async_trait]
+impl<Provider, Events> SimpPubSubApiServer for SimpPubSub<Provider, Events>
+where
+    Provider: BlockReader + VolumetricReader + 'static,
+    Events: PipelineEventSubscriptions + 'static,
+{
+    async fn subscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        kind: SubscriptionKind,
+        params: Option<Params>,
+    ) -> SubscriptionResult {
+        trace!(target: "rpc::pubsub", ?kind, ?params, "Serving simp_subscribe");
+        let filter = match (kind, params) {
+            (SubscriptionKind::NewHeads, None) => Filter::NewHeads,
+            (SubscriptionKind::Logs, None) => Filter::Logs(LogsFilter::default()),
+            (SubscriptionKind::Logs, Some(Params::Logs(filter))) => Filter::Logs(filter),
+            (SubscriptionKind::Volumes, Some(Params::Volumes(filter))) => Filter::Volumes(filter),
+            (kind, _) => {
+                pending.reject(invalid_params_rpc_err(format!("invalid params for {kind:?}"))).await;
+                return Ok(())
+            }
+        };
+
+        // listen before accepting, so nothing committed in between is missed
+        let events = self.events.subscribe_to_pipeline_events();
+        let sink = pending.accept().await?;
+
+        self.pipe(sink, events, filter).await
+    }
+}
+
+impl<Provider, Events> SimpPubSub<Provider, Events>
+where
+    Provider: BlockReader + VolumetricReader,
+{
+    /// Sends the items of the blocks committed by the stage of the filter until the subscriber
+    /// leaves or the pipeline stops.
+    async fn pipe(
+        &self,
+        sink: SubscriptionSink,
+        mut events: Receiver<PipelineEvent>,
+        filter: Filter,
+    ) -> SubscriptionResult {
+        let stage = filter.stage();
+        // checkpoint of the stage before its current run
+        let mut checkpoint = None;
+
+        loop {
+            let event = tokio::select! {
+                _ = sink.closed() => break,
+                event = events.recv() => event,
+            };
+
+            match event {
+                Ok(PipelineEvent::Running { stage_id, checkpoint: previous, .. })
+                    if stage_id == stage =>
+                {
+                    checkpoint = previous;
+                }
+                Ok(PipelineEvent::Ran { stage_id, result, .. }) if stage_id == stage => {
+                    let from = checkpoint.map_or(0, |checkpoint| checkpoint + 1);
+                    checkpoint = Some(result.checkpoint);
+
+                    for item in self.items(&filter, from..=result.checkpoint)? {
+                        let message = SubscriptionMessage::from_json(&item)?;
+                        if sink.send(message).await.is_err() {
+                            return Ok(())
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(target: "rpc::pubsub", skipped, "Subscription lagged behind the pipeline events");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the items of the committed blocks matching the filter.
+    fn items(
+        &self,
+        filter: &Filter,
+        blocks: RangeInclusive<BlockNumber>,
+    ) -> interfaces::Result<Vec<SubscriptionItem>> {
+        let mut items = Vec::new();
+
+        match filter {
+            Filter::NewHeads => {
+                for number in blocks {
+                    if let Some(header) = self.provider.header_by_number(number)? {
+                        items.push(SubscriptionItem::Header(Box::new(header)));
+                    }
+                }
+            }
+            Filter::Logs(filter) => {
+                for number in blocks {
+                    let logs = self.provider.logs_by_block(number.into(), false)?.unwrap_or_default();
+
+                    items.extend(logs.into_iter().filter_map(|log| match log {
+                        StoredOrDecodedLog::Raw(log) if filter.matches(&log) => {
+                            Some(SubscriptionItem::Log(Box::new(log)))
+                        }
+                        _ => None,
+                    }));
+                }
+            }
+            Filter::Volumes(VolumesFilter { market_address, timeframe }) => {
+                // the period of the block before the range was still open
+                let previous = self.provider.header_by_number(blocks.start().saturating_sub(1))?;
+                let last = self.provider.header_by_number(*blocks.end())?;
+
+                if let (Some(previous), Some(last)) = (previous, last) {
+                    let open = timeframe.period_start(previous.timestamp);
+                    let current = timeframe.period_start(last.timestamp);
+
+                    if open < current {
+                        let volumes = self.provider.get_market_range(
+                            *market_address,
+                            *timeframe,
+                            Some(open),
+                            Some(current - 1),
+                        )?;
+                        items.extend(
+                            volumes
+                                .into_iter()
+                                .map(|volume| SubscriptionItem::Volume(Box::new(volume.into()))),
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(items)
+    }
+}
+
+impl<Provider, Events> std::fmt::Debug for SimpPubSub<Provider, Events> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimpPubSub").finish_non_exhaustive()
+    }
+}
+
+/// A validated subscription.
+#[derive(Debug, Clone)]
+enum Filter {
+    NewHeads,
+    Logs(LogsFilter),
+    Volumes(VolumesFilter),
+}
+
+impl Filter {
+    /// The stage whose commits produce the items of the subscription.
+    ///
+    /// Candles are aggregated by the snapshots indexing stage, a candle is closed once a block of
+    /// a later period is indexed.
+    fn stage(&self) -> StageId {
+        match self {
+            Filter::NewHeads => StageId::Headers,
+            Filter::Logs(_) => StageId::BlockIndexing,
+            Filter::Volumes(_) => StageId::SnapshotsIndexing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::init_db;
+    use simp_primitives::{Header, Timeframe, Volumetric, H256};
+    use storage_provider::{
+        options::AccessType,
+        traits::{HeaderWriter, VolumetricWriter},
+        DatabaseProvider,
+    };
+
+    #[test]
+    fn committed_items() {
+        let db = init_db(tempfile::TempDir::new().unwrap().into_path()).unwrap();
+        let provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        // one block every two minutes
+        for number in 0..10 {
+            provider
+                .insert_header(&number, Header { number, timestamp: number * 120, ..Default::default() })
+                .unwrap();
+        }
+
+        let market_address = H256::from_low_u64_be(1);
+        let volumes = (0..4)
+            .map(|period| Volumetric { timestamp: period * 300, market_address, ..Default::default() })
+            .collect();
+        provider.add_market_volumes(volumes, Timeframe::FiveMinute).unwrap();
+
+        let pubsub = SimpPubSub::new(provider, ());
+
+        let heads = pubsub.items(&Filter::NewHeads, 3..=5).unwrap();
+        assert_eq!(heads.len(), 3);
+        assert!(matches!(&heads[0], SubscriptionItem::Header(header) if header.number == 3));
+
+        // blocks 4 and 5 are at 480s and 600s, closing the period starting at 300s
+        let filter = Filter::Volumes(VolumesFilter { market_address, timeframe: Timeframe::FiveMinute });
+        let candles = pubsub.items(&filter, 4..=5).unwrap();
+        assert_eq!(candles.len(), 1);
+        assert!(matches!(&candles[0], SubscriptionItem::Volume(candle) if candle.timestamp == 300));
+
+        // block 6 at 720s is still in the period starting at 600s
+        assert!(pubsub.items(&filter, 6..=6).unwrap().is_empty());
+    }
+}
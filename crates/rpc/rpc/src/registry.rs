@@ -0,0 +1,178 @@
+use jsonrpsee::core::RpcResult;
+use simp_primitives::{Market, Protocol, H256};
+use simp_rpc_api::{RegistryAdminApiServer, RegistryApiServer};
+use storage_provider::traits::{MarketProvider, ProtocolProvider};
+use tracing::{info, trace};
+
+use crate::result::{internal_rpc_err, invalid_params_rpc_err, ToRpcResult};
+
+/// `registry` API implementation, serving the protocols and markets of the database.
+pub struct RegistryApi<Provider> {
+    provider: Provider,
+}
+
+impl<Provider> RegistryApi<Provider> {
+    pub fn new(provider: Provider) -> Self {
+        RegistryApi { provider }
+    }
+}
+
+impl<Provider> RegistryApiServer for RegistryApi<Provider>
+where
+    Provider: MarketProvider + ProtocolProvider + 'static,
+{
+    fn protocols(&self, synced: Option<bool>) -> RpcResult<Vec<Protocol>> {
+        trace!(target: "rpc::registry", ?synced, "Serving registry_getProtocols");
+        if synced.unwrap_or_default() {
+            self.provider.get_all_synced_protocols().to_rpc_result()
+        } else {
+            self.provider.get_all_protocols().to_rpc_result()
+        }
+    }
+
+    fn protocol(&self, protocol_id: u64) -> RpcResult<Option<Protocol>> {
+        trace!(target: "rpc::registry", protocol_id, "Serving registry_getProtocol");
+        self.provider.get_protocol(protocol_id).to_rpc_result()
+    }
+
+    fn market(&self, market_address: H256) -> RpcResult<Option<Market>> {
+        trace!(target: "rpc::registry", ?market_address, "Serving registry_getMarket");
+        self.provider.get_market(market_address).to_rpc_result()
+    }
+
+    fn token_markets(&self, token_address: H256) -> RpcResult<Vec<H256>> {
+        trace!(target: "rpc::registry", ?token_address, "Serving registry_getTokenMarkets");
+        let markets = self.provider.get_token_markets(token_address).to_rpc_result()?;
+
+        Ok(markets.map(|markets| markets.market_addresses).unwrap_or_default())
+    }
+}
+
+impl<Provider> std::fmt::Debug for RegistryApi<Provider> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistryApi").finish_non_exhaustive()
+    }
+}
+
+/// `registry` admin API implementation, registering protocols and markets.
+pub struct RegistryAdminApi<Provider> {
+    provider: Provider,
+}
+
+impl<Provider> RegistryAdminApi<Provider> {
+    pub fn new(provider: Provider) -> Self {
+        RegistryAdminApi { provider }
+    }
+}
+
+impl<Provider> RegistryAdminApiServer for RegistryAdminApi<Provider>
+where
+    Provider: MarketProvider + ProtocolProvider + 'static,
+{
+    fn create_protocol(&self, factory_address: H256) -> RpcResult<Protocol> {
+        let protocols = self.provider.get_all_protocols().to_rpc_result()?;
+        if protocols.iter().any(|protocol| protocol.factory_address == factory_address) {
+            return Err(invalid_params_rpc_err(format!(
+                "a protocol with factory {factory_address:?} already exists"
+            ))
+            .into())
+        }
+
+        let protocol_id = self.provider.create_protocol(factory_address).to_rpc_result()?;
+        info!(target: "rpc::registry", protocol_id, ?factory_address, "Created protocol");
+
+        self.provider
+            .get_protocol(protocol_id)
+            .to_rpc_result()?
+            .ok_or_else(|| internal_rpc_err(format!("protocol {protocol_id} was not created")).into())
+    }
+
+    fn delete_protocol(&self, protocol_id: u64) -> RpcResult<bool> {
+        if self.provider.get_protocol(protocol_id).to_rpc_result()?.is_none() {
+            return Ok(false)
+        }
+
+        self.provider.delete_protocol(protocol_id).to_rpc_result()?;
+        info!(target: "rpc::registry", protocol_id, "Deleted protocol");
+
+        Ok(true)
+    }
+
+    fn create_market(&self, market_address: H256, market: Market) -> RpcResult<bool> {
+        if self.provider.get_protocol(market.protocol_id).to_rpc_result()?.is_none() {
+            return Err(invalid_params_rpc_err(format!(
+                "protocol {} does not exist",
+                market.protocol_id
+            ))
+            .into())
+        }
+        if self.provider.get_market(market_address).to_rpc_result()?.is_some() {
+            return Ok(false)
+        }
+
+        self.provider.create_market(market, market_address).to_rpc_result()?;
+        info!(target: "rpc::registry", ?market_address, "Created market");
+
+        Ok(true)
+    }
+
+    fn delete_market(&self, market_address: H256) -> RpcResult<bool> {
+        if self.provider.get_market(market_address).to_rpc_result()?.is_none() {
+            return Ok(false)
+        }
+
+        self.provider.delete_market(market_address).to_rpc_result()?;
+        info!(target: "rpc::registry", ?market_address, "Deleted market");
+
+        Ok(true)
+    }
+}
+
+impl<Provider> std::fmt::Debug for RegistryAdminApi<Provider> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistryAdminApi").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::init_db;
+    use std::sync::Arc;
+    use storage_provider::{options::AccessType, DatabaseProvider};
+
+    #[test]
+    fn register_protocols_and_markets() {
+        let db = init_db(tempfile::TempDir::new().unwrap().into_path()).unwrap();
+        let provider = Arc::new(DatabaseProvider::new(db, AccessType::Primary));
+        let registry = RegistryApi::new(provider.clone());
+        let admin = RegistryAdminApi::new(provider);
+
+        let factory_address = H256::from_low_u64_be(1);
+        let protocol = admin.create_protocol(factory_address).unwrap();
+        assert_eq!(protocol.factory_address, factory_address);
+        assert!(admin.create_protocol(factory_address).is_err());
+        assert_eq!(registry.protocols(None).unwrap(), vec![protocol]);
+        assert!(registry.protocols(Some(true)).unwrap().is_empty());
+
+        let token = H256::from_low_u64_be(2);
+        let market_address = H256::from_low_u64_be(3);
+        let market = Market { protocol_id: protocol.protocol_id, input_tokens: vec![token] };
+
+        assert!(admin.create_market(market_address, market.clone()).unwrap());
+        assert!(!admin.create_market(market_address, market.clone()).unwrap());
+        assert!(admin
+            .create_market(H256::from_low_u64_be(4), Market { protocol_id: 42, ..market.clone() })
+            .is_err());
+
+        assert_eq!(registry.market(market_address).unwrap(), Some(market));
+        assert_eq!(registry.token_markets(token).unwrap(), vec![market_address]);
+
+        assert!(admin.delete_market(market_address).unwrap());
+        assert!(!admin.delete_market(market_address).unwrap());
+        assert!(registry.token_markets(token).unwrap().is_empty());
+
+        assert!(admin.delete_protocol(protocol.protocol_id).unwrap());
+        assert!(registry.protocol(protocol.protocol_id).unwrap().is_none());
+    }
+}
@@ -0,0 +1,36 @@
+//! Additional helpers for converting errors.
+
+use jsonrpsee::{
+    core::RpcResult,
+    types::error::{ErrorObject, INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+};
+
+/// Helper trait to easily convert various `Result` types into [`RpcResult`]
+pub(crate) trait ToRpcResult<Ok> {
+    /// Converts the error of the result into an internal rpc error.
+    fn to_rpc_result(self) -> RpcResult<Ok>;
+}
+
+impl<Ok> ToRpcResult<Ok> for interfaces::Result<Ok> {
+    fn to_rpc_result(self) -> RpcResult<Ok> {
+        self.map_err(|err| match err {
+            interfaces::Error::RangeTooLarge { .. } => invalid_params_rpc_err(err.to_string()).into(),
+            err => internal_rpc_err(err.to_string()).into(),
+        })
+    }
+}
+
+/// Constructs an invalid params JSON-RPC error.
+pub(crate) fn invalid_params_rpc_err(msg: impl Into<String>) -> ErrorObject<'static> {
+    rpc_err(INVALID_PARAMS_CODE, msg)
+}
+
+/// Constructs an internal JSON-RPC error.
+pub(crate) fn internal_rpc_err(msg: impl Into<String>) -> ErrorObject<'static> {
+    rpc_err(INTERNAL_ERROR_CODE, msg)
+}
+
+/// Constructs a JSON-RPC error, consisting of `code` and a `message`.
+pub(crate) fn rpc_err(code: i32, msg: impl Into<String>) -> ErrorObject<'static> {
+    ErrorObject::owned(code, msg.into(), None::<()>)
+}
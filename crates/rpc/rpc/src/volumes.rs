@@ -0,0 +1,140 @@
+use jsonrpsee::core::RpcResult;
+use simp_primitives::{Timeframe, H256};
+use simp_rpc_api::{Page, VolumeCandle, VolumesApiServer};
+use storage_provider::traits::VolumetricReader;
+use tracing::trace;
+
+use crate::result::{invalid_params_rpc_err, ToRpcResult};
+
+/// Number of candles returned when the request has no limit.
+pub const DEFAULT_VOLUMES_PAGE_SIZE: usize = 100;
+
+/// Maximum number of candles returned in a single page.
+pub const MAX_VOLUMES_PAGE_SIZE: usize = 1_000;
+
+/// `volumes` API implementation, serving the candles aggregated by the volumetric stages.
+pub struct VolumesApi<Provider> {
+    provider: Provider,
+}
+
+impl<Provider> VolumesApi<Provider> {
+    pub fn new(provider: Provider) -> Self {
+        VolumesApi { provider }
+    }
+}
+
+impl<Provider> VolumesApiServer for VolumesApi<Provider>
+where
+    Provider: VolumetricReader + 'static,
+{
+    fn market_volumes(
+        &self,
+        market_address: H256,
+        timeframe: Timeframe,
+        from: u64,
+        to: u64,
+        limit: Option<usize>,
+    ) -> RpcResult<Page<VolumeCandle>> {
+        trace!(target: "rpc::volumes", ?market_address, %timeframe, from, to, "Serving volumes_getMarketVolumes");
+        if from > to {
+            return Err(invalid_params_rpc_err(format!("invalid timestamp range {from}..={to}")).into())
+        }
+
+        // one more candle than the page tells where the next page starts
+        let limit = page_size(limit);
+        let mut volumes = self
+            .provider
+            .get_market_range_page(market_address, timeframe, from, to, limit + 1)
+            .to_rpc_result()?;
+
+        let next = volumes.get(limit).map(|volume| volume.timestamp);
+        volumes.truncate(limit);
+
+        Ok(Page { items: volumes.into_iter().map(Into::into).collect(), next })
+    }
+
+    fn latest_volume(
+        &self,
+        market_address: H256,
+        timeframe: Timeframe,
+    ) -> RpcResult<Option<VolumeCandle>> {
+        trace!(target: "rpc::volumes", ?market_address, %timeframe, "Serving volumes_getLatestVolume");
+        let volume =
+            self.provider.get_latest_market_volume(market_address, timeframe).to_rpc_result()?;
+
+        Ok(volume.map(Into::into))
+    }
+
+    fn volumes_at_timestamp(
+        &self,
+        timeframe: Timeframe,
+        timestamp: u64,
+        offset: Option<u64>,
+        limit: Option<usize>,
+    ) -> RpcResult<Page<VolumeCandle>> {
+        trace!(target: "rpc::volumes", %timeframe, timestamp, ?offset, "Serving volumes_getVolumesAtTimestamp");
+        let offset = offset.unwrap_or_default() as usize;
+        let limit = page_size(limit);
+        let mut volumes = self
+            .provider
+            .get_by_timestamp_page(timeframe, timestamp, offset, limit + 1)
+            .to_rpc_result()?;
+
+        let next = (volumes.len() > limit).then_some((offset + limit) as u64);
+        volumes.truncate(limit);
+
+        Ok(Page { items: volumes.into_iter().map(Into::into).collect(), next })
+    }
+}
+
+impl<Provider> std::fmt::Debug for VolumesApi<Provider> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VolumesApi").finish_non_exhaustive()
+    }
+}
+
+/// Returns the number of candles of a page, capped to [MAX_VOLUMES_PAGE_SIZE].
+fn page_size(limit: Option<usize>) -> usize {
+    limit.unwrap_or(DEFAULT_VOLUMES_PAGE_SIZE).clamp(1, MAX_VOLUMES_PAGE_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::init_db;
+    use simp_primitives::Volumetric;
+    use storage_provider::{options::AccessType, traits::VolumetricWriter, DatabaseProvider};
+
+    #[test]
+    fn paginates_market_volumes() {
+        let db = init_db(tempfile::TempDir::new().unwrap().into_path()).unwrap();
+        let provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        let market_address = H256::from_low_u64_be(1);
+        let volumes = (1..=5)
+            .map(|period| Volumetric { timestamp: period * 300, market_address, ..Default::default() })
+            .collect();
+        provider.add_market_volumes(volumes, Timeframe::FiveMinute).unwrap();
+
+        let api = VolumesApi::new(provider);
+
+        let page = api.market_volumes(market_address, Timeframe::FiveMinute, 300, 1500, Some(2)).unwrap();
+        assert_eq!(page.items.iter().map(|candle| candle.timestamp).collect::<Vec<_>>(), [300, 600]);
+        assert_eq!(page.next, Some(900));
+
+        let page = api.market_volumes(market_address, Timeframe::FiveMinute, 900, 1500, Some(5)).unwrap();
+        assert_eq!(page.items.len(), 3);
+        assert_eq!(page.next, None);
+
+        assert!(api.market_volumes(market_address, Timeframe::Hourly, 0, 1500, None).unwrap().items.is_empty());
+        assert!(api.market_volumes(market_address, Timeframe::FiveMinute, 1500, 300, None).is_err());
+
+        let latest = api.latest_volume(market_address, Timeframe::FiveMinute).unwrap().unwrap();
+        assert_eq!(latest.timestamp, 1500);
+
+        let page = api.volumes_at_timestamp(Timeframe::FiveMinute, 600, None, None).unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.next, None);
+        assert!(api.volumes_at_timestamp(Timeframe::FiveMinute, 600, Some(1), None).unwrap().items.is_empty());
+    }
+}
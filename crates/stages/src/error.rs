@@ -1,3 +1,5 @@
+use interfaces::db::DatabaseError;
+use simp_primitives::{BlockNumber, StageId};
 use std::sync::mpsc::SendError;
 use thiserror::Error;
 
@@ -11,9 +13,17 @@ pub enum StageError {
         /// The block that caused the error.
         block: u64,
     },
-    // /// The stage encountered a database error.
-    // #[error("An internal database error occurred: {0}")]
-    // Database(#[from] DatabaseError),
+    /// The stage found that the stored chain diverged from the canonical one.
+    #[error("Chain reorganisation detected at block {bad_block}, common ancestor is block {fork_block}")]
+    Reorg {
+        /// The last block shared by the stored chain and the canonical one.
+        fork_block: BlockNumber,
+        /// The first stored block that is no longer canonical.
+        bad_block: BlockNumber,
+    },
+    /// The stage encountered a database error.
+    #[error("An internal database error occurred: {0}")]
+    Database(#[from] DatabaseError),
     /// Invalid checkpoint passed to the stage
     #[error("Invalid stage checkpoint: {0}")]
     StageCheckpoint(u64),
@@ -43,16 +53,27 @@ pub enum StageError {
     Fatal(Box<dyn std::error::Error + Send + Sync>),
 }
 
-// TODO: update
 impl StageError {
     /// If the error is fatal the pipeline will stop.
+    ///
+    /// Database errors are fatal unless the failed transaction can be retried.
     pub fn is_fatal(&self) -> bool {
-        matches!(
-            self,
-                StageError::StageCheckpoint(_) |
-                StageError::ChannelClosed |
-                StageError::Fatal(_)
-        )
+        match self {
+            StageError::Database(err) => !err.is_recoverable(),
+            StageError::StageCheckpoint(_) |
+            StageError::ChannelClosed |
+            StageError::Fatal(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<interfaces::Error> for StageError {
+    fn from(err: interfaces::Error) -> Self {
+        match err {
+            interfaces::Error::Database(err) => StageError::Database(err),
+            err => StageError::Fatal(Box::new(err)),
+        }
     }
 }
 
@@ -65,9 +86,19 @@ pub enum PipelineError {
     /// The pipeline encountered a database error.
     // #[error("A database error occurred.")]
     // Database(#[from] DbError),
-    /// The pipeline encountered an irrecoverable error in one of the stages.
-    // #[error("An interface error occurred.")]
-    // Interface(#[from] RethError),
+    /// The pipeline failed to read or write the stage checkpoints.
+    #[error("An interface error occurred: {0}")]
+    Interface(#[from] interfaces::Error),
+    /// A block kept failing a stage after the stages were unwound below it and synced again.
+    #[error("Block {block} failed stage {stage_id} {attempts} times in a row")]
+    BadBlock {
+        /// The stage the block failed.
+        stage_id: StageId,
+        /// The failing block.
+        block: BlockNumber,
+        /// Number of times the block failed.
+        attempts: usize,
+    },
     /// The pipeline encountered an error while trying to send an event.
     #[error("The pipeline encountered an error while trying to send an event.")]
     Channel(#[from] SendError<String>),
@@ -1,12 +1,17 @@
 mod pipeline;
+pub use pipeline::{
+    ControlFlow, Pipeline, PipelineBuilder, PipelineCommand, PipelineController, PipelineEvent,
+    PipelineEventSubscriptions, PipelineHandle, PipelineStagesProgress, PipelineStatus,
+    DEFAULT_BATCH_SIZE, MAX_BAD_BLOCK_ATTEMPTS, PIPELINE_EVENTS_CAPACITY,
+};
 
 mod error;
 pub use error::PipelineError;
-mod stages;
+pub mod stages;
 
 mod stage;
-pub use stage::Stage;
+pub use stage::{ExecInput, ExecOutput, Stage, UnwindInput, UnwindOutput};
 
 pub mod util;
 
-pub mod test_utils;
\ No newline at end of file
+pub mod test_utils;
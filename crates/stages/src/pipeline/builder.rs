@@ -4,17 +4,24 @@
 
 // includes the db?
 
-use simp_primitives::{StageId, BlockNumber,ChainSpec};
+use std::sync::Arc;
+
+use simp_primitives::{StageId, BlockNumber, ChainSpec, NewHeadsStream};
 use storage_provider::DatabaseProvider;
+use tokio::sync::{mpsc, watch};
 
 use crate::{stage::BoxedStage, Stage};
 
-use super::Pipeline;
+use super::{Pipeline, PipelineStatus, DEFAULT_BATCH_SIZE};
 
 pub struct PipelineBuilder {
     stages: Vec<BoxedStage>,
 
     max_block: Option<BlockNumber>,
+
+    batch_size: u64,
+
+    new_heads: Option<NewHeadsStream>,
 }
 
 impl PipelineBuilder {
@@ -30,18 +37,47 @@ impl PipelineBuilder {
         self.max_block = Some(block);
         self
     }
-    pub fn build(self, db: DatabaseProvider, chain_spec: ChainSpec) -> Pipeline {
+
+    /// Sets the number of blocks a stage executes at once, the commands sent through the
+    /// [PipelineHandle](super::PipelineHandle)s are applied between batches.
+    pub fn with_batch_size(mut self, blocks: u64) -> Self {
+        self.batch_size = blocks.max(1);
+        self
+    }
+
+    /// Follows the tip of the chain with the given headers, see
+    /// [ChainRpcProvider::subscribe_block][simp_primitives::ChainRpcProvider::subscribe_block].
+    ///
+    /// The first stage syncs up to the latest head, once every stage reached it the pipeline
+    /// waits for the next one.
+    pub fn with_new_heads(mut self, new_heads: NewHeadsStream) -> Self {
+        self.new_heads = Some(new_heads);
+        self
+    }
+    /// Builds the pipeline, the database can be shared with other readers, e.g. the rpc servers.
+    pub fn build(self, db: impl Into<Arc<DatabaseProvider>>, chain_spec: ChainSpec) -> Pipeline {
         let Self { 
             stages,
-            max_block
+            max_block,
+            batch_size,
+            new_heads,
         } = self;
+        let (commands_tx, commands) = mpsc::unbounded_channel();
         Pipeline {
-            db,
+            db: db.into(),
             chain: chain_spec,
             stages,
             max_block,
+            batch_size,
+            new_heads,
+            tip: None,
+            bad_block: None,
             listeners: Default::default(),
             progress: Default::default(),
+            paused: false,
+            commands,
+            commands_tx,
+            status: watch::channel(PipelineStatus { max_block, ..Default::default() }).0,
         }
     }
 }
@@ -51,6 +87,8 @@ impl  Default for PipelineBuilder {
         Self { 
             stages: Vec::new(),
             max_block: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+            new_heads: None,
         }
     }
 }
@@ -60,6 +98,7 @@ impl std::fmt::Debug for PipelineBuilder {
         f.debug_struct("PipelineBuilder")
             .field("stages", &self.stages.iter().map(|stage| stage.id()).collect::<Vec<StageId>>())
             .field("max_block", &self.max_block)
+            .field("new_heads", &self.new_heads.is_some())
             .finish()
     }
 }
@@ -1,6 +1,12 @@
-use crate::stage::ExecOutput;
+use crate::stage::{ExecOutput, UnwindInput, UnwindOutput};
 use simp_primitives::{StageId, BlockNumber};
+use simp_tokio_util::EventListeners;
 use std::fmt::{Display, Formatter};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// Number of events buffered for each subscription of a [PipelineHandle][super::PipelineHandle].
+pub const PIPELINE_EVENTS_CAPACITY: usize = 1024;
 
 /// An event emitted by a [Pipeline][crate::Pipeline].
 ///
@@ -29,20 +35,20 @@ pub enum PipelineEvent {
         /// The result of executing the stage.
         result: ExecOutput,
     },
-    // /// Emitted when a stage is about to be unwound.
-    // Unwinding {
-    //     /// The stage that is about to be unwound.
-    //     stage_id: StageId,
-    //     /// The unwind parameters.
-    //     input: UnwindInput,
-    // },
-    // /// Emitted when a stage has been unwound.
-    // Unwound {
-    //     /// The stage that was unwound.
-    //     stage_id: StageId,
-    //     /// The result of unwinding the stage.
-    //     result: UnwindOutput,
-    // },
+    /// Emitted when a stage is about to be unwound.
+    Unwinding {
+        /// The stage that is about to be unwound.
+        stage_id: StageId,
+        /// The unwind parameters.
+        input: UnwindInput,
+    },
+    /// Emitted when a stage has been unwound.
+    Unwound {
+        /// The stage that was unwound.
+        stage_id: StageId,
+        /// The result of unwinding the stage.
+        result: UnwindOutput,
+    },
     /// Emitted when a stage encounters an error either during execution or unwinding.
     Error {
         /// The stage that encountered an error.
@@ -73,3 +79,61 @@ impl Display for PipelineStagesProgress {
         write!(f, "{}/{}", self.current, self.total)
     }
 }
+
+/// A type that hands out subscriptions to the [PipelineEvent]s of a running pipeline.
+///
+/// [Pipeline::events][crate::Pipeline::events] has a single consumer, the events are forwarded
+/// into a [broadcast::Sender] to be shared with any number of subscribers.
+pub trait PipelineEventSubscriptions: Send + Sync {
+    /// Returns a receiver of the events emitted from now on.
+    fn subscribe_to_pipeline_events(&self) -> broadcast::Receiver<PipelineEvent>;
+}
+
+impl PipelineEventSubscriptions for broadcast::Sender<PipelineEvent> {
+    fn subscribe_to_pipeline_events(&self) -> broadcast::Receiver<PipelineEvent> {
+        self.subscribe()
+    }
+}
+
+/// No pipeline is running, the returned receiver is closed.
+impl PipelineEventSubscriptions for () {
+    fn subscribe_to_pipeline_events(&self) -> broadcast::Receiver<PipelineEvent> {
+        broadcast::channel(1).1
+    }
+}
+
+/// The listeners of a [Pipeline][crate::Pipeline]: the streams of its events and the subscriptions
+/// of its handles.
+#[derive(Debug)]
+pub(crate) struct PipelineListeners {
+    listeners: EventListeners<PipelineEvent>,
+    subscriptions: broadcast::Sender<PipelineEvent>,
+}
+
+impl PipelineListeners {
+    /// Sends an event to every listener and subscription.
+    pub(crate) fn notify(&mut self, event: PipelineEvent) {
+        // fails while nobody is subscribed
+        let _ = self.subscriptions.send(event.clone());
+        self.listeners.notify(event)
+    }
+
+    /// Adds a new event listener.
+    pub(crate) fn new_listener(&mut self) -> UnboundedReceiverStream<PipelineEvent> {
+        self.listeners.new_listener()
+    }
+
+    /// Returns the sender the subscriptions are created from.
+    pub(crate) fn subscriptions(&self) -> broadcast::Sender<PipelineEvent> {
+        self.subscriptions.clone()
+    }
+}
+
+impl Default for PipelineListeners {
+    fn default() -> Self {
+        Self {
+            listeners: Default::default(),
+            subscriptions: broadcast::channel(PIPELINE_EVENTS_CAPACITY).0,
+        }
+    }
+}
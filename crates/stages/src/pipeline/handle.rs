@@ -0,0 +1,83 @@
+use simp_primitives::BlockNumber;
+use tokio::sync::{broadcast, mpsc, watch};
+
+use super::{PipelineEvent, PipelineEventSubscriptions};
+
+/// A command sent to a running [Pipeline][crate::Pipeline] through a [PipelineHandle].
+///
+/// Commands are applied between two runs of the stages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineCommand {
+    /// Stop running the stages until [PipelineCommand::Resume] is sent.
+    Pause,
+    /// Run the stages again after a [PipelineCommand::Pause].
+    Resume,
+    /// Change the block the pipeline stops at, `None` runs it forever.
+    SetMaxBlock(Option<BlockNumber>),
+    /// Unwind every stage to the given block.
+    Unwind(BlockNumber),
+}
+
+/// State of a [Pipeline][crate::Pipeline], published after every change.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineStatus {
+    /// Whether the stages are paused.
+    pub paused: bool,
+    /// The block the pipeline stops at.
+    pub max_block: Option<BlockNumber>,
+    /// The lowest block reached by a stage since the pipeline started.
+    pub minimum_block_number: Option<BlockNumber>,
+    /// The highest block reached by a stage since the pipeline started.
+    pub maximum_block_number: Option<BlockNumber>,
+}
+
+/// A type that reports the status of a pipeline and forwards commands to it.
+pub trait PipelineController: Send + Sync {
+    /// Returns the status of the pipeline, `None` if it is not running.
+    fn pipeline_status(&self) -> Option<PipelineStatus>;
+
+    /// Sends a command to the pipeline, returns false if it is not running.
+    fn send_pipeline_command(&self, command: PipelineCommand) -> bool;
+}
+
+/// No pipeline is running, commands are dropped.
+impl PipelineController for () {
+    fn pipeline_status(&self) -> Option<PipelineStatus> {
+        None
+    }
+
+    fn send_pipeline_command(&self, _command: PipelineCommand) -> bool {
+        false
+    }
+}
+
+/// A cloneable handle to a [Pipeline][crate::Pipeline], see [Pipeline::handle][crate::Pipeline::handle].
+///
+/// It outlives the pipeline, it then reports it as stopped.
+#[derive(Debug, Clone)]
+pub struct PipelineHandle {
+    pub(crate) commands: mpsc::UnboundedSender<PipelineCommand>,
+    pub(crate) status: watch::Receiver<PipelineStatus>,
+    pub(crate) events: broadcast::Sender<PipelineEvent>,
+}
+
+impl PipelineController for PipelineHandle {
+    fn pipeline_status(&self) -> Option<PipelineStatus> {
+        // the pipeline owns the receiving end of the commands
+        if self.commands.is_closed() {
+            return None
+        }
+
+        Some(*self.status.borrow())
+    }
+
+    fn send_pipeline_command(&self, command: PipelineCommand) -> bool {
+        self.commands.send(command).is_ok()
+    }
+}
+
+impl PipelineEventSubscriptions for PipelineHandle {
+    fn subscribe_to_pipeline_events(&self) -> broadcast::Receiver<PipelineEvent> {
+        self.events.subscribe()
+    }
+}
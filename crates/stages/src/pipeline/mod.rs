@@ -1,4 +1,11 @@
 mod event;
+use event::PipelineListeners;
+pub use event::{
+    PipelineEvent, PipelineEventSubscriptions, PipelineStagesProgress, PIPELINE_EVENTS_CAPACITY,
+};
+
+mod handle;
+pub use handle::{PipelineCommand, PipelineController, PipelineHandle, PipelineStatus};
 
 mod progress;
 use progress::PipelineProgress;
@@ -7,33 +14,58 @@ mod ctrl;
 pub use ctrl::ControlFlow;
 mod builder;
 pub use builder::PipelineBuilder;
-use simp_primitives::{BlockNumber, ChainSpec, StageId};
-use simp_tokio_util::EventListeners;
+use std::sync::Arc;
+
+use futures::FutureExt;
+use simp_primitives::{BlockNumber, ChainSpec, NewHeadsStream, StageId};
 use storage_provider::{traits::*, DatabaseProvider};
+use tokio::sync::{mpsc, watch};
 use tracing::*;
 
-use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::{wrappers::UnboundedReceiverStream, StreamExt};
 
 use crate::{
     error::StageError,
-    pipeline::event::PipelineEvent,
-    stage::{BoxedStage, ExecInput, ExecOutput},
+    stage::{BoxedStage, ExecInput, ExecOutput, UnwindInput},
     PipelineError,
 };
 
-use self::event::PipelineStagesProgress;
+/// Number of times in a row a stage can fail on the same block before the pipeline stops.
+pub const MAX_BAD_BLOCK_ATTEMPTS: usize = 3;
+
+/// Default number of blocks a stage executes before the pipeline checks for commands.
+pub const DEFAULT_BATCH_SIZE: u64 = 1_000;
 
 pub struct Pipeline {
     stages: Vec<BoxedStage>,
 
-    db: DatabaseProvider,
+    db: Arc<DatabaseProvider>,
 
     chain: ChainSpec,
 
-    listeners: EventListeners<PipelineEvent>,
+    listeners: PipelineListeners,
 
     max_block: Option<BlockNumber>,
+    /// Number of blocks a stage executes at once, commands are applied between batches.
+    batch_size: u64,
     progress: PipelineProgress,
+
+    /// Headers of the new blocks when the pipeline follows the tip of the chain.
+    new_heads: Option<NewHeadsStream>,
+    /// The latest head received.
+    tip: Option<BlockNumber>,
+
+    /// The last block a stage failed on and the number of times in a row it did.
+    bad_block: Option<(BlockNumber, usize)>,
+
+    /// Whether the stages are paused by a [PipelineCommand::Pause].
+    paused: bool,
+    /// Commands sent by the [PipelineHandle]s.
+    commands: mpsc::UnboundedReceiver<PipelineCommand>,
+    /// Sender the [PipelineHandle]s are created from.
+    commands_tx: mpsc::UnboundedSender<PipelineCommand>,
+    /// The last published [PipelineStatus].
+    status: watch::Sender<PipelineStatus>,
 }
 
 impl Pipeline {
@@ -46,6 +78,15 @@ impl Pipeline {
     pub fn events(&mut self) -> UnboundedReceiverStream<PipelineEvent> {
         self.listeners.new_listener()
     }
+
+    /// Returns a handle to query and steer the pipeline while it runs.
+    pub fn handle(&self) -> PipelineHandle {
+        PipelineHandle {
+            commands: self.commands_tx.clone(),
+            status: self.status.subscribe(),
+            events: self.listeners.subscriptions(),
+        }
+    }
     // run pipeline in infinte loop
 
     /// Registers progress metrics for each registered stage
@@ -68,8 +109,19 @@ impl Pipeline {
 
     pub async fn run(&mut self) -> Result<(), PipelineError> {
         self.register_metrics()?;
+        self.publish_status();
+        let mut unwound = false;
         loop {
+            unwound |= self.apply_commands().await?;
+
+            // an unwound pipeline, by a reorg or an admin command, syncs the unwound blocks again
+            // right away
+            if !unwound {
+                self.wait_for_new_head().await?;
+            }
+
             let next_action = self.run_loop().await?;
+            unwound = next_action.is_unwind();
 
             // Terminate the loop early if it's reached the maximum block number
             // configured block.
@@ -92,6 +144,104 @@ impl Pipeline {
         }
     }
 
+    /// Applies the commands received since the last batch of blocks, waiting for
+    /// [PipelineCommand::Resume] while the pipeline is paused.
+    ///
+    /// Returns whether the stages were unwound.
+    async fn apply_commands(&mut self) -> Result<bool, PipelineError> {
+        let mut unwound = false;
+        loop {
+            let command = if self.paused {
+                match self.commands.recv().await {
+                    Some(command) => command,
+                    // the pipeline holds a sender, the channel is never closed
+                    None => return Ok(unwound),
+                }
+            } else {
+                match self.commands.try_recv() {
+                    Ok(command) => command,
+                    Err(_) => return Ok(unwound),
+                }
+            };
+
+            unwound |= self.apply_command(command).await?;
+        }
+    }
+
+    /// Applies a command, returns whether the stages were unwound.
+    async fn apply_command(&mut self, command: PipelineCommand) -> Result<bool, PipelineError> {
+        info!(target: "sync::pipeline", ?command, "Applying pipeline command");
+        let mut unwound = false;
+        match command {
+            PipelineCommand::Pause => self.paused = true,
+            PipelineCommand::Resume => self.paused = false,
+            PipelineCommand::SetMaxBlock(max_block) => self.max_block = max_block,
+            PipelineCommand::Unwind(target) => {
+                self.unwind(target, None).await?;
+                // the stages are behind the blocks they reached so far
+                self.progress = PipelineProgress::default();
+                unwound = true;
+            }
+        }
+
+        self.publish_status();
+        Ok(unwound)
+    }
+
+    /// Waits for a new head of the chain once the stages reached the latest one, when the
+    /// pipeline follows the tip.
+    ///
+    /// A command received meanwhile ends the wait, it is applied before the stages run again.
+    async fn wait_for_new_head(&mut self) -> Result<(), PipelineError> {
+        let new_heads = match self.new_heads.as_mut() {
+            Some(new_heads) => new_heads,
+            None => return Ok(()),
+        };
+
+        let behind_tip = match (self.tip, self.progress.block_number) {
+            (Some(tip), Some(reached)) => reached < tip,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if behind_tip {
+            return Ok(())
+        }
+
+        trace!(target: "sync::pipeline", tip = ?self.tip, "Waiting for a new head");
+        let command = tokio::select! {
+            head = new_heads.next() => match head {
+                Some(head) => {
+                    // skip to the latest of the heads received while the stages ran
+                    let mut tip = head.number;
+                    while let Some(Some(head)) = new_heads.next().now_or_never() {
+                        tip = head.number;
+                    }
+
+                    debug!(target: "sync::pipeline", tip, "Received a new head");
+                    self.tip = Some(tip);
+                    return Ok(())
+                }
+                None => return Err(PipelineError::Internal("the new heads stream ended".into())),
+            },
+            command = self.commands.recv() => command,
+        };
+
+        match command {
+            Some(command) => self.apply_command(command).await.map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Publishes the current [PipelineStatus] to the [PipelineHandle]s.
+    fn publish_status(&self) {
+        self.status.send_replace(PipelineStatus {
+            paused: self.paused,
+            max_block: self.max_block,
+            minimum_block_number: self.progress.minimum_block_number,
+            maximum_block_number: self.progress.maximum_block_number,
+        });
+    }
+
     pub async fn run_loop(&mut self) -> Result<ControlFlow, PipelineError> {
         let mut previous_stage = None;
         for stage_index in 0..self.stages.len() {
@@ -118,11 +268,11 @@ impl Pipeline {
                     return Ok(ControlFlow::Unwind { target, bad_block });
                 }
             }
+            self.publish_status();
 
             previous_stage = self
                 .db
-                .get_stage_checkpoint(stage_id)
-                .unwrap()
+                .get_stage_checkpoint(stage_id)?
                 .map(|progress| progress);
         }
 
@@ -130,13 +280,60 @@ impl Pipeline {
     }
     // run pipeline once
 
-    // unwind ??
+    /// Unwind the stages to the target block.
+    ///
+    /// Stages are unwound in reverse order of execution. Stages whose checkpoint is already at or
+    /// below the target are skipped.
     pub async fn unwind(
         &mut self,
         target: BlockNumber,
         bad_block: Option<BlockNumber>,
     ) -> Result<(), PipelineError> {
-        unimplemented!()
+        for stage in self.stages.iter_mut().rev() {
+            let stage_id = stage.id();
+
+            let checkpoint = self.db.get_stage_checkpoint(stage_id)?;
+
+            let checkpoint = match checkpoint {
+                Some(checkpoint) if checkpoint > target => checkpoint,
+                _ => {
+                    info!(
+                        target: "sync::pipeline",
+                        stage = %stage_id,
+                        unwind_target = target,
+                        "Stage does not need to be unwound, skipping."
+                    );
+                    self.listeners.notify(PipelineEvent::Skipped { stage_id });
+                    continue;
+                }
+            };
+
+            let input = UnwindInput { checkpoint, unwind_to: target, bad_block };
+            self.listeners.notify(PipelineEvent::Unwinding { stage_id, input });
+
+            debug!(target: "sync::pipeline", stage = %stage_id, from = checkpoint, to = target, "Starting unwind");
+            match stage.unwind(input, &self.db).await {
+                Ok(output) => {
+                    info!(
+                        target: "sync::pipeline",
+                        stage = %stage_id,
+                        unwind_target = target,
+                        progress = output.checkpoint,
+                        "Stage unwound"
+                    );
+
+                    self.db.save_stage_checkpoint(stage_id, output.checkpoint)?;
+
+                    self.listeners.notify(PipelineEvent::Unwound { stage_id, result: output });
+                }
+                Err(err) => {
+                    self.listeners.notify(PipelineEvent::Error { stage_id });
+                    return Err(PipelineError::Stage(err));
+                }
+            }
+        }
+
+        Ok(())
     }
 
     // execute stage to completion
@@ -147,15 +344,24 @@ impl Pipeline {
         stage_index: usize,
     ) -> Result<ControlFlow, PipelineError> {
         let total_stages = self.stages.len();
-        let db_provider = &self.db;
+        let db_provider = Arc::clone(&self.db);
 
-        let stage = &mut self.stages[stage_index];
-        let stage_id = stage.id();
+        let stage_id = self.stages[stage_index].id();
         let mut made_progress = false;
-        let target = self.max_block.or(previous_stage);
 
         loop {
-            let prev_checkpoint = db_provider.get_stage_checkpoint(stage_id).unwrap();
+            // commands sent while a long range is synced are applied between batches
+            if self.apply_commands().await? {
+                // the stages after this one sync up to its checkpoint once unwound
+                return Ok(ControlFlow::NoProgress { block_number: None })
+            }
+
+            // the first stage syncs up to the tip of the chain, when the pipeline follows it
+            let target = self.max_block.or(previous_stage).or(self.tip);
+            let prev_checkpoint = db_provider.get_stage_checkpoint(stage_id)?;
+            let batch_target = target.map(|target| {
+                target.min(prev_checkpoint.unwrap_or_default().saturating_add(self.batch_size))
+            });
 
             let stage_reached_max_block = prev_checkpoint
                 .zip(self.max_block)
@@ -187,19 +393,22 @@ impl Pipeline {
 
             // if stage does not error update and continue pipeline
             // else fail gracefully (try again or stop process)
-            match stage
+            match self.stages[stage_index]
                 .execute(
                     ExecInput {
-                        target,
+                        target: batch_target,
                         checkpoint: prev_checkpoint,
                     },
-                    db_provider,
+                    &db_provider,
                     &self.chain,
                 )
                 .await
             {
                 Ok(out @ ExecOutput { checkpoint, done }) => {
                     made_progress |= checkpoint != prev_checkpoint.unwrap_or_default();
+                    if self.bad_block.map_or(false, |(block, _)| checkpoint >= block) {
+                        self.bad_block = None;
+                    }
                     debug!(
                         target: "sync::pipeline",
                         stage = %stage_id,
@@ -209,9 +418,7 @@ impl Pipeline {
                         "Stage committed progress"
                     );
 
-                    db_provider
-                        .save_stage_checkpoint(stage_id, checkpoint)
-                        .unwrap();
+                    db_provider.save_stage_checkpoint(stage_id, checkpoint)?;
 
                     self.listeners.notify(PipelineEvent::Ran {
                         pipeline_stages_progress: PipelineStagesProgress {
@@ -222,6 +429,16 @@ impl Pipeline {
                         result: out.clone(),
                     });
 
+                    // the stage reached the end of a batch short of the target
+                    let batch_done = batch_target
+                        .zip(target)
+                        .map_or(false, |(batch_target, target)| {
+                            batch_target < target && checkpoint >= batch_target
+                        });
+                    if done && batch_done {
+                        continue;
+                    }
+
                     if done {
                         let block_number = checkpoint;
                         return Ok(if made_progress {
@@ -239,8 +456,48 @@ impl Pipeline {
                     // unwind stage
                     //
                     let out = if let StageError::Block { block } = err {
-                        unimplemented!()
-                        // retry block
+                        let attempts = match self.bad_block {
+                            Some((bad_block, attempts)) if bad_block == block => attempts + 1,
+                            _ => 1,
+                        };
+                        self.bad_block = Some((block, attempts));
+
+                        // a block failing deterministically would be unwound and synced again
+                        // forever
+                        if attempts >= MAX_BAD_BLOCK_ATTEMPTS {
+                            error!(
+                                target: "sync::pipeline",
+                                stage = %stage_id,
+                                bad_block = block,
+                                attempts,
+                                "Stage keeps failing on the same block, stopping."
+                            );
+                            return Err(PipelineError::BadBlock { stage_id, block, attempts })
+                        }
+
+                        warn!(
+                            target: "sync::pipeline",
+                            stage = %stage_id,
+                            bad_block = block,
+                            attempts,
+                            "Stage encountered a bad block. Unwinding..."
+                        );
+
+                        // unwind to the last checkpoint the stage committed before the bad block
+                        Ok(ControlFlow::Unwind {
+                            target: prev_checkpoint.unwrap_or_default(),
+                            bad_block: block,
+                        })
+                    } else if let StageError::Reorg { fork_block, bad_block } = err {
+                        warn!(
+                            target: "sync::pipeline",
+                            stage = %stage_id,
+                            fork_block,
+                            bad_block,
+                            "Stage detected a chain reorganisation. Unwinding..."
+                        );
+
+                        Ok(ControlFlow::Unwind { target: fork_block, bad_block })
                     } else if err.is_fatal() {
                         error!(
                             target: "sync::pipeline",
@@ -291,11 +548,13 @@ impl std::fmt::Debug for Pipeline {
 mod tests {
     use super::*;
     use db::test_utils::create_test_rw_db;
-    use simp_primitives::{StageId, MAINNET};
+    use interfaces::db::DatabaseError;
+    use simp_primitives::{Header, StageId, MAINNET};
     use storage_provider::options::AccessType;
 
     use crate::{
         pipeline::{progress::PipelineProgress, ControlFlow},
+        stage::UnwindOutput,
         test_utils::stage::TestStage,
     };
     use tokio_stream::StreamExt;
@@ -406,5 +665,332 @@ mod tests {
     }
 
 
-    // TODO: missing unwind implementation and pipeline error handling
+    #[tokio::test]
+    async fn unwind_pipeline() {
+        let db = create_test_rw_db();
+        let db_provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: 100, done: true }))
+                    .add_unwind(Ok(UnwindOutput { checkpoint: 10 })),
+            )
+            .add_stage(
+                TestStage::new(StageId::Other("B"))
+                    .add_exec(Ok(ExecOutput { checkpoint: 10, done: true })),
+            )
+            .with_max_block(10)
+            .build(
+                db_provider,
+                MAINNET.clone().as_ref().clone(),
+            );
+        let events = pipeline.events();
+
+        // Run pipeline and unwind it right after
+        tokio::spawn(async move {
+            pipeline.run().await.unwrap();
+            pipeline.unwind(10, None).await.unwrap();
+        });
+
+        let events = events.collect::<Vec<PipelineEvent>>().await;
+
+        // Check that the stages were unwound in reverse order, skipping the one already at the
+        // target
+        assert_eq!(
+            events[4..].to_vec(),
+            vec![
+                PipelineEvent::Skipped { stage_id: StageId::Other("B") },
+                PipelineEvent::Unwinding {
+                    stage_id: StageId::Other("A"),
+                    input: UnwindInput { checkpoint: 100, unwind_to: 10, bad_block: None },
+                },
+                PipelineEvent::Unwound {
+                    stage_id: StageId::Other("A"),
+                    result: UnwindOutput { checkpoint: 10 },
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn unwind_on_bad_block() {
+        let db = create_test_rw_db();
+        let db_provider = DatabaseProvider::new(db, AccessType::Primary);
+        db_provider.save_stage_checkpoint(StageId::Other("A"), 5).unwrap();
+        db_provider.save_stage_checkpoint(StageId::Other("B"), 5).unwrap();
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: 10, done: true }))
+                    .add_unwind(Ok(UnwindOutput { checkpoint: 5 })),
+            )
+            .add_stage(
+                TestStage::new(StageId::Other("B"))
+                    .add_exec(Err(StageError::Block { block: 6 })),
+            )
+            .with_max_block(10)
+            .build(
+                db_provider,
+                MAINNET.clone().as_ref().clone(),
+            );
+
+        let next = pipeline.run_loop().await.unwrap();
+
+        assert_eq!(next, ControlFlow::Unwind { target: 5, bad_block: 6 });
+        assert_eq!(pipeline.db.get_stage_checkpoint(StageId::Other("A")).unwrap(), Some(5));
+        assert_eq!(pipeline.db.get_stage_checkpoint(StageId::Other("B")).unwrap(), Some(5));
+    }
+
+    #[tokio::test]
+    async fn stop_on_persistent_bad_block() {
+        let db = create_test_rw_db();
+        let db_provider = DatabaseProvider::new(db, AccessType::Primary);
+        db_provider.save_stage_checkpoint(StageId::Other("A"), 5).unwrap();
+
+        // the stage is already at the unwind target, it only executes again
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .with_exec(
+                        (0..MAX_BAD_BLOCK_ATTEMPTS).map(|_| Err(StageError::Block { block: 6 })).collect(),
+                    ),
+            )
+            .with_max_block(10)
+            .build(
+                db_provider,
+                MAINNET.clone().as_ref().clone(),
+            );
+
+        let err = pipeline.run().await.unwrap_err();
+
+        assert!(matches!(
+            err,
+            PipelineError::BadBlock { block: 6, attempts: MAX_BAD_BLOCK_ATTEMPTS, .. }
+        ));
+        assert_eq!(pipeline.db.get_stage_checkpoint(StageId::Other("A")).unwrap(), Some(5));
+    }
+
+    #[tokio::test]
+    async fn unwind_on_reorg() {
+        let db = create_test_rw_db();
+        let db_provider = DatabaseProvider::new(db, AccessType::Primary);
+        db_provider.save_stage_checkpoint(StageId::Other("A"), 8).unwrap();
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Err(StageError::Reorg { fork_block: 3, bad_block: 4 }))
+                    .add_unwind(Ok(UnwindOutput { checkpoint: 3 })),
+            )
+            .with_max_block(10)
+            .build(
+                db_provider,
+                MAINNET.clone().as_ref().clone(),
+            );
+
+        let next = pipeline.run_loop().await.unwrap();
+
+        assert_eq!(next, ControlFlow::Unwind { target: 3, bad_block: 4 });
+        assert_eq!(pipeline.db.get_stage_checkpoint(StageId::Other("A")).unwrap(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn control_pipeline_through_handle() {
+        let db = create_test_rw_db();
+        let db_provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: 20, done: true }))
+                    .add_unwind(Ok(UnwindOutput { checkpoint: 5 }))
+                    .add_exec(Ok(ExecOutput { checkpoint: 10, done: true })),
+            )
+            .build(
+                db_provider,
+                MAINNET.clone().as_ref().clone(),
+            );
+        let handle = pipeline.handle();
+
+        pipeline.run_loop().await.unwrap();
+        let status = handle.pipeline_status().unwrap();
+        assert_eq!((status.minimum_block_number, status.maximum_block_number), (Some(20), Some(20)));
+
+        assert!(handle.send_pipeline_command(PipelineCommand::Unwind(5)));
+        assert!(handle.send_pipeline_command(PipelineCommand::SetMaxBlock(Some(10))));
+        pipeline.apply_commands().await.unwrap();
+
+        assert_eq!(pipeline.db.get_stage_checkpoint(StageId::Other("A")).unwrap(), Some(5));
+        assert_eq!(
+            handle.pipeline_status(),
+            Some(PipelineStatus { max_block: Some(10), ..Default::default() })
+        );
+
+        // the paused pipeline only runs the stages once resumed
+        handle.send_pipeline_command(PipelineCommand::Pause);
+        let run = tokio::spawn(async move {
+            pipeline.run().await.unwrap();
+            pipeline.db.get_stage_checkpoint(StageId::Other("A")).unwrap()
+        });
+        handle.send_pipeline_command(PipelineCommand::Resume);
+
+        assert_eq!(run.await.unwrap(), Some(10));
+        assert_eq!(handle.pipeline_status(), None);
+        assert!(!handle.send_pipeline_command(PipelineCommand::Pause));
+    }
+
+    #[tokio::test]
+    async fn execute_in_batches() {
+        let db = create_test_rw_db();
+        let db_provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        // the stage panics if it runs for a third batch
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: 10, done: true }))
+                    .add_exec(Ok(ExecOutput { checkpoint: 20, done: true })),
+            )
+            .with_max_block(100)
+            .with_batch_size(10)
+            .build(
+                db_provider,
+                MAINNET.clone().as_ref().clone(),
+            );
+        let handle = pipeline.handle();
+        let events = pipeline.events().filter_map(|event| match event {
+            PipelineEvent::Ran { result, .. } => Some(result.checkpoint),
+            _ => None,
+        });
+
+        // the stage applies the commands before each of its batches
+        assert!(handle.send_pipeline_command(PipelineCommand::SetMaxBlock(Some(20))));
+        let next = pipeline.run_loop().await.unwrap();
+
+        assert_eq!(next, ControlFlow::Continue { block_number: 20 });
+        assert_eq!(pipeline.db.get_stage_checkpoint(StageId::Other("A")).unwrap(), Some(20));
+
+        drop(pipeline);
+        assert_eq!(events.collect::<Vec<_>>().await, [10, 20]);
+    }
+
+    #[tokio::test]
+    async fn retry_on_transaction_conflict() {
+        let db = create_test_rw_db();
+        let db_provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Err(StageError::Database(DatabaseError::TransactionConflict(
+                        "busy".to_string(),
+                    ))))
+                    .add_exec(Ok(ExecOutput { checkpoint: 10, done: true })),
+            )
+            .with_max_block(10)
+            .build(
+                db_provider,
+                MAINNET.clone().as_ref().clone(),
+            );
+
+        pipeline.run_loop().await.unwrap();
+
+        assert_eq!(pipeline.db.get_stage_checkpoint(StageId::Other("A")).unwrap(), Some(10));
+    }
+
+    #[tokio::test]
+    async fn stop_on_database_error() {
+        let db = create_test_rw_db();
+        let db_provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Err(StageError::Database(DatabaseError::Codec("Headers")))),
+            )
+            .with_max_block(10)
+            .build(
+                db_provider,
+                MAINNET.clone().as_ref().clone(),
+            );
+
+        let err = pipeline.run_loop().await.unwrap_err();
+
+        assert!(matches!(err, PipelineError::Stage(StageError::Database(DatabaseError::Codec(_)))));
+        assert_eq!(pipeline.db.get_stage_checkpoint(StageId::Other("A")).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn follow_chain_tip() {
+        let db = create_test_rw_db();
+        let db_provider = DatabaseProvider::new(db, AccessType::Primary);
+        let (heads, new_heads) = mpsc::channel(1);
+
+        // the stage only runs once per head, it panics if the pipeline runs it again at the tip
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: 5, done: true }))
+                    .add_exec(Ok(ExecOutput { checkpoint: 8, done: true })),
+            )
+            .with_new_heads(Box::pin(tokio_stream::wrappers::ReceiverStream::new(new_heads)))
+            .build(
+                db_provider,
+                MAINNET.clone().as_ref().clone(),
+            );
+        let mut events = pipeline.events().filter_map(|event| match event {
+            PipelineEvent::Ran { result, .. } => Some(result.checkpoint),
+            _ => None,
+        });
+        let run = tokio::spawn(async move { pipeline.run().await });
+
+        heads.send(Header { number: 5, ..Default::default() }).await.unwrap();
+        assert_eq!(events.next().await, Some(5));
+
+        heads.send(Header { number: 8, ..Default::default() }).await.unwrap();
+        assert_eq!(events.next().await, Some(8));
+
+        drop(heads);
+        assert!(matches!(run.await.unwrap(), Err(PipelineError::Internal(_))));
+    }
+
+    #[tokio::test]
+    async fn resync_after_admin_unwind() {
+        let db = create_test_rw_db();
+        let db_provider = DatabaseProvider::new(db, AccessType::Primary);
+        let (heads, new_heads) = mpsc::channel(1);
+
+        // no head follows the unwind, the stage syncs the unwound blocks again without one
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: 5, done: true }))
+                    .add_unwind(Ok(UnwindOutput { checkpoint: 3 }))
+                    .add_exec(Ok(ExecOutput { checkpoint: 5, done: true })),
+            )
+            .with_new_heads(Box::pin(tokio_stream::wrappers::ReceiverStream::new(new_heads)))
+            .build(
+                db_provider,
+                MAINNET.clone().as_ref().clone(),
+            );
+        let handle = pipeline.handle();
+        let mut events = pipeline.events().filter_map(|event| match event {
+            PipelineEvent::Ran { result, .. } => Some(result.checkpoint),
+            _ => None,
+        });
+
+        pipeline.run_loop().await.unwrap();
+        assert_eq!(events.next().await, Some(5));
+
+        assert!(handle.send_pipeline_command(PipelineCommand::Unwind(3)));
+        let run = tokio::spawn(async move { pipeline.run().await });
+
+        let resynced = tokio::time::timeout(std::time::Duration::from_secs(5), events.next()).await;
+        assert_eq!(resynced.unwrap(), Some(5));
+
+        drop(heads);
+        assert!(matches!(run.await.unwrap(), Err(PipelineError::Internal(_))));
+    }
 }
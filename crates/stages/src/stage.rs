@@ -49,11 +49,32 @@ impl ExecOutput {
     }
 }
 
+/// Stage unwind input, see [Stage::unwind].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct UnwindInput {
+    /// The current highest checkpoint of the stage.
+    pub checkpoint: BlockNumber,
+    /// The block to unwind to.
+    pub unwind_to: BlockNumber,
+    /// The bad block that caused the unwind, if any.
+    pub bad_block: Option<BlockNumber>,
+}
+
+/// The output of a stage unwinding.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnwindOutput {
+    /// The checkpoint at which the stage has unwound to.
+    pub checkpoint: BlockNumber,
+}
+
 #[async_trait::async_trait]
 pub trait Stage: Send + Sync {
     fn id(&self) -> StageId;
     
     async fn execute(&mut self, input: ExecInput, db_provider: &DatabaseProvider, chain: &ChainSpec) -> Result<ExecOutput, StageError>;
+
+    /// Unwind the stage, removing everything it wrote above [UnwindInput::unwind_to].
+    async fn unwind(&mut self, input: UnwindInput, db_provider: &DatabaseProvider) -> Result<UnwindOutput, StageError>;
 }
 
 
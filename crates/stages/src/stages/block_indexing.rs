@@ -3,12 +3,12 @@ use std::collections::HashMap;
 
 use crate::{
     error::StageError,
-    stage::{ExecInput, ExecOutput},
+    stage::{ExecInput, ExecOutput, UnwindInput, UnwindOutput},
     Stage,
 };
 use db::tables::BlockBodyIndices;
-use simp_primitives::{ChainSpec, StageId, ComputationEngine, ChainRpcProvider, Log, TransactionSigned, TxNumber, TxHash, };
-use storage_provider::{DatabaseProvider, traits::{TransactionsWriter, BlockBodyIndicesWriter, LogsWriter}};
+use simp_primitives::{ChainSpec, StageId, ComputationEngine, ChainRpcProvider, Log, Receipt, TransactionSigned, TxNumber, TxHash, };
+use storage_provider::{DatabaseProvider, traits::{TransactionsWriter, BlockBodyIndicesWriter, BlockBodyIndicesProvider, LogsWriter, ReceiptsWriter, StageCheckpointWriter}};
 use serde_json::Value;
 
 pub struct BlockIndexingStage;
@@ -33,25 +33,52 @@ impl Stage for BlockIndexingStage {
             match chain.chain_type() {
                 ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
 
-                    let logs = chain.get_block_logs::<Log>(block).await.unwrap();
+                    let logs = chain
+                        .get_block_logs::<Log>(block)
+                        .await
+                        .map_err(|err| StageError::Recoverable(Box::new(err)))?;
 
-                    let txs = chain.get_block_txs::<String>(block).await.unwrap();
+                    let txs = chain
+                        .get_block_txs::<Value>(block)
+                        .await
+                        .map_err(|err| StageError::Recoverable(Box::new(err)))?
+                        .result
+                        .into_iter()
+                        .map(TransactionSigned::from)
+                        .collect::<Vec<TransactionSigned>>();
 
-                    let txs = txs.result.iter().map(|tx| {
-                        let tx = serde_json::from_str::<Value>(tx).unwrap();
-                        TransactionSigned::from(tx)
-                    }).collect::<Vec<TransactionSigned>>();
+                    let receipts = chain
+                        .get_block_receipts::<Receipt>(block)
+                        .await
+                        .map_err(|err| StageError::Recoverable(Box::new(err)))?
+                        .result;
 
-                    let (tx_indices, tx_num_hash) = db_provider.insert_transactions(txs).unwrap();
+                    // receipts are stored under the number of their transaction, matched by order.
+                    // The block may have been reorganised between the two requests, it is then
+                    // downloaded again.
+                    if receipts.len() != txs.len() {
+                        return Err(StageError::Recoverable(format!(
+                            "block {block} has {} transactions but {} receipts",
+                            txs.len(),
+                            receipts.len()
+                        ).into()))
+                    }
+
+                    // everything of the block is committed at once, together with the checkpoint
+                    let provider = db_provider.tx_mut();
+
+                    let (tx_indices, tx_num_hash) = provider.insert_transactions(txs)?;
                     
                     let tx_hash_num = tx_num_hash.iter().map(|(num, hash)| {
                         (hash.clone(), num.clone())
                     }).collect::<Vec<(TxHash, TxNumber)>>();
                     
-                    db_provider.insert_block_body_indices(block, BlockBodyIndices {
+                    provider.insert_receipts(tx_indices.first_tx_num, receipts)?;
+
+                    provider.insert_block_body_indices(block, BlockBodyIndices {
                         first_tx_num: tx_indices.first_tx_num,
                         tx_count: tx_indices.tx_count,
-                    }).unwrap();
+                    })?;
 
                     let mut tx_hash_logs = HashMap::new();
 
@@ -60,18 +87,24 @@ impl Stage for BlockIndexingStage {
                         tx_hash_logs.entry(tx_hash).or_insert(vec![]).push(log.clone());
                     }
 
-                    let tx_hash_logs = tx_hash_logs.iter().map(|(hash, logs)| {
-                        let tx_num = tx_hash_num.iter().find(|(h, _)| h == hash).unwrap().1.clone();
-                        (tx_num.clone(), logs.clone())
-                    }).collect::<Vec<(TxNumber, Vec<Log>)>>();
+                    // like the receipts, the logs may belong to a reorganised version of the block.
+                    // Returning drops the uncommitted transaction, so nothing of the block is kept.
+                    let tx_hash_logs = tx_hash_logs.into_iter().map(|(hash, logs)| {
+                        let (_, tx_num) = tx_hash_num.iter().find(|(h, _)| *h == hash).ok_or_else(|| {
+                            StageError::Recoverable(format!(
+                                "block {block} has logs of the unknown transaction {hash:?}"
+                            ).into())
+                        })?;
+                        Ok((*tx_num, logs))
+                    }).collect::<Result<Vec<(TxNumber, Vec<Log>)>, StageError>>()?;
 
-                    db_provider.insert_logs(tx_hash_logs).unwrap();
-                    // check bn to TxLogs
-                    // check logid to storedLog
+                    provider.insert_logs(tx_hash_logs)?;
 
-                    // TODO: create traces type
+                    provider.save_stage_checkpoint(self.id(), block)?;
 
-                    // let traces = chain.get_block_traces::<Trace).unwrap();
+                    provider.commit()?;
+                    // check bn to TxLogs
+                    // check logid to storedLog
                 },
                 _ => panic!("chain not supported")
             }
@@ -81,4 +114,37 @@ impl Stage for BlockIndexingStage {
             done: true,
         })
     }
+
+    /// removes the transactions, receipts, logs and body indices of every block above the unwind
+    /// target
+    async fn unwind(
+        &mut self,
+        input: UnwindInput,
+        db_provider: &DatabaseProvider,
+    ) -> Result<UnwindOutput, StageError> {
+        let unwind_to = input.unwind_to;
+
+        // everything from the first transaction after the target block gets removed. If the
+        // target was never indexed, every stored transaction is above it.
+        let first_tx = db_provider
+            .block_body_indices(unwind_to.into())?
+            .map(|index| index.next_tx_num())
+            .unwrap_or_default();
+
+        let provider = db_provider.tx_mut();
+
+        provider.remove_logs_above(unwind_to, first_tx)?;
+
+        provider.remove_receipts_from(first_tx)?;
+
+        provider.remove_transactions_from(first_tx)?;
+
+        provider.remove_block_body_indices_above(unwind_to)?;
+
+        provider.save_stage_checkpoint(self.id(), unwind_to)?;
+
+        provider.commit()?;
+
+        Ok(UnwindOutput { checkpoint: unwind_to })
+    }
 }
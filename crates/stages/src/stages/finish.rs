@@ -1,6 +1,6 @@
 use simp_primitives::{StageId, ChainSpec};
 use storage_provider::DatabaseProvider;
-use crate::{stage::{ExecInput, ExecOutput}, error::StageError, Stage};
+use crate::{stage::{ExecInput, ExecOutput, UnwindInput, UnwindOutput}, error::StageError, Stage};
 
 /// The finish stage.
 ///
@@ -23,4 +23,12 @@ impl Stage for FinishStage {
     ) -> Result<ExecOutput, StageError> {
         Ok(ExecOutput { checkpoint: input.target(), done: true })
     }
+
+    async fn unwind(
+        &mut self,
+        input: UnwindInput,
+        _db_provider: &DatabaseProvider,
+    ) -> Result<UnwindOutput, StageError> {
+        Ok(UnwindOutput { checkpoint: input.unwind_to })
+    }
 }
\ No newline at end of file
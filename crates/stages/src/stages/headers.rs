@@ -1,8 +1,75 @@
-use simp_primitives::{StageId, ChainSpec, ComputationEngine, ChainRpcProvider, Header};
+use simp_primitives::{StageId, ChainSpec, ComputationEngine, ChainRpcProvider, Header, BlockNumber};
 use storage_provider::DatabaseProvider;
-use crate::{Stage, stage::{ExecInput, ExecOutput}, error::StageError};
+use crate::{Stage, stage::{ExecInput, ExecOutput, UnwindInput, UnwindOutput}, error::StageError};
 use storage_provider::traits::*;
-pub struct HeadersStage;
+use tracing::warn;
+
+/// The default number of headers downloaded before they are written to the database.
+pub const DEFAULT_HEADERS_BATCH_SIZE: u64 = 10_000;
+
+pub struct HeadersStage {
+    /// Number of headers downloaded before they are written, split in JSON-RPC batches of
+    /// [ChainSpec::rpc_batch_size] requests.
+    batch_size: u64,
+}
+
+impl HeadersStage {
+    /// Creates a stage writing the headers every `batch_size` downloaded ones.
+    pub fn new(batch_size: u64) -> Self {
+        Self { batch_size: batch_size.max(1) }
+    }
+
+    /// Walks back from `from` until the stored hash matches the one of the rpc provider and
+    /// returns that block, the common ancestor of both chains.
+    ///
+    /// Only blocks inside the confirmation window below the head of the chain are tentative, a
+    /// mismatch at or below the finalized block means the stored chain can't be trusted anymore.
+    async fn find_fork_block(
+        &self,
+        from: BlockNumber,
+        db_provider: &DatabaseProvider,
+        chain: &ChainSpec,
+    ) -> Result<BlockNumber, StageError> {
+        let head = chain
+            .get_block_number()
+            .await
+            .map_err(|err| StageError::Recoverable(Box::new(err)))?;
+        let finalized = chain.finalized_block(head);
+        let mut block = from;
+
+        loop {
+            let local_hash = db_provider.block_hash(block)?;
+
+            let remote_header = chain
+                .get_block_header::<Header>(block)
+                .await
+                .map_err(|err| StageError::Recoverable(Box::new(err)))?
+                .result;
+
+            if local_hash == Some(remote_header.hash) {
+                return Ok(block)
+            }
+
+            if block <= finalized {
+                return Err(StageError::Fatal(
+                    format!(
+                        "stored block {block} diverged from the canonical chain below the confirmation window of {} blocks under head {head}",
+                        chain.confirmation_depth()
+                    )
+                    .into(),
+                ))
+            }
+
+            block -= 1;
+        }
+    }
+}
+
+impl Default for HeadersStage {
+    fn default() -> Self {
+        Self::new(DEFAULT_HEADERS_BATCH_SIZE)
+    }
+}
 
 #[async_trait::async_trait]
 impl Stage for HeadersStage {
@@ -16,19 +83,174 @@ impl Stage for HeadersStage {
             // load chain to get block methods
             match chain.chain_type() {
                 ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
-                    // TODO: if more than 10k blocks, batch calls
-                    let headers = chain.get_blocks_headers::<Header>(checkpoint, target).await.unwrap();
+                    let mut from = checkpoint;
+
+                    while from <= target {
+                        let to = target.min(from + self.batch_size - 1);
+
+                        let headers = chain
+                            .get_blocks_headers::<Header>(from, to)
+                            .await
+                            .map_err(|err| StageError::Recoverable(Box::new(err)))?;
+
+                        // the first downloaded header has to extend the stored chain, otherwise the
+                        // stored tip was reorganised away
+                        if let Some(first) = headers.first() {
+                            let local_tip = db_provider.block_hash(from - 1)?;
+
+                            if local_tip.map_or(false, |hash| hash != first.parent_hash) {
+                                let fork_block = self.find_fork_block(from - 1, db_provider, chain).await?;
+
+                                warn!(
+                                    target: "sync::stages::headers",
+                                    fork_block,
+                                    local_tip = from - 1,
+                                    "Stored chain diverged from the rpc provider"
+                                );
+
+                                return Err(StageError::Reorg { fork_block, bad_block: fork_block + 1 })
+                            }
+                        }
+
+                        // a reorg while downloading leaves a gap in the range, fetch it again
+                        if let Some(pair) = headers.windows(2).find(|pair| pair[1].parent_hash != pair[0].hash) {
+                            return Err(StageError::Recoverable(
+                                format!("header {} does not extend header {}", pair[1].number, pair[0].number).into(),
+                            ))
+                        }
+
+                        for header in headers.iter() {
+                            let provider = db_provider.tx_mut();
+
+                            provider.insert_block_hash(header.number, header.hash)?;
+
+                            provider.insert_block_number(header.hash, header.number)?;
+
+                            provider.insert_header(&header.number, header.clone())?;
 
-                    for header in headers.iter() {
-                        db_provider.insert_block_hash(header.number, header.hash).unwrap();
+                            provider.save_stage_checkpoint(self.id(), header.number)?;
 
-                        db_provider.insert_block_number(header.hash, header.number).unwrap();
+                            provider.commit()?;
+                        }
 
-                        db_provider.insert_header(&header.number, header.clone()).unwrap();
+                        from = to + 1;
                     }
                 },
                 _ => panic!("chain not supported")
             }
         Ok(ExecOutput { checkpoint: input.target(), done: true })
     }
-}
\ No newline at end of file
+
+    /// removes the headers, hashes and numbers of every block above the unwind target
+    async fn unwind(&mut self, input: UnwindInput, db_provider: &DatabaseProvider) -> Result<UnwindOutput, StageError> {
+        let provider = db_provider.tx_mut();
+
+        provider.remove_headers_above(input.unwind_to)?;
+
+        provider.save_stage_checkpoint(self.id(), input.unwind_to)?;
+
+        provider.commit()?;
+
+        Ok(UnwindOutput { checkpoint: input.unwind_to })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::test_utils::create_test_rw_db;
+    use mockito::Matcher;
+    use serde_json::{json, Value};
+    use simp_primitives::H256;
+    use storage_provider::options::AccessType;
+
+    fn hash(number: u64, fork: bool) -> H256 {
+        H256::from_low_u64_be(number + if fork { 1000 } else { 0 })
+    }
+
+    fn header(number: u64, fork: bool) -> Value {
+        json!({
+            "hash": hash(number, fork),
+            "parentHash": hash(number - 1, fork && number > 5),
+            "sha3Uncles": H256::zero(),
+            "miner": format!("0x{:040x}", 0),
+            "transactionsRoot": H256::zero(),
+            "receiptsRoot": H256::zero(),
+            "stateRoot": H256::zero(),
+            "difficulty": "0x0",
+            "number": format!("0x{number:x}"),
+            "gasLimit": "0x0",
+            "gasUsed": "0x0",
+            "timestamp": "0x0",
+            "mixHash": H256::zero(),
+            "nonce": "0x0",
+            "extraData": "0x",
+        })
+    }
+
+    /// Stores the blocks `1..=5` and serves a chain forked at block 5, with its head at `head`.
+    async fn setup(head: u64) -> (DatabaseProvider, mockito::ServerGuard) {
+        let db_provider = DatabaseProvider::new(create_test_rw_db(), AccessType::Primary);
+        for number in 1..=5 {
+            db_provider.insert_block_hash(number, hash(number, false)).unwrap();
+        }
+        db_provider.save_stage_checkpoint(StageId::Headers, 5).unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJsonString(r#"{"method":"eth_blockNumber"}"#.to_string()))
+            .with_body(json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{head:x}")}).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::Regex(r#"^\["#.to_string()))
+            .with_body(json!([{"jsonrpc": "2.0", "id": 6, "result": header(6, true)}]).to_string())
+            .create_async()
+            .await;
+        for number in 4..=5 {
+            server
+                .mock("POST", "/")
+                .match_body(Matcher::PartialJson(json!({"params": [format!("0x{number:x}"), false]})))
+                .with_body(json!({"jsonrpc": "2.0", "id": 1, "result": header(number, number == 5)}).to_string())
+                .create_async()
+                .await;
+        }
+
+        (db_provider, server)
+    }
+
+    fn chain(server: &mockito::ServerGuard) -> ChainSpec {
+        let mut chain = ChainSpec::default();
+        chain.rpc_connection = server.url();
+        chain.confirmation_depth = 3;
+        chain
+    }
+
+    #[tokio::test]
+    async fn unwind_reorg_inside_confirmation_window() {
+        // block 5 is above the finalized block 4
+        let (db_provider, server) = setup(7).await;
+
+        let err = HeadersStage::default()
+            .execute(ExecInput { target: Some(6), checkpoint: Some(5) }, &db_provider, &chain(&server))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, StageError::Reorg { fork_block: 4, bad_block: 5 }));
+    }
+
+    #[tokio::test]
+    async fn stop_on_reorg_below_confirmation_window() {
+        // block 5 is final under the head of the chain, although it is the stored tip
+        let (db_provider, server) = setup(100).await;
+
+        let err = HeadersStage::default()
+            .execute(ExecInput { target: Some(6), checkpoint: Some(5) }, &db_provider, &chain(&server))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, StageError::Fatal(_)));
+    }
+}
@@ -2,11 +2,14 @@ mod finish;
 pub use finish::FinishStage;
 
 mod headers;
-pub use headers::HeadersStage;
+pub use headers::{HeadersStage, DEFAULT_HEADERS_BATCH_SIZE};
 
 mod block_indexing;
 pub use block_indexing::BlockIndexingStage;
 
+mod traces;
+pub use traces::TracesStage;
+
 mod snapshots_indexing;
 pub use snapshots_indexing::SnapshotsIndexingStage;
 
@@ -1,6 +1,6 @@
 use simp_primitives::{StageId, ChainSpec};
 use storage_provider::DatabaseProvider;
-use crate::{Stage, stage::{ExecInput, ExecOutput}, error::StageError};
+use crate::{Stage, stage::{ExecInput, ExecOutput, UnwindInput, UnwindOutput}, error::StageError};
 
 pub struct SnapshotsIndexingStage;
 
@@ -20,4 +20,9 @@ impl Stage for SnapshotsIndexingStage {
         }
         Ok(ExecOutput { checkpoint: input.target(), done: true })
     }
+
+    /// snapshots are not persisted yet, so there is nothing to remove
+    async fn unwind(&mut self, input: UnwindInput, _db_provider: &DatabaseProvider) -> Result<UnwindOutput, StageError> {
+        Ok(UnwindOutput { checkpoint: input.unwind_to })
+    }
 }
\ No newline at end of file
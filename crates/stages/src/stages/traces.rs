@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    error::StageError,
+    stage::{ExecInput, ExecOutput, UnwindInput, UnwindOutput},
+    Stage,
+};
+use simp_primitives::{
+    ChainRpcProvider, ChainSpec, ComputationEngine, ParityTrace, StageId, Trace, TraceMethod,
+    TransactionCallFrame, TxNumber,
+};
+use storage_provider::{
+    traits::{BlockBodyIndicesProvider, StageCheckpointWriter, TracesWriter},
+    DatabaseProvider,
+};
+
+/// Downloads the call traces of the indexed transactions and indexes their internal calls by
+/// address.
+///
+/// Runs after the [BlockIndexingStage](super::BlockIndexingStage), the traces are stored under
+/// the numbers it gave to the transactions.
+pub struct TracesStage {
+    method: TraceMethod,
+}
+
+impl TracesStage {
+    /// Creates a stage downloading the traces with the given rpc method.
+    pub fn new(method: TraceMethod) -> Self {
+        Self { method }
+    }
+
+    /// Returns the traces of every transaction of the block that made calls, keyed by their
+    /// position in the block.
+    async fn block_traces(
+        &self,
+        block: u64,
+        chain: &ChainSpec,
+    ) -> Result<BTreeMap<u64, Vec<Trace>>, StageError> {
+        let mut traces: BTreeMap<u64, Vec<Trace>> = BTreeMap::new();
+
+        match self.method {
+            TraceMethod::TraceBlock => {
+                let response = chain
+                    .get_block_traces::<ParityTrace>(block)
+                    .await
+                    .map_err(|err| StageError::Recoverable(Box::new(err)))?;
+
+                // block rewards don't belong to a transaction and are left out
+                for (position, trace) in response.result.into_iter().filter_map(ParityTrace::into_trace) {
+                    traces.entry(position).or_default().push(trace);
+                }
+            }
+            TraceMethod::DebugTraceBlock => {
+                let response = chain
+                    .get_block_call_traces::<TransactionCallFrame>(block)
+                    .await
+                    .map_err(|err| StageError::Recoverable(Box::new(err)))?;
+
+                // the frames are returned in the order of the transactions
+                for (position, frame) in response.result.into_iter().enumerate() {
+                    traces.insert(position as u64, frame.result.into_traces());
+                }
+            }
+        }
+
+        Ok(traces)
+    }
+}
+
+impl Default for TracesStage {
+    fn default() -> Self {
+        Self::new(TraceMethod::default())
+    }
+}
+
+#[async_trait::async_trait]
+impl Stage for TracesStage {
+    fn id(&self) -> StageId {
+        StageId::Traces
+    }
+
+    /// saves the traces of the transactions of every block up to the target
+    async fn execute(
+        &mut self,
+        input: ExecInput,
+        db_provider: &DatabaseProvider,
+        chain: &ChainSpec,
+    ) -> Result<ExecOutput, StageError> {
+        let target = input.target();
+        let checkpoint = input.checkpoint() + 1;
+
+        for block in checkpoint..=target {
+            match chain.chain_type() {
+                ComputationEngine::EVM | ComputationEngine::EVMCompatible => {
+                    let indices = db_provider.block_body_indices(block.into())?.ok_or_else(|| {
+                        StageError::Fatal(format!("block {block} has not been indexed").into())
+                    })?;
+
+                    let traces = match indices.tx_count {
+                        0 => BTreeMap::new(),
+                        _ => self.block_traces(block, chain).await?,
+                    };
+
+                    // a trace past the last transaction means the block was reorganised since it
+                    // was indexed, it is downloaded again
+                    if let Some(position) = traces.keys().find(|position| **position >= indices.tx_count) {
+                        return Err(StageError::Recoverable(format!(
+                            "block {block} has {} transactions but a trace for transaction {position}",
+                            indices.tx_count
+                        ).into()))
+                    }
+
+                    let traces = traces
+                        .into_iter()
+                        .map(|(position, traces)| (indices.first_tx_num + position, traces))
+                        .collect::<Vec<(TxNumber, Vec<Trace>)>>();
+
+                    let provider = db_provider.tx_mut();
+
+                    provider.insert_traces(traces)?;
+
+                    provider.save_stage_checkpoint(self.id(), block)?;
+
+                    provider.commit()?;
+                }
+                _ => panic!("chain not supported"),
+            }
+        }
+
+        Ok(ExecOutput { checkpoint: target, done: true })
+    }
+
+    /// removes the traces of the transactions of every block above the unwind target
+    async fn unwind(
+        &mut self,
+        input: UnwindInput,
+        db_provider: &DatabaseProvider,
+    ) -> Result<UnwindOutput, StageError> {
+        let unwind_to = input.unwind_to;
+
+        // the block indexing stage unwinds after this one, the indices of the target are still
+        // there
+        let first_tx = db_provider
+            .block_body_indices(unwind_to.into())?
+            .map(|index| index.next_tx_num())
+            .unwrap_or_default();
+
+        let provider = db_provider.tx_mut();
+
+        provider.remove_traces_from(first_tx)?;
+
+        provider.save_stage_checkpoint(self.id(), unwind_to)?;
+
+        provider.commit()?;
+
+        Ok(UnwindOutput { checkpoint: unwind_to })
+    }
+}
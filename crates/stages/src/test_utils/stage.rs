@@ -1,4 +1,4 @@
-use crate::{Stage, stage::{ExecOutput, ExecInput}, error::StageError};
+use crate::{Stage, stage::{ExecOutput, ExecInput, UnwindInput, UnwindOutput}, error::StageError};
 use simp_primitives::{StageId, ChainSpec};
 use storage_provider::DatabaseProvider;
 use std::collections::VecDeque;
@@ -7,11 +7,12 @@ use std::collections::VecDeque;
 pub struct TestStage {
     id: StageId,
     exec_outputs: VecDeque<Result<ExecOutput, StageError>>,
+    unwind_outputs: VecDeque<Result<UnwindOutput, StageError>>,
 }
 
 impl TestStage {
     pub fn new(id: StageId) -> Self {
-        Self { id, exec_outputs: VecDeque::new() }
+        Self { id, exec_outputs: VecDeque::new(), unwind_outputs: VecDeque::new() }
     }
 
     pub fn with_exec(mut self, exec_outputs: VecDeque<Result<ExecOutput, StageError>>) -> Self {
@@ -23,6 +24,16 @@ impl TestStage {
         self.exec_outputs.push_back(output);
         self
     }
+
+    pub fn with_unwind(mut self, unwind_outputs: VecDeque<Result<UnwindOutput, StageError>>) -> Self {
+        self.unwind_outputs = unwind_outputs;
+        self
+    }
+
+    pub fn add_unwind(mut self, output: Result<UnwindOutput, StageError>) -> Self {
+        self.unwind_outputs.push_back(output);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -41,4 +52,14 @@ impl Stage for TestStage {
             .pop_front()
             .unwrap_or_else(|| panic!("Test stage {} executed too many times.", self.id))
     }
+
+    async fn unwind(
+        &mut self,
+        _input: UnwindInput,
+        _db_provider: &DatabaseProvider,
+    ) -> Result<UnwindOutput, StageError> {
+        self.unwind_outputs
+            .pop_front()
+            .unwrap_or_else(|| panic!("Test stage {} unwound too many times.", self.id))
+    }
 }
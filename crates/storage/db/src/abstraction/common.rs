@@ -15,6 +15,12 @@ pub type PairResult<T> = Result<Option<KeyValue<T>>, DatabaseError>;
 /// not there is another entry.
 pub type IterPairResult<T> = Option<Result<KeyValue<T>, DatabaseError>>;
 
+/// A subkey-value pair of the DupSort table `T`.
+pub type DupKeyValue<T> = (<T as DupSort>::SubKey, <T as Table>::Value);
+
+/// A fallible subkey-value pair that may or may not exist for the current key.
+pub type DupPairResult<T> = Result<Option<DupKeyValue<T>>, DatabaseError>;
+
 /// A value only result for table `T`.
 pub type ValueOnlyResult<T> = Result<Option<<T as Table>::Value>, DatabaseError>;
 
@@ -0,0 +1,384 @@
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
+
+use interfaces::db::DatabaseError;
+use rocksdb::{DBAccess, DBRawIteratorWithThreadMode, MultiThreaded, TransactionDB};
+
+use crate::{
+    common::{DupKeyValue, DupPairResult, IterPairResult, KeyValue, PairResult},
+    implementation::dae_rocksdb::TxLock,
+    table::{Decode, DupSort, Encode, Table},
+    tables::utils::{decode_one, decoder, encode_dup_key},
+};
+
+/// A typed cursor over the entries of table `T`, in the order of their encoded keys.
+///
+/// Every positioning method returns the entry the cursor lands on, or `None` if it moved past
+/// either end of the table.
+pub struct Cursor<'a, T: Table, D: DBAccess = TransactionDB<MultiThreaded>> {
+    iter: DBRawIteratorWithThreadMode<'a, D>,
+    /// Lock of the transaction the cursor iterates over, released after the iterator is dropped.
+    _lock: Option<TxLock<'a>>,
+    _table: PhantomData<T>,
+}
+
+impl<'a, T: Table, D: DBAccess> Cursor<'a, T, D> {
+    /// Wraps a raw iterator over the column family of `T`.
+    pub fn new(iter: DBRawIteratorWithThreadMode<'a, D>) -> Self {
+        Self { iter, _lock: None, _table: PhantomData }
+    }
+
+    /// Keeps the transaction the iterator reads locked while the cursor is open.
+    pub(crate) fn with_lock(mut self, lock: TxLock<'a>) -> Self {
+        self._lock = Some(lock);
+        self
+    }
+
+    /// Positions the cursor at the first entry of the table.
+    pub fn first(&mut self) -> PairResult<T> {
+        self.iter.seek_to_first();
+        self.current()
+    }
+
+    /// Positions the cursor at the last entry of the table.
+    pub fn last(&mut self) -> PairResult<T> {
+        self.iter.seek_to_last();
+        self.current()
+    }
+
+    /// Positions the cursor at the first entry whose key is greater than or equal to `key`.
+    pub fn seek(&mut self, key: T::Key) -> PairResult<T> {
+        self.iter.seek(key.encode());
+        self.current()
+    }
+
+    /// Positions the cursor at `key`, returning `None` if the table has no such entry.
+    pub fn seek_exact(&mut self, key: T::Key) -> PairResult<T> {
+        let encoded = key.encode();
+        self.iter.seek(encoded.as_ref());
+
+        match self.iter.key() {
+            Some(found) if found == encoded.as_ref() => self.current(),
+            _ => self.status().map(|_| None),
+        }
+    }
+
+    /// Moves the cursor to the next entry.
+    pub fn next(&mut self) -> PairResult<T> {
+        if !self.iter.valid() {
+            return self.status().map(|_| None)
+        }
+        self.iter.next();
+        self.current()
+    }
+
+    /// Moves the cursor to the previous entry.
+    pub fn prev(&mut self) -> PairResult<T> {
+        if !self.iter.valid() {
+            return self.status().map(|_| None)
+        }
+        self.iter.prev();
+        self.current()
+    }
+
+    /// Returns the entry at the current position of the cursor.
+    pub fn current(&self) -> PairResult<T> {
+        match self.iter.item() {
+            Some((k, v)) => Ok(Some(decoder::<T>((k.to_vec(), v.to_vec()))?)),
+            None => self.status().map(|_| None),
+        }
+    }
+
+    /// Walks forward over every entry with a key inside `range`.
+    pub fn walk<R: RangeBounds<T::Key>>(
+        &mut self,
+        range: R,
+    ) -> Result<Walker<'_, 'a, T, D>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Unbounded => self.first(),
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(key) => {
+                let encoded = key.clone().encode();
+                match self.seek(key)? {
+                    Some(_) if self.iter.key() == Some(encoded.as_ref()) => self.next(),
+                    current => Ok(current),
+                }
+            }
+        }?;
+
+        let end = match range.end_bound().cloned() {
+            Bound::Unbounded => Bound::Unbounded,
+            Bound::Included(key) => Bound::Included(key.encode().as_ref().to_vec()),
+            Bound::Excluded(key) => Bound::Excluded(key.encode().as_ref().to_vec()),
+        };
+
+        Ok(Walker { cursor: self, start: start.map(Ok), end })
+    }
+
+    /// Walks backwards from `start`, or from the last entry of the table if `None`, down to the
+    /// first entry.
+    pub fn walk_back(
+        &mut self,
+        start: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, 'a, T, D>, DatabaseError> {
+        let start = match start {
+            Some(key) => {
+                self.iter.seek_for_prev(key.encode());
+                self.current()
+            }
+            None => self.last(),
+        }?;
+
+        Ok(ReverseWalker { cursor: self, start: start.map(Ok) })
+    }
+
+    fn status(&self) -> Result<(), DatabaseError> {
+        self.iter.status().map_err(crate::implementation::dae_rocksdb::map_rocksdb_error)
+    }
+}
+
+/// Iterator over the entries of a [Cursor] inside a key range, see [Cursor::walk].
+pub struct Walker<'c, 'a, T: Table, D: DBAccess> {
+    cursor: &'c mut Cursor<'a, T, D>,
+    start: IterPairResult<T>,
+    end: Bound<Vec<u8>>,
+}
+
+impl<'c, 'a, T: Table, D: DBAccess> Iterator for Walker<'c, 'a, T, D> {
+    type Item = Result<KeyValue<T>, DatabaseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = match self.start.take() {
+            Some(start) => Some(start),
+            None => self.cursor.next().transpose(),
+        }?;
+
+        // keys are compared the same way rocksdb orders them
+        let in_range = match (&self.end, self.cursor.iter.key()) {
+            (Bound::Unbounded, _) | (_, None) => true,
+            (Bound::Included(end), Some(key)) => key <= end.as_slice(),
+            (Bound::Excluded(end), Some(key)) => key < end.as_slice(),
+        };
+
+        in_range.then_some(item)
+    }
+}
+
+/// Iterator over the entries of a [Cursor] in reverse order, see [Cursor::walk_back].
+pub struct ReverseWalker<'c, 'a, T: Table, D: DBAccess> {
+    cursor: &'c mut Cursor<'a, T, D>,
+    start: IterPairResult<T>,
+}
+
+impl<'c, 'a, T: Table, D: DBAccess> Iterator for ReverseWalker<'c, 'a, T, D> {
+    type Item = Result<KeyValue<T>, DatabaseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.start.take() {
+            Some(start) => Some(start),
+            None => self.cursor.prev().transpose(),
+        }
+    }
+}
+
+/// A typed cursor over the values of the keys of the DupSort table `T`, in the order of their
+/// encoded subkeys.
+///
+/// The cursor is bound to the key it was last positioned on, it returns `None` once it moves past
+/// the values of that key.
+pub struct DupCursor<'a, T: DupSort, D: DBAccess = TransactionDB<MultiThreaded>> {
+    iter: DBRawIteratorWithThreadMode<'a, D>,
+    /// Lock of the transaction the cursor iterates over, released after the iterator is dropped.
+    _lock: Option<TxLock<'a>>,
+    /// Encoded key the cursor is positioned on.
+    key: Option<Vec<u8>>,
+    _table: PhantomData<T>,
+}
+
+impl<'a, T: DupSort, D: DBAccess> DupCursor<'a, T, D> {
+    /// Wraps a raw iterator over the column family of `T`.
+    pub fn new(iter: DBRawIteratorWithThreadMode<'a, D>) -> Self {
+        Self { iter, _lock: None, key: None, _table: PhantomData }
+    }
+
+    /// Keeps the transaction the iterator reads locked while the cursor is open.
+    pub(crate) fn with_lock(mut self, lock: TxLock<'a>) -> Self {
+        self._lock = Some(lock);
+        self
+    }
+
+    /// Positions the cursor at the first value of `key`.
+    pub fn seek_by_key(&mut self, key: T::Key) -> DupPairResult<T> {
+        self.seek_dup(key, None)
+    }
+
+    /// Positions the cursor at the first value of `key` whose subkey is greater than or equal to
+    /// `subkey`.
+    pub fn seek_by_key_subkey(&mut self, key: T::Key, subkey: T::SubKey) -> DupPairResult<T> {
+        self.seek_dup(key, Some(subkey))
+    }
+
+    /// Moves the cursor to the next value of the current key.
+    pub fn next_dup(&mut self) -> DupPairResult<T> {
+        if !self.iter.valid() {
+            return self.status().map(|_| None)
+        }
+        self.iter.next();
+        self.current()
+    }
+
+    /// Returns the value at the current position of the cursor, if it still belongs to the
+    /// current key.
+    pub fn current(&self) -> DupPairResult<T> {
+        let (key, (raw_key, value)) = match (&self.key, self.iter.item()) {
+            (Some(key), Some(item)) => (key, item),
+            _ => return self.status().map(|_| None),
+        };
+
+        match raw_key.strip_prefix(key.as_slice()) {
+            Some(subkey) => {
+                let subkey = <T::SubKey as Decode>::decode(subkey)
+                    .map_err(|_| DatabaseError::Codec(T::NAME))?;
+
+                Ok(Some((subkey, decode_one::<T>(value.to_vec())?)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Walks over the values of `key`, starting from `subkey` or from its first value if `None`.
+    pub fn walk_dup(
+        &mut self,
+        key: T::Key,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, 'a, T, D>, DatabaseError> {
+        let start = self.seek_dup(key, subkey)?;
+
+        Ok(DupWalker { cursor: self, start: start.map(Ok) })
+    }
+
+    fn seek_dup(&mut self, key: T::Key, subkey: Option<T::SubKey>) -> DupPairResult<T> {
+        let encoded = key.clone().encode().as_ref().to_vec();
+
+        match subkey {
+            Some(subkey) => self.iter.seek(encode_dup_key::<T>(key, subkey)),
+            None => self.iter.seek(&encoded),
+        }
+        self.key = Some(encoded);
+
+        self.current()
+    }
+
+    fn status(&self) -> Result<(), DatabaseError> {
+        self.iter.status().map_err(crate::implementation::dae_rocksdb::map_rocksdb_error)
+    }
+}
+
+/// Iterator over the values of a single key of a [DupCursor], see [DupCursor::walk_dup].
+pub struct DupWalker<'c, 'a, T: DupSort, D: DBAccess> {
+    cursor: &'c mut DupCursor<'a, T, D>,
+    start: Option<Result<DupKeyValue<T>, DatabaseError>>,
+}
+
+impl<'c, 'a, T: DupSort, D: DBAccess> Iterator for DupWalker<'c, 'a, T, D> {
+    type Item = Result<DupKeyValue<T>, DatabaseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.start.take() {
+            Some(start) => Some(start),
+            None => self.cursor.next_dup().transpose(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{init_db, tables::CanonicalHeaders, test_utils::ERROR_TEMPDIR, transaction::DbTx};
+    use rocksdb::Options;
+    use simp_primitives::{BlockNumber, H256};
+
+    crate::dupsort!(
+        /// Stores the events of a block by their index.
+        ( BlockEvents ) BlockNumber | [u64] H256
+    );
+
+    #[test]
+    fn cursor_walks_ranges() {
+        let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
+
+        for number in 0..10u64 {
+            db.dae_put::<CanonicalHeaders>(number, H256::from_low_u64_be(number)).unwrap();
+        }
+
+        let mut cursor = db.dae_cursor::<CanonicalHeaders>().unwrap();
+
+        assert_eq!(cursor.first().unwrap().map(|(k, _)| k), Some(0));
+        assert_eq!(cursor.last().unwrap().map(|(k, _)| k), Some(9));
+        assert_eq!(cursor.next().unwrap(), None);
+        assert_eq!(cursor.seek(4).unwrap().map(|(k, _)| k), Some(4));
+        assert_eq!(cursor.prev().unwrap().map(|(k, _)| k), Some(3));
+        assert_eq!(cursor.seek_exact(11).unwrap(), None);
+
+        let walked = cursor
+            .walk(2..5)
+            .unwrap()
+            .map(|entry| entry.map(|(k, _)| k))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(walked, vec![2, 3, 4]);
+
+        let walked = cursor.walk(7..).unwrap().count();
+        assert_eq!(walked, 3);
+
+        let walked = cursor
+            .walk_back(Some(2))
+            .unwrap()
+            .map(|entry| entry.map(|(k, _)| k))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(walked, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn dup_cursor_walks_subkeys() {
+        let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
+        db.create_cf(BlockEvents::const_name(), &Options::default()).unwrap();
+
+        for block in 1..4u64 {
+            for event in 0..3u64 {
+                let value = H256::from_low_u64_be(block * 10 + event);
+                db.dae_put_dup::<BlockEvents>(block, event, value).unwrap();
+            }
+        }
+
+        let mut cursor = db.dae_dup_cursor::<BlockEvents>().unwrap();
+
+        assert_eq!(cursor.seek_by_key_subkey(2, 1).unwrap(), Some((1, H256::from_low_u64_be(21))));
+        assert_eq!(cursor.next_dup().unwrap().map(|(subkey, _)| subkey), Some(2));
+        // the next entry belongs to block 3
+        assert_eq!(cursor.next_dup().unwrap(), None);
+        assert_eq!(cursor.seek_by_key(4).unwrap(), None);
+
+        let walked = cursor
+            .walk_dup(1, None)
+            .unwrap()
+            .map(|entry| entry.map(|(subkey, _)| subkey))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(walked, vec![0, 1, 2]);
+
+        db.dae_delete_dup::<BlockEvents>(3, 1).unwrap();
+
+        // the iterator of a cursor is a snapshot, open a new one to see the delete
+        let mut cursor = db.dae_dup_cursor::<BlockEvents>().unwrap();
+        let walked = cursor
+            .walk_dup(3, Some(1))
+            .unwrap()
+            .map(|entry| entry.map(|(subkey, _)| subkey))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(walked, vec![2]);
+    }
+}
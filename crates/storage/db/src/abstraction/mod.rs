@@ -1,4 +1,5 @@
 pub mod table;
 pub mod common;
+pub mod cursor;
 pub mod transaction;
 
@@ -72,6 +72,18 @@ pub trait Table: Send + Sync + Debug + 'static {
 /// Tuple with `T::Key` and `T::Value`.
 pub type TableRow<T> = (<T as Table>::Key, <T as Table>::Value);
 
+/// DupSort allows for keys to be repeated in the database.
+///
+/// RocksDB has no notion of duplicate keys, so every value is stored under the composite
+/// `key||subkey` key. The encoding of `Key` must have a fixed width, otherwise the values of a key
+/// are not contiguous.
+pub trait DupSort: Table {
+    /// The table subkey, values of a key are sorted by it.
+    ///
+    /// Sorting should be taken into account when encoding this.
+    type SubKey: Key;
+}
+
 pub trait ShardedTable: Send + Sync + Debug + 'static {
         /// Return table name as it is present inside the RocksDB.
         const NAME: &'static str;
@@ -1,10 +1,24 @@
 use interfaces::db::DatabaseError;
-use rocksdb::{DBRawIteratorWithThreadMode, TransactionDB, ReadOptions};
+use rocksdb::DBAccess;
 
-use crate::{table::Table, common::PairResult};
+use crate::{
+    table::{DupSort, Table},
+    common::PairResult,
+    cursor::{Cursor, DupCursor},
+};
 
+/// Size estimates of a table, read from the RocksDB properties of its column family.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableStats {
+    /// Estimated number of entries, flushed or not.
+    pub estimated_entries: u64,
+    /// Size in bytes of the table files on disk, writes not flushed yet aren't counted.
+    pub sst_files_size: u64,
+}
 
 pub trait DbTx: Send + Sync {
+    /// RocksDB handle the cursors iterate over
+    type Access: DBAccess;
     /// Get value
     fn dae_get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError>;
     /// Get latest value saved in table
@@ -15,14 +29,28 @@ pub trait DbTx: Send + Sync {
     // fn dae_drop(&self);
     /// Iterate over read only values in table.
     fn dae_entries<T: Table>(&self) -> Result<usize, DatabaseError>;
+    /// Estimates the size of the table without reading its entries.
+    fn dae_table_stats<T: Table>(&self) -> Result<TableStats, DatabaseError>;
     /// Put value to database
     fn dae_put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError>;
     /// Delete value from database
     fn dae_delete<T: Table>(&self, key: T::Key)
         -> Result<bool, DatabaseError>;
-    /// Clears database.
+    /// Clears the table. It can't be rolled back, read-write transactions refuse to do it.
     fn dae_clear<T: Table>(&self) -> Result<(), DatabaseError>;
-    /// Create db Cursor
-    fn dae_new_cursor<T: Table>(&self, opts: ReadOptions) 
-        -> Result<DBRawIteratorWithThreadMode<TransactionDB>, DatabaseError>;
+    /// Create a typed cursor over the table
+    fn dae_cursor<T: Table>(&self) -> Result<Cursor<'_, T, Self::Access>, DatabaseError>;
+    /// Put a value under the subkey of a key in a DupSort table
+    fn dae_put_dup<T: DupSort>(
+        &self,
+        key: T::Key,
+        subkey: T::SubKey,
+        value: T::Value,
+    ) -> Result<(), DatabaseError>;
+    /// Delete the value under the subkey of a key in a DupSort table
+    fn dae_delete_dup<T: DupSort>(&self, key: T::Key, subkey: T::SubKey)
+        -> Result<bool, DatabaseError>;
+    /// Create a typed cursor over the values of the keys in a DupSort table
+    fn dae_dup_cursor<T: DupSort>(&self)
+        -> Result<DupCursor<'_, T, Self::Access>, DatabaseError>;
 }
\ No newline at end of file
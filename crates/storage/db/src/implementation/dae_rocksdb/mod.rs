@@ -1,120 +1,199 @@
+use std::{ops::Deref, sync::Arc};
+
 use interfaces::db::DatabaseError;
-use rocksdb::{TransactionDB, Options, ReadOptions, DBRawIteratorWithThreadMode, MultiThreaded};
+use rocksdb::{
+    properties, BoundColumnFamily, Cache, ErrorKind, MultiThreaded, Options, ReadOptions,
+    TransactionDB,
+};
 
 use crate::{
-    tables::utils::{decode_one, decoder},
-    transaction::DbTx,
-    table::{Compress, Encode, Table}, common::PairResult,
+    tables::utils::{decode_one, encode_dup_key},
+    transaction::{DbTx, TableStats},
+    table::{Compress, DupSort, Encode, Table}, common::PairResult,
+    cursor::{Cursor, DupCursor},
+    options::DatabaseOptions,
 };
 
-impl DbTx for TransactionDB<MultiThreaded> {
-    fn dae_get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
-        let tx = self.transaction();
+mod secondary;
+mod tx;
+pub use secondary::SecondaryDB;
+pub use tx::DbTxMut;
+pub(crate) use tx::TxLock;
+
+/// Maps a RocksDB error into a [DatabaseError]. Busy and timed out transactions are reported as
+/// conflicts since retrying them can succeed.
+pub(crate) fn map_rocksdb_error(err: rocksdb::Error) -> DatabaseError {
+    match err.kind() {
+        ErrorKind::Busy | ErrorKind::TimedOut | ErrorKind::TryAgain => {
+            DatabaseError::TransactionConflict(err.into_string())
+        }
+        _ => DatabaseError::Io(err.into_string()),
+    }
+}
 
-        let cf = self.cf_handle(T::NAME).unwrap();
-        let value = tx.get_cf(&cf, key.encode()).unwrap();
+/// Read options of the cursors. Tables read by key prefix have a prefix extractor, their cursors
+/// still have to walk across prefixes in key order.
+pub(crate) fn cursor_read_options() -> ReadOptions {
+    let mut opts = ReadOptions::default();
+    opts.set_total_order_seek(true);
+    opts
+}
 
-        match value {
-            None => Ok(None),
-            Some(val) => {
-                let value = decode_one::<T>(val).unwrap();
-                tx.commit().unwrap();
-                Ok(Some(value))
-            }
-        }
+/// Returns the column family handle of the table `T`.
+pub(crate) fn cf_handle<T: Table>(
+    db: &TransactionDB<MultiThreaded>,
+) -> Result<Arc<BoundColumnFamily<'_>>, DatabaseError> {
+    db.cf_handle(T::NAME).ok_or(DatabaseError::MissingColumnFamily(T::NAME))
+}
+
+/// The primary database, together with the options it was opened with. Tables created once it is
+/// open, by a migration or by clearing them, get the same options as the ones it was opened with.
+pub struct Database {
+    inner: TransactionDB<MultiThreaded>,
+    options: DatabaseOptions,
+    cache: Option<Cache>,
+}
+
+impl Database {
+    pub(crate) fn new(
+        inner: TransactionDB<MultiThreaded>,
+        options: DatabaseOptions,
+        cache: Option<Cache>,
+    ) -> Self {
+        Self { inner, options, cache }
+    }
+
+    /// Options the table `name` is created with, see [DatabaseOptions::table_options].
+    pub(crate) fn table_options(&self, name: &str) -> Options {
+        self.options.table_options(name, self.cache.as_ref())
+    }
+}
+
+impl Deref for Database {
+    type Target = TransactionDB<MultiThreaded>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DbTx for Database {
+    type Access = TransactionDB<MultiThreaded>;
+
+    fn dae_get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        let cf = cf_handle::<T>(self)?;
+        let value = self.get_cf(&cf, key.encode()).map_err(map_rocksdb_error)?;
+
+        value.map(decode_one::<T>).transpose()
     }
 
     fn dae_get_last<T: Table>(&self) -> PairResult<T> {
-        let opts = ReadOptions::default();
-        let mut iter = self.dae_new_cursor::<T>(opts).unwrap();
-        iter.seek_to_last();
-        if iter.valid() {
-            let k = iter.key().unwrap();
-            let v = iter.value().unwrap();
-            let kv = decoder::<T>((k.to_vec(), v.to_vec())).unwrap();
-            Ok(Some(kv))
-        } else {
-            Ok(None)
-        }
+        self.dae_cursor::<T>()?.last()
     }
 
     fn dae_get_first<T: Table>(&self) -> PairResult<T> {
-        let opts = ReadOptions::default();
-        let mut iter = self.dae_new_cursor::<T>(opts).unwrap();
-        iter.seek_to_first();
-        if iter.valid() {
-            let k = iter.key().unwrap();
-            let v = iter.value().unwrap();
-            let kv = decoder::<T>((k.to_vec(), v.to_vec())).unwrap();
-            Ok(Some(kv))
-        } else {
-            Ok(None)
-        }
+        self.dae_cursor::<T>()?.first()
     }
 
     fn dae_put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
-        let cf = self.cf_handle(T::NAME).unwrap();
+        let cf = cf_handle::<T>(self)?;
 
         let tx = self.transaction();
-        tx.put_cf(&cf, key.encode(), value.compress()).unwrap();
-        tx.commit().unwrap();
-        Ok(())
+        tx.put_cf(&cf, key.encode(), value.compress()).map_err(map_rocksdb_error)?;
+        tx.commit().map_err(map_rocksdb_error)
     }
 
     fn dae_clear<T: Table>(&self) -> Result<(), DatabaseError> {
-        let tx = self.transaction();
+        self.drop_cf(T::NAME).map_err(map_rocksdb_error)?;
 
-        self.drop_cf(T::NAME).unwrap();
-
-        self.create_cf(T::NAME, &Options::default()).unwrap();
-
-        tx.commit().unwrap();        
-        Ok(())
+        self.create_cf(T::NAME, &self.table_options(T::NAME)).map_err(map_rocksdb_error)
     }
 
     fn dae_delete<T: Table>(&self, key: T::Key) -> Result<bool, DatabaseError> {
-        let tx = self.transaction();
-
-        let cf = self.cf_handle(T::NAME).unwrap();
+        let cf = cf_handle::<T>(self)?;
 
-        tx.delete_cf(&cf, key.encode()).unwrap();
-
-        tx.commit().unwrap();
+        let tx = self.transaction();
+        tx.delete_cf(&cf, key.encode()).map_err(map_rocksdb_error)?;
+        tx.commit().map_err(map_rocksdb_error)?;
 
         Ok(true)
     }
 
     fn dae_entries<T: Table>(&self) -> Result<usize, DatabaseError> {
-        let tx = self.transaction();
-
-        let cf = self.cf_handle(T::NAME);
-
-        match cf {
-            None => {
-                tx.commit().unwrap();    
-                Ok(0)
-            },
+        match self.cf_handle(T::NAME) {
+            None => Ok(0),
             Some(cf) => {
-                let iter = tx.full_iterator_cf(&cf, rocksdb::IteratorMode::Start);
-                
-                let count = iter.count();
-
-                tx.commit().unwrap();
+                let mut count = 0;
+                for item in self.full_iterator_cf(&cf, rocksdb::IteratorMode::Start) {
+                    item.map_err(map_rocksdb_error)?;
+                    count += 1;
+                }
 
                 Ok(count)
             }
         }
     }
 
+    fn dae_table_stats<T: Table>(&self) -> Result<TableStats, DatabaseError> {
+        match self.cf_handle(T::NAME) {
+            None => Ok(TableStats::default()),
+            Some(cf) => {
+                let property = |name| {
+                    self.property_int_value_cf(&cf, name)
+                        .map(Option::unwrap_or_default)
+                        .map_err(map_rocksdb_error)
+                };
+
+                Ok(TableStats {
+                    estimated_entries: property(properties::ESTIMATE_NUM_KEYS)?,
+                    sst_files_size: property(properties::TOTAL_SST_FILES_SIZE)?,
+                })
+            }
+        }
+    }
+
     // fn dae_drop(&self) {
     //     drop(&self.transaction())
     // }
 
-    fn dae_new_cursor<T: Table>(&self, opts: ReadOptions) -> Result<DBRawIteratorWithThreadMode<TransactionDB>, DatabaseError> {
-        let cf_handle = self.cf_handle(T::NAME).unwrap();
-        let iter = self.raw_iterator_cf_opt(&cf_handle, opts);
+    fn dae_cursor<T: Table>(&self) -> Result<Cursor<'_, T>, DatabaseError> {
+        let cf_handle = cf_handle::<T>(self)?;
+
+        Ok(Cursor::new(self.raw_iterator_cf_opt(&cf_handle, cursor_read_options())))
+    }
+
+    fn dae_put_dup<T: DupSort>(
+        &self,
+        key: T::Key,
+        subkey: T::SubKey,
+        value: T::Value,
+    ) -> Result<(), DatabaseError> {
+        let cf = cf_handle::<T>(self)?;
+
+        let tx = self.transaction();
+        tx.put_cf(&cf, encode_dup_key::<T>(key, subkey), value.compress())
+            .map_err(map_rocksdb_error)?;
+        tx.commit().map_err(map_rocksdb_error)
+    }
+
+    fn dae_delete_dup<T: DupSort>(
+        &self,
+        key: T::Key,
+        subkey: T::SubKey,
+    ) -> Result<bool, DatabaseError> {
+        let cf = cf_handle::<T>(self)?;
+
+        let tx = self.transaction();
+        tx.delete_cf(&cf, encode_dup_key::<T>(key, subkey)).map_err(map_rocksdb_error)?;
+        tx.commit().map_err(map_rocksdb_error)?;
+
+        Ok(true)
+    }
+
+    fn dae_dup_cursor<T: DupSort>(&self) -> Result<DupCursor<'_, T>, DatabaseError> {
+        let cf_handle = cf_handle::<T>(self)?;
 
-        Ok(iter)
+        Ok(DupCursor::new(self.raw_iterator_cf_opt(&cf_handle, cursor_read_options())))
     }
 }
 
@@ -130,7 +209,7 @@ mod tests {
     use simp_primitives::Header;
 
     /// Create database for testing
-    fn create_test_db() -> eyre::Result<TransactionDB> {
+    fn create_test_db() -> eyre::Result<Database> {
         init_db(
             &tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path(),
         )
@@ -0,0 +1,195 @@
+use std::{ops::Deref, sync::Arc};
+
+use interfaces::db::DatabaseError;
+use rocksdb::{properties, BoundColumnFamily, Cache, DBWithThreadMode, MultiThreaded};
+
+use super::{cursor_read_options, map_rocksdb_error};
+use crate::{
+    common::PairResult,
+    cursor::{Cursor, DupCursor},
+    options::DatabaseOptions,
+    table::{Compress, DupSort, Encode, Table},
+    tables::utils::{decode_one, encode_dup_key},
+    transaction::{DbTx, TableStats},
+};
+
+/// A read-only RocksDB secondary instance following a primary database.
+///
+/// It only sees the writes of the primary up to the last call to
+/// [DBWithThreadMode::try_catch_up_with_primary], writes through it are rejected by RocksDB.
+pub struct SecondaryDB {
+    inner: DBWithThreadMode<MultiThreaded>,
+    options: DatabaseOptions,
+    cache: Option<Cache>,
+}
+
+impl SecondaryDB {
+    pub(crate) fn new(
+        inner: DBWithThreadMode<MultiThreaded>,
+        options: DatabaseOptions,
+        cache: Option<Cache>,
+    ) -> Self {
+        Self { inner, options, cache }
+    }
+}
+
+impl Deref for SecondaryDB {
+    type Target = DBWithThreadMode<MultiThreaded>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+fn cf_handle<T: Table>(db: &SecondaryDB) -> Result<Arc<BoundColumnFamily<'_>>, DatabaseError> {
+    db.cf_handle(T::NAME).ok_or(DatabaseError::MissingColumnFamily(T::NAME))
+}
+
+impl DbTx for SecondaryDB {
+    type Access = DBWithThreadMode<MultiThreaded>;
+
+    fn dae_get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        let cf = cf_handle::<T>(self)?;
+        let value = self.get_cf(&cf, key.encode()).map_err(map_rocksdb_error)?;
+
+        value.map(decode_one::<T>).transpose()
+    }
+
+    fn dae_get_last<T: Table>(&self) -> PairResult<T> {
+        self.dae_cursor::<T>()?.last()
+    }
+
+    fn dae_get_first<T: Table>(&self) -> PairResult<T> {
+        self.dae_cursor::<T>()?.first()
+    }
+
+    fn dae_entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        match self.cf_handle(T::NAME) {
+            None => Ok(0),
+            Some(cf) => {
+                let mut count = 0;
+                for item in self.full_iterator_cf(&cf, rocksdb::IteratorMode::Start) {
+                    item.map_err(map_rocksdb_error)?;
+                    count += 1;
+                }
+
+                Ok(count)
+            }
+        }
+    }
+
+    fn dae_table_stats<T: Table>(&self) -> Result<TableStats, DatabaseError> {
+        match self.cf_handle(T::NAME) {
+            None => Ok(TableStats::default()),
+            Some(cf) => {
+                let property = |name| {
+                    self.property_int_value_cf(&cf, name)
+                        .map(Option::unwrap_or_default)
+                        .map_err(map_rocksdb_error)
+                };
+
+                Ok(TableStats {
+                    estimated_entries: property(properties::ESTIMATE_NUM_KEYS)?,
+                    sst_files_size: property(properties::TOTAL_SST_FILES_SIZE)?,
+                })
+            }
+        }
+    }
+
+    fn dae_put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let cf = cf_handle::<T>(self)?;
+
+        self.put_cf(&cf, key.encode(), value.compress()).map_err(map_rocksdb_error)
+    }
+
+    fn dae_delete<T: Table>(&self, key: T::Key) -> Result<bool, DatabaseError> {
+        let cf = cf_handle::<T>(self)?;
+
+        self.delete_cf(&cf, key.encode()).map_err(map_rocksdb_error)?;
+        Ok(true)
+    }
+
+    fn dae_clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        self.drop_cf(T::NAME).map_err(map_rocksdb_error)?;
+
+        let options = self.options.table_options(T::NAME, self.cache.as_ref());
+        self.create_cf(T::NAME, &options).map_err(map_rocksdb_error)
+    }
+
+    fn dae_cursor<T: Table>(&self) -> Result<Cursor<'_, T, Self::Access>, DatabaseError> {
+        let cf_handle = cf_handle::<T>(self)?;
+
+        Ok(Cursor::new(self.raw_iterator_cf_opt(&cf_handle, cursor_read_options())))
+    }
+
+    fn dae_put_dup<T: DupSort>(
+        &self,
+        key: T::Key,
+        subkey: T::SubKey,
+        value: T::Value,
+    ) -> Result<(), DatabaseError> {
+        let cf = cf_handle::<T>(self)?;
+
+        self.put_cf(&cf, encode_dup_key::<T>(key, subkey), value.compress())
+            .map_err(map_rocksdb_error)
+    }
+
+    fn dae_delete_dup<T: DupSort>(
+        &self,
+        key: T::Key,
+        subkey: T::SubKey,
+    ) -> Result<bool, DatabaseError> {
+        let cf = cf_handle::<T>(self)?;
+
+        self.delete_cf(&cf, encode_dup_key::<T>(key, subkey)).map_err(map_rocksdb_error)?;
+        Ok(true)
+    }
+
+    fn dae_dup_cursor<T: DupSort>(&self) -> Result<DupCursor<'_, T, Self::Access>, DatabaseError> {
+        let cf_handle = cf_handle::<T>(self)?;
+
+        Ok(DupCursor::new(self.raw_iterator_cf_opt(&cf_handle, cursor_read_options())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        init_db, open_db_secondary, options::DatabaseOptions, tables::Headers,
+        test_utils::ERROR_TEMPDIR, transaction::DbTx,
+    };
+    use simp_primitives::Header;
+
+    #[test]
+    fn secondary_follows_primary() {
+        let primary_path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+        let secondary_path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+
+        let primary = init_db(&primary_path).unwrap();
+        primary.dae_put::<Headers>(1, Header::default()).unwrap();
+
+        let secondary =
+            open_db_secondary(&primary_path, &secondary_path, &DatabaseOptions::default()).unwrap();
+        assert_eq!(secondary.dae_get::<Headers>(1).unwrap(), Some(Header::default()));
+
+        primary.dae_put::<Headers>(2, Header::default()).unwrap();
+        assert_eq!(secondary.dae_get::<Headers>(2).unwrap(), None);
+
+        secondary.try_catch_up_with_primary().unwrap();
+        assert_eq!(secondary.dae_entries::<Headers>().unwrap(), 2);
+
+        // secondary instances are read-only
+        assert!(secondary.dae_put::<Headers>(3, Header::default()).is_err());
+    }
+
+    #[test]
+    fn secondary_does_not_create_primary() {
+        let primary_path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path().join("db");
+        let secondary_path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+
+        assert!(
+            open_db_secondary(&primary_path, &secondary_path, &DatabaseOptions::default()).is_err()
+        );
+        assert!(!primary_path.exists());
+    }
+}
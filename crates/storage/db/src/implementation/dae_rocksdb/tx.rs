@@ -0,0 +1,281 @@
+use std::cell::Cell;
+
+use interfaces::db::DatabaseError;
+use parking_lot::{ReentrantMutex, ReentrantMutexGuard};
+use rocksdb::{MultiThreaded, Transaction, TransactionDB};
+
+use super::{cf_handle, cursor_read_options, map_rocksdb_error};
+use crate::{
+    common::PairResult,
+    cursor::{Cursor, DupCursor},
+    table::{Compress, DupSort, Encode, Table},
+    tables::utils::{decode_one, encode_dup_key},
+    transaction::{DbTx, TableStats},
+};
+
+/// Guard of the lock of a [DbTxMut] held by an open cursor, the transaction can't be written
+/// until it is dropped.
+pub(crate) struct TxLock<'a> {
+    open_cursors: ReentrantMutexGuard<'a, Cell<usize>>,
+}
+
+impl<'a> TxLock<'a> {
+    fn new(open_cursors: ReentrantMutexGuard<'a, Cell<usize>>) -> Self {
+        open_cursors.set(open_cursors.get() + 1);
+        Self { open_cursors }
+    }
+}
+
+impl Drop for TxLock<'_> {
+    fn drop(&mut self) {
+        self.open_cursors.set(self.open_cursors.get() - 1);
+    }
+}
+
+/// A read-write transaction over the database.
+///
+/// Every write goes into a single RocksDB transaction that is only made visible to other readers
+/// once [DbTxMut::commit] is called. Dropping the handle without committing discards the writes.
+///
+/// Reads, cursors included, see the writes pending in the transaction. Writes are refused while a
+/// cursor over the transaction is open, RocksDB doesn't allow changing it under a live iterator.
+pub struct DbTxMut<'db> {
+    db: &'db TransactionDB<MultiThreaded>,
+    tx: Transaction<'db, TransactionDB<MultiThreaded>>,
+    /// Held while `tx` is used, counts the open cursors. Rocksdb transactions are `Send` but not
+    /// `Sync`, the lock is reentrant so the transaction can be read while a cursor over it is
+    /// open.
+    lock: ReentrantMutex<Cell<usize>>,
+}
+
+// SAFETY: `tx` is only used while holding `lock`. Its guards are not `Send`, so the thread holding
+// them is the only one using the transaction until they are all dropped, and that thread can't
+// write the transaction while one of its cursors is open.
+unsafe impl Sync for DbTxMut<'_> {}
+
+impl<'db> DbTxMut<'db> {
+    /// Opens a new transaction on the given database.
+    pub fn new(db: &'db TransactionDB<MultiThreaded>) -> Self {
+        Self { db, tx: db.transaction(), lock: ReentrantMutex::new(Cell::new(0)) }
+    }
+
+    /// Locks the transaction for a write, refused while a cursor over it is open.
+    fn write_lock(&self) -> Result<ReentrantMutexGuard<'_, Cell<usize>>, DatabaseError> {
+        let lock = self.lock.lock();
+
+        if lock.get() > 0 {
+            return Err(DatabaseError::CursorOpen)
+        }
+
+        Ok(lock)
+    }
+
+    /// Commits every write made through this transaction atomically.
+    pub fn commit(self) -> Result<(), DatabaseError> {
+        self.tx.commit().map_err(map_rocksdb_error)
+    }
+
+    /// Discards every write made through this transaction.
+    pub fn rollback(self) -> Result<(), DatabaseError> {
+        self.tx.rollback().map_err(map_rocksdb_error)
+    }
+}
+
+impl<'db> std::fmt::Debug for DbTxMut<'db> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DbTxMut").finish_non_exhaustive()
+    }
+}
+
+impl<'db> DbTx for DbTxMut<'db> {
+    type Access = Transaction<'db, TransactionDB<MultiThreaded>>;
+
+    fn dae_get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        let cf = cf_handle::<T>(self.db)?;
+        let _lock = self.lock.lock();
+        let value = self.tx.get_cf(&cf, key.encode()).map_err(map_rocksdb_error)?;
+
+        value.map(decode_one::<T>).transpose()
+    }
+
+    fn dae_get_last<T: Table>(&self) -> PairResult<T> {
+        self.dae_cursor::<T>()?.last()
+    }
+
+    fn dae_get_first<T: Table>(&self) -> PairResult<T> {
+        self.dae_cursor::<T>()?.first()
+    }
+
+    fn dae_entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        let cf = self.db.cf_handle(T::NAME);
+
+        match cf {
+            None => Ok(0),
+            Some(cf) => {
+                let _lock = self.lock.lock();
+                let mut count = 0;
+                for item in self.tx.full_iterator_cf(&cf, rocksdb::IteratorMode::Start) {
+                    item.map_err(map_rocksdb_error)?;
+                    count += 1;
+                }
+                Ok(count)
+            }
+        }
+    }
+
+    /// The estimates are the ones of the committed table, pending writes aren't counted.
+    fn dae_table_stats<T: Table>(&self) -> Result<TableStats, DatabaseError> {
+        self.db.dae_table_stats::<T>()
+    }
+
+    fn dae_put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let cf = cf_handle::<T>(self.db)?;
+        let _lock = self.write_lock()?;
+
+        self.tx.put_cf(&cf, key.encode(), value.compress()).map_err(map_rocksdb_error)
+    }
+
+    fn dae_delete<T: Table>(&self, key: T::Key) -> Result<bool, DatabaseError> {
+        let cf = cf_handle::<T>(self.db)?;
+        let _lock = self.write_lock()?;
+
+        self.tx.delete_cf(&cf, key.encode()).map_err(map_rocksdb_error)?;
+        Ok(true)
+    }
+
+    /// Dropping a column family can't be rolled back, tables are cleared on the database itself.
+    fn dae_clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        Err(DatabaseError::NotTransactional("clearing a table"))
+    }
+
+    fn dae_cursor<T: Table>(&self) -> Result<Cursor<'_, T, Self::Access>, DatabaseError> {
+        let cf = cf_handle::<T>(self.db)?;
+        let lock = TxLock::new(self.lock.lock());
+
+        Ok(Cursor::new(self.tx.raw_iterator_cf_opt(&cf, cursor_read_options())).with_lock(lock))
+    }
+
+    fn dae_put_dup<T: DupSort>(
+        &self,
+        key: T::Key,
+        subkey: T::SubKey,
+        value: T::Value,
+    ) -> Result<(), DatabaseError> {
+        let cf = cf_handle::<T>(self.db)?;
+        let _lock = self.write_lock()?;
+
+        self.tx
+            .put_cf(&cf, encode_dup_key::<T>(key, subkey), value.compress())
+            .map_err(map_rocksdb_error)
+    }
+
+    fn dae_delete_dup<T: DupSort>(
+        &self,
+        key: T::Key,
+        subkey: T::SubKey,
+    ) -> Result<bool, DatabaseError> {
+        let cf = cf_handle::<T>(self.db)?;
+        let _lock = self.write_lock()?;
+
+        self.tx.delete_cf(&cf, encode_dup_key::<T>(key, subkey)).map_err(map_rocksdb_error)?;
+        Ok(true)
+    }
+
+    fn dae_dup_cursor<T: DupSort>(&self) -> Result<DupCursor<'_, T, Self::Access>, DatabaseError> {
+        let cf = cf_handle::<T>(self.db)?;
+        let lock = TxLock::new(self.lock.lock());
+
+        Ok(DupCursor::new(self.tx.raw_iterator_cf_opt(&cf, cursor_read_options())).with_lock(lock))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_db, tables::Headers, test_utils::ERROR_TEMPDIR};
+    use simp_primitives::Header;
+
+    #[test]
+    fn writes_are_visible_after_commit() {
+        let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
+
+        let tx = DbTxMut::new(&db);
+        tx.dae_put::<Headers>(1, Header::default()).unwrap();
+        tx.dae_put::<Headers>(2, Header::default()).unwrap();
+
+        // pending writes are only visible inside the transaction
+        assert_eq!(tx.dae_get::<Headers>(1).unwrap(), Some(Header::default()));
+        assert_eq!(db.dae_get::<Headers>(1).unwrap(), None);
+
+        tx.commit().unwrap();
+
+        assert_eq!(db.dae_entries::<Headers>().unwrap(), 2);
+    }
+
+    #[test]
+    fn writes_are_discarded_on_rollback() {
+        let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
+
+        let tx = DbTxMut::new(&db);
+        tx.dae_put::<Headers>(1, Header::default()).unwrap();
+        tx.rollback().unwrap();
+
+        let tx = DbTxMut::new(&db);
+        tx.dae_put::<Headers>(2, Header::default()).unwrap();
+        drop(tx);
+
+        assert_eq!(db.dae_entries::<Headers>().unwrap(), 0);
+    }
+
+    #[test]
+    fn cursors_see_pending_writes() {
+        let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
+        db.dae_put::<Headers>(1, Header::default()).unwrap();
+
+        let tx = DbTxMut::new(&db);
+        tx.dae_put::<Headers>(2, Header::default()).unwrap();
+        tx.dae_delete::<Headers>(1).unwrap();
+
+        let keys = tx
+            .dae_cursor::<Headers>()
+            .unwrap()
+            .walk(..)
+            .unwrap()
+            .map(|entry| entry.map(|(key, _)| key))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(keys, vec![2]);
+        assert_eq!(tx.dae_get_last::<Headers>().unwrap().map(|(key, _)| key), Some(2));
+
+        // the transaction can't be written while a cursor over it is open
+        let mut cursor = tx.dae_cursor::<Headers>().unwrap();
+        assert!(cursor.first().unwrap().is_some());
+        assert!(matches!(
+            tx.dae_put::<Headers>(3, Header::default()),
+            Err(DatabaseError::CursorOpen)
+        ));
+        assert!(tx.dae_get::<Headers>(2).unwrap().is_some());
+        drop(cursor);
+        tx.dae_put::<Headers>(3, Header::default()).unwrap();
+
+        assert!(tx.dae_clear::<Headers>().is_err());
+        tx.commit().unwrap();
+
+        assert_eq!(db.dae_entries::<Headers>().unwrap(), 2);
+    }
+
+    #[test]
+    fn conflicting_writes_are_recoverable() {
+        let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
+
+        let tx = DbTxMut::new(&db);
+        tx.dae_put::<Headers>(1, Header::default()).unwrap();
+
+        // the key is locked by the pending transaction until it commits
+        let err = DbTxMut::new(&db).dae_put::<Headers>(1, Header::default()).unwrap_err();
+        assert!(matches!(err, DatabaseError::TransactionConflict(_)));
+        assert!(err.is_recoverable());
+
+        tx.commit().unwrap();
+    }
+}
@@ -1,14 +1,10 @@
 use eyre::Result;
-use rocksdb::{TransactionDB, MultiThreaded, Options};
 
-use crate::tables::Tables;
-
-pub fn create_tables(db: &mut TransactionDB::<MultiThreaded>) -> Result<()> {
-
-    let opts = Options::default();
+use crate::{implementation::dae_rocksdb::Database, tables::Tables};
 
+pub fn create_tables(db: &Database) -> Result<()> {
     for table in Tables::ALL {
-        db.create_cf(table.name(), &opts).unwrap();
+        db.create_cf(table.name(), &db.table_options(table.name()))?;
     }
 
     Ok(())
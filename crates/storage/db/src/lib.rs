@@ -7,14 +7,25 @@ use std::{
 };
 
 pub mod version;
+pub mod migration;
+pub mod options;
 pub mod tables;
 pub mod implementation;
 /// Traits defining the database abstractions, such as cursors and transactions.
 pub mod abstraction;
 pub use abstraction::*;
-use rocksdb::{Options, TransactionDB, TransactionDBOptions, SingleThreaded, MultiThreaded };
+use rocksdb::{
+    ColumnFamilyDescriptor, DBWithThreadMode, Options, TransactionDB, TransactionDBOptions,
+    SingleThreaded, MultiThreaded,
+};
 
-use crate::implementation::create_tables;
+use crate::{
+    implementation::{
+        create_tables,
+        dae_rocksdb::{Database, SecondaryDB},
+    },
+    options::{AccessType, DatabaseOptions},
+};
 
 pub fn get_all_cfs<P: AsRef<Path>>(path: P) -> eyre::Result<Vec<String>> {
 
@@ -31,10 +42,24 @@ pub fn get_all_cfs<P: AsRef<Path>>(path: P) -> eyre::Result<Vec<String>> {
     Ok(cfs)
 }
 
-pub fn init_db<P: AsRef<Path>>(path: P) -> eyre::Result<TransactionDB::<MultiThreaded>> {
+pub fn init_db<P: AsRef<Path>>(path: P) -> eyre::Result<Database> {
+    init_db_with_options(path, &DatabaseOptions::default())
+}
+
+/// Opens the database at `path` with the given options, creating it if it doesn't exist yet and
+/// migrating it if it was written by an older version.
+pub fn init_db_with_options<P: AsRef<Path>>(
+    path: P,
+    options: &DatabaseOptions,
+) -> eyre::Result<Database> {
     use crate::version::{check_db_version_file, create_db_version_file, DatabaseVersionError};
 
+    if options.access_type == AccessType::Secondary {
+        eyre::bail!("Secondary instances are opened with open_db_secondary");
+    }
+
     let path = path.as_ref();
+    let mut migrate_from = None;
     if is_database_empty(path) {
         create_dir_all(path)
             .wrap_err_with(|| format!("Could not create database directory {}", path.display()))?;
@@ -43,46 +68,86 @@ pub fn init_db<P: AsRef<Path>>(path: P) -> eyre::Result<TransactionDB::<MultiThr
         match check_db_version_file(path) {
             Ok(_) => (),
             Err(DatabaseVersionError::MissingFile) => create_db_version_file(path)?,
+            Err(DatabaseVersionError::VersionMismatch { version })
+                if migration::migrations_from(version).is_some() =>
+            {
+                migrate_from = Some(version)
+            }
             Err(err) => return Err(err.into()),
         }
     }
 
-    let mut opts: Options = Options::default();
-    opts.create_if_missing(true);
-    opts.create_missing_column_families(true);
-
+    let opts = options.db_options();
     let tx_opts = TransactionDBOptions::default();
+    let cache = options.block_cache();
 
-    let cfs = get_all_cfs(&path).unwrap();
+    let cfs = get_all_cfs(&path)?;
+    let descriptors = cfs.iter().map(|name| {
+        ColumnFamilyDescriptor::new(name, options.table_options(name, cache.as_ref()))
+    });
 
-    if cfs.len() > 0 {
-        let db = TransactionDB::<MultiThreaded>::open_cf(&opts, &tx_opts, path, &cfs)?;
+    let db = TransactionDB::<MultiThreaded>::open_cf_descriptors(&opts, &tx_opts, path, descriptors)?;
+    let db = Database::new(db, options.clone(), cache);
 
-        Ok(db)
+    if cfs.len() > 0 {
+        if let Some(version) = migrate_from {
+            migration::migrate(&db, path, version)?;
+        }
     } else {
-        let mut db = TransactionDB::<MultiThreaded>::open_cf(&opts, &tx_opts, path, &cfs)?;
-        create_tables(&mut db).unwrap();
-
-        Ok(db)
+        create_tables(&db)?;
     }
+
+    Ok(db)
 }
 
 /// Opens up an existing database. Read/Write mode.
 
-pub fn open_db<P: AsRef<Path>>(path: P) -> eyre::Result<TransactionDB::<MultiThreaded>> {
+pub fn open_db<P: AsRef<Path>>(path: P) -> eyre::Result<Database> {
     let path = path.as_ref();
-    let mut opts: Options = Options::default();
-    opts.create_if_missing(true);
-    opts.create_missing_column_families(true);
+    let options = DatabaseOptions::default();
+    let opts = options.db_options();
 
     let tx_opts = TransactionDBOptions::default();
 
-    let cfs = get_all_cfs(&path).unwrap();
+    let cfs = get_all_cfs(&path)?;
+    let descriptors = cfs
+        .iter()
+        .map(|name| ColumnFamilyDescriptor::new(name, options.table_options(name, None)));
 
-    TransactionDB::<MultiThreaded>::open_cf(&opts, &tx_opts, &path, &cfs)
+    TransactionDB::<MultiThreaded>::open_cf_descriptors(&opts, &tx_opts, &path, descriptors)
+        .map(|db| Database::new(db, options, None))
         .with_context(|| format!("Could not open database at path: {}", path.display()))
 }
 
+/// Opens a secondary instance of the database at `primary_path`, storing its own logs at
+/// `secondary_path`. It can be opened while another process writes to the primary, call
+/// [SecondaryDB::try_catch_up_with_primary] to see the latest writes.
+pub fn open_db_secondary<P: AsRef<Path>, S: AsRef<Path>>(
+    primary_path: P,
+    secondary_path: S,
+    options: &DatabaseOptions,
+) -> eyre::Result<SecondaryDB> {
+    let primary_path = primary_path.as_ref();
+
+    let opts = options.secondary_db_options();
+    let cache = options.block_cache();
+
+    let cfs = get_all_cfs(primary_path)?;
+    let descriptors = cfs.iter().map(|name| {
+        ColumnFamilyDescriptor::new(name, options.table_options(name, cache.as_ref()))
+    });
+
+    DBWithThreadMode::<MultiThreaded>::open_cf_descriptors_as_secondary(
+        &opts,
+        primary_path,
+        secondary_path.as_ref(),
+        descriptors,
+    )
+    .map(|db| SecondaryDB::new(db, options.clone(), cache))
+    .with_context(|| {
+        format!("Could not open secondary database of {}", primary_path.display())
+    })
+}
 
 // TODO: Once supported, create ReadOnly DB methods
 
@@ -99,13 +164,13 @@ pub mod test_utils {
     pub const ERROR_TEMPDIR: &str = "Not able to create a temporary directory.";
 
     /// Create read/write database for testing
-    pub fn create_test_rw_db() -> TransactionDB {
+    pub fn create_test_rw_db() -> Database {
         init_db(tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path())
             .expect(ERROR_DB_CREATION)
     }
 
     /// Create read/write database for testing
-    pub fn create_test_rw_db_with_path<P: AsRef<Path>>(path: P) -> TransactionDB {
+    pub fn create_test_rw_db_with_path<P: AsRef<Path>>(path: P) -> Database {
         init_db(path.as_ref()).expect(ERROR_DB_CREATION)
     }
 }
@@ -115,9 +180,13 @@ mod tests {
     use std::sync::Arc;
 
     use crate::{
-        init_db,
+        init_db, init_db_with_options,
+        options::{DatabaseCompressionType, DatabaseOptions},
+        tables::{ContractLogs, Headers, ShardedKey, Tables, TxLogs},
+        transaction::DbTx,
         version::db_version_file_path, get_all_cfs,
     };
+    use simp_primitives::{Address, Header};
     use tempfile::tempdir;
 
     #[test]
@@ -151,4 +220,31 @@ mod tests {
         }
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn db_with_options() {
+        let path = tempdir().unwrap();
+        let options = DatabaseOptions {
+            compression: DatabaseCompressionType::Lz4,
+            table_compression: vec![(Tables::Headers, DatabaseCompressionType::Zstd)],
+            block_cache_size: Some(8 * 1024 * 1024),
+            max_open_files: 256,
+            ..Default::default()
+        };
+
+        {
+            let db = init_db_with_options(&path, &options).unwrap();
+            db.dae_put::<Headers>(1, Header::default()).unwrap();
+
+            // tables with a prefix extractor are still walked across prefixes
+            for address in 0..3u64 {
+                let key = ShardedKey::new(Address::from_low_u64_be(address), 1);
+                db.dae_put::<ContractLogs>(key, TxLogs::default()).unwrap();
+            }
+            assert_eq!(db.dae_cursor::<ContractLogs>().unwrap().walk(..).unwrap().count(), 3);
+        }
+
+        let db = init_db_with_options(&path, &options).unwrap();
+        assert_eq!(db.dae_get::<Headers>(1).unwrap(), Some(Header::default()));
+    }
+}
@@ -0,0 +1,616 @@
+//! Migrations of databases written by older versions of the engine.
+//!
+//! Every change to the schema bumps [DB_VERSION] and registers a [Migration] from the previous
+//! version in [MIGRATIONS], [init_db](crate::init_db) applies the pending ones when it opens an
+//! older database.
+
+use std::{path::Path, sync::Arc};
+
+use eyre::Context;
+use rocksdb::BoundColumnFamily;
+use simp_primitives::{BlockNumber, LogNumber, StoredLog, TransactionSigned, TxNumber};
+
+use crate::{
+    implementation::dae_rocksdb::{cf_handle, cursor_read_options, Database},
+    table::{Compress, Decode, Decompress, Encode, Table},
+    tables::{
+        codecs::ZSTD_MAGIC,
+        models::{Timeframe, TxLogId},
+        utils::encode_dup_key,
+        AddressTraces, DecodedLogs, Logs, MarketVolumetricsIndices, Receipts, TempPeriodVolumes,
+        TimestampVolumetricsIndices, Traces, Transactions, TxTraces, Volumetrics,
+    },
+    version::{write_db_version_file, DB_VERSION},
+};
+
+/// Number of entries rewritten by a single transaction.
+const BATCH_SIZE: usize = 10_000;
+
+/// A step bringing a database from version `from` to version `to`.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Version of the database the migration applies to.
+    pub from: u64,
+    /// Version of the database once the migration ran.
+    pub to: u64,
+    /// Short description of the schema change.
+    pub name: &'static str,
+    /// Applies the schema change.
+    pub run: fn(&Database) -> eyre::Result<()>,
+}
+
+/// Every migration, in the order they have to be applied.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        to: 2,
+        name: "key Logs and DecodedLogs on binary TxLogId",
+        run: rekey_logs,
+    },
+    Migration {
+        from: 2,
+        to: 3,
+        name: "merge the volumetric tables of every timeframe",
+        run: merge_volumetric_tables,
+    },
+    Migration {
+        from: 3,
+        to: 4,
+        name: "add the Receipts table",
+        run: create_table::<Receipts>,
+    },
+    Migration {
+        from: 4,
+        to: 5,
+        name: "compress Transactions with the transaction dictionary",
+        run: compress_transactions,
+    },
+    Migration {
+        from: 5,
+        to: 6,
+        name: "add the Traces and AddressTraces tables",
+        run: create_traces_tables,
+    },
+    Migration {
+        from: 6,
+        to: 7,
+        name: "merge the temporary period volume tables of every timeframe",
+        run: merge_temp_volume_tables,
+    },
+    Migration {
+        from: 7,
+        to: 8,
+        name: "store AddressTraces as one entry per transaction",
+        run: rebuild_address_traces,
+    },
+];
+
+/// Returns the migrations bringing a database at `version` up to [DB_VERSION], or `None` if there
+/// is no such path.
+pub fn migrations_from(version: u64) -> Option<Vec<Migration>> {
+    let mut steps = Vec::new();
+    let mut current = version;
+
+    while current != DB_VERSION {
+        let step = MIGRATIONS.iter().find(|migration| migration.from == current)?;
+        current = step.to;
+        steps.push(*step);
+    }
+
+    Some(steps)
+}
+
+/// Applies the migrations bringing the database at `path` from `version` up to [DB_VERSION].
+///
+/// The version file is written after every step, so an interrupted run resumes from the last
+/// completed migration.
+pub fn migrate<P: AsRef<Path>>(
+    db: &Database,
+    path: P,
+    version: u64,
+) -> eyre::Result<()> {
+    let steps = migrations_from(version)
+        .ok_or_else(|| eyre::eyre!("No migration path from database version {version}"))?;
+
+    for step in steps {
+        (step.run)(db).wrap_err_with(|| {
+            format!("Migration from v{} to v{} ({}) failed", step.from, step.to, step.name)
+        })?;
+        write_db_version_file(path.as_ref(), step.to)?;
+    }
+
+    Ok(())
+}
+
+/// What [rewrite_entries] does with an entry.
+#[derive(Debug)]
+pub enum Rewrite {
+    /// Leaves the entry untouched.
+    Keep,
+    /// Replaces the entry, the old key is removed if it differs from the new one.
+    Replace(Vec<u8>, Vec<u8>),
+    /// Removes the entry.
+    Delete,
+}
+
+/// Rewrites the entries of the column family `name`, which re-keys a table or changes the
+/// encoding of its values.
+///
+/// `f` is called with the raw key and value of every entry, entries written by it are not visited
+/// again.
+pub fn rewrite_entries(
+    db: &Database,
+    name: &str,
+    mut f: impl FnMut(&[u8], &[u8]) -> eyre::Result<Rewrite>,
+) -> eyre::Result<()> {
+    let cf = column_family(db, name)?;
+
+    // the iterator reads a snapshot, it never sees the keys written while migrating
+    let mut iter = db.raw_iterator_cf_opt(&cf, cursor_read_options());
+    iter.seek_to_first();
+
+    let mut tx = db.transaction();
+    let mut pending = 0;
+
+    while let Some((key, value)) = iter.item() {
+        match f(key, value)? {
+            Rewrite::Keep => {}
+            Rewrite::Replace(new_key, new_value) => {
+                if new_key.as_slice() != key {
+                    tx.delete_cf(&cf, key)?;
+                }
+                tx.put_cf(&cf, new_key, new_value)?;
+                pending += 1;
+            }
+            Rewrite::Delete => {
+                tx.delete_cf(&cf, key)?;
+                pending += 1;
+            }
+        }
+
+        if pending == BATCH_SIZE {
+            tx.commit()?;
+            tx = db.transaction();
+            pending = 0;
+        }
+
+        iter.next();
+    }
+    iter.status()?;
+
+    tx.commit().wrap_err_with(|| format!("Could not rewrite table {name}"))
+}
+
+/// Copies every entry of the column family `from` into `to`, under the key returned by `map_key`.
+/// Used to merge or split tables.
+pub fn copy_entries(
+    db: &Database,
+    from: &str,
+    to: &str,
+    map_key: impl Fn(&[u8]) -> Vec<u8>,
+) -> eyre::Result<()> {
+    let from_cf = column_family(db, from)?;
+    let to_cf = column_family(db, to)?;
+
+    let mut iter = db.raw_iterator_cf_opt(&from_cf, cursor_read_options());
+    iter.seek_to_first();
+
+    let mut tx = db.transaction();
+    let mut pending = 0;
+
+    while let Some((key, value)) = iter.item() {
+        tx.put_cf(&to_cf, map_key(key), value)?;
+        pending += 1;
+
+        if pending == BATCH_SIZE {
+            tx.commit()?;
+            tx = db.transaction();
+            pending = 0;
+        }
+
+        iter.next();
+    }
+    iter.status()?;
+
+    tx.commit().wrap_err_with(|| format!("Could not copy table {from} into {to}"))
+}
+
+/// Creates the column family of a table added to the schema, if it doesn't exist yet.
+pub fn create_table<T: Table>(db: &Database) -> eyre::Result<()> {
+    if db.cf_handle(T::NAME).is_none() {
+        db.create_cf(T::NAME, &db.table_options(T::NAME))?;
+    }
+
+    Ok(())
+}
+
+/// Drops the column family of a table removed from the schema.
+pub fn drop_table(db: &Database, name: &str) -> eyre::Result<()> {
+    if db.cf_handle(name).is_some() {
+        db.drop_cf(name)?;
+    }
+
+    Ok(())
+}
+
+fn column_family<'a>(
+    db: &'a Database,
+    name: &str,
+) -> eyre::Result<Arc<BoundColumnFamily<'a>>> {
+    db.cf_handle(name).ok_or_else(|| eyre::eyre!("Missing column family {name}"))
+}
+
+/// Re-keys the [Logs] and [DecodedLogs] tables from the `"{tx}_{log}"` strings of version 1 to
+/// binary [TxLogId]s.
+fn rekey_logs(db: &Database) -> eyre::Result<()> {
+    let logs_cf = cf_handle::<Logs>(db)?;
+
+    // the block of a decoded log is only known through its raw log, so decoded logs go first
+    rekey_legacy_logs::<DecodedLogs>(db, |legacy_key, _| {
+        db.get_cf(&logs_cf, legacy_key)?
+            .map(|raw| eyre::Ok(StoredLog::decompress(raw)?.block_number))
+            .transpose()
+    })?;
+
+    rekey_legacy_logs::<Logs>(db, |_, value| Ok(Some(StoredLog::decompress(value)?.block_number)))
+}
+
+/// Moves every value of `T` stored under a legacy key to its [TxLogId], `block_of` resolves the
+/// block of the log from the legacy key and the value.
+fn rekey_legacy_logs<T: Table<Key = TxLogId>>(
+    db: &Database,
+    block_of: impl Fn(&[u8], &[u8]) -> eyre::Result<Option<BlockNumber>>,
+) -> eyre::Result<()> {
+    rewrite_entries(db, T::NAME, |key, value| {
+        let (tx, log) = match parse_legacy_key(key) {
+            Some(id) => id,
+            None => return Ok(Rewrite::Keep),
+        };
+
+        // a decoded log without its raw log can't be placed anymore, it is dropped
+        Ok(match block_of(key, value)? {
+            Some(block_number) => {
+                let id = TxLogId { tx, block_number, log };
+                Rewrite::Replace(id.encode().to_vec(), value.to_vec())
+            }
+            None => Rewrite::Delete,
+        })
+    })
+}
+
+/// Tables of version 2 holding the volumetrics of a single timeframe, with the table of version 3
+/// they were merged into.
+const TIMEFRAME_TABLES: [(&str, Timeframe, &str); 9] = [
+    ("VolumetricsFiveMin", Timeframe::FiveMinute, Volumetrics::NAME),
+    ("VolumetricsHour", Timeframe::Hourly, Volumetrics::NAME),
+    ("VolumetricsDay", Timeframe::Daily, Volumetrics::NAME),
+    ("MarketVolumetricsIndicesFiveMin", Timeframe::FiveMinute, MarketVolumetricsIndices::NAME),
+    ("MarketVolumetricsIndicesHour", Timeframe::Hourly, MarketVolumetricsIndices::NAME),
+    ("MarketVolumetricsIndicesDay", Timeframe::Daily, MarketVolumetricsIndices::NAME),
+    ("TimestampVolumetricsIndicesFiveMin", Timeframe::FiveMinute, TimestampVolumetricsIndices::NAME),
+    ("TimestampVolumetricsIndicesHour", Timeframe::Hourly, TimestampVolumetricsIndices::NAME),
+    ("TimestampVolumetricsIndicesDay", Timeframe::Daily, TimestampVolumetricsIndices::NAME),
+];
+
+/// Moves the volumetric tables of every timeframe into shared tables keyed by
+/// [TimeframeKey](crate::tables::models::TimeframeKey).
+fn merge_volumetric_tables(db: &Database) -> eyre::Result<()> {
+    create_table::<Volumetrics>(db)?;
+    create_table::<MarketVolumetricsIndices>(db)?;
+    create_table::<TimestampVolumetricsIndices>(db)?;
+
+    merge_timeframe_tables(db, &TIMEFRAME_TABLES)
+}
+
+/// Tables of version 6 holding the temporary period volumes of a single timeframe, with the table
+/// of version 7 they were merged into.
+const TEMP_TIMEFRAME_TABLES: [(&str, Timeframe, &str); 2] = [
+    ("TempPeriodVolumesFive", Timeframe::FiveMinute, TempPeriodVolumes::NAME),
+    ("TempPeriodVolumesHour", Timeframe::Hourly, TempPeriodVolumes::NAME),
+];
+
+/// Moves the temporary period volume tables of every timeframe into [TempPeriodVolumes].
+fn merge_temp_volume_tables(db: &Database) -> eyre::Result<()> {
+    create_table::<TempPeriodVolumes>(db)?;
+
+    merge_timeframe_tables(db, &TEMP_TIMEFRAME_TABLES)
+}
+
+/// Copies the `(legacy, timeframe, table)` tables into `table`, prefixing their keys with the id
+/// of `timeframe`, then drops them.
+fn merge_timeframe_tables(db: &Database, tables: &[(&str, Timeframe, &str)]) -> eyre::Result<()> {
+    for (legacy, timeframe, table) in tables {
+        // already merged by an interrupted run
+        if db.cf_handle(legacy).is_none() {
+            continue
+        }
+
+        copy_entries(db, legacy, table, |key| {
+            let mut new_key = vec![timeframe.id()];
+            new_key.extend_from_slice(key);
+            new_key
+        })?;
+        drop_table(db, legacy)?;
+    }
+
+    Ok(())
+}
+
+/// Compresses the [Transactions] written before version 5 with the transaction dictionary.
+///
+/// Transactions already compressed by an interrupted run are kept as they are.
+fn compress_transactions(db: &Database) -> eyre::Result<()> {
+    rewrite_entries(db, Transactions::NAME, |key, value| {
+        if value.starts_with(&ZSTD_MAGIC) {
+            return Ok(Rewrite::Keep)
+        }
+
+        Ok(Rewrite::Replace(key.to_vec(), TransactionSigned::decompress(value)?.compress()))
+    })
+}
+
+fn create_traces_tables(db: &Database) -> eyre::Result<()> {
+    create_table::<Traces>(db)?;
+    create_table::<AddressTraces>(db)
+}
+
+/// Rebuilds [AddressTraces] from the [Traces] it indexes, its shards of transaction numbers of
+/// version 7 became one entry per transaction.
+///
+/// The table is dropped first, so an interrupted run starts over.
+fn rebuild_address_traces(db: &Database) -> eyre::Result<()> {
+    drop_table(db, AddressTraces::NAME)?;
+    create_table::<AddressTraces>(db)?;
+
+    let traces_cf = column_family(db, Traces::NAME)?;
+    let address_traces_cf = column_family(db, AddressTraces::NAME)?;
+
+    let mut iter = db.raw_iterator_cf_opt(&traces_cf, cursor_read_options());
+    iter.seek_to_first();
+
+    let mut tx = db.transaction();
+    let mut pending = 0;
+
+    while let Some((key, value)) = iter.item() {
+        let number = TxNumber::decode(key)?;
+
+        for address in TxTraces::decompress(value)?.internal_call_addresses() {
+            let dup_key = encode_dup_key::<AddressTraces>(address, number);
+            tx.put_cf(&address_traces_cf, dup_key, number.compress())?;
+            pending += 1;
+        }
+
+        // a transaction may add several entries, the batch is committed once it is full
+        if pending >= BATCH_SIZE {
+            tx.commit()?;
+            tx = db.transaction();
+            pending = 0;
+        }
+
+        iter.next();
+    }
+    iter.status()?;
+
+    tx.commit().wrap_err("Could not rebuild table AddressTraces")
+}
+
+/// Parses a `"{tx}_{log}"` key of version 1.
+fn parse_legacy_key(key: &[u8]) -> Option<(TxNumber, LogNumber)> {
+    let (tx, log) = std::str::from_utf8(key).ok()?.split_once('_')?;
+
+    Some((tx.parse().ok()?, log.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        init_db,
+        test_utils::ERROR_TEMPDIR,
+        transaction::DbTx,
+        tables::{
+            models::{TimeframeKey, VolumeKeys},
+            ShardedKey,
+        },
+        version::{db_version_file_path, get_db_version},
+    };
+    use hex_literal::hex;
+    use rocksdb::Options;
+    use simp_primitives::{
+        IntegerList, PeriodVolumes, Signature, StoredDecodedData, Trace, Transaction,
+        TransactionKind, TxLegacy, H160, H256, U256,
+    };
+    use sip_codecs::Compact;
+
+    #[test]
+    fn rekeys_legacy_log_ids() {
+        let path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+
+        {
+            let db = init_db(&path).unwrap();
+            let log = StoredLog { block_number: 7, ..Default::default() };
+
+            db.put_cf(&cf_handle::<Logs>(&db).unwrap(), "12_3", log.compress()).unwrap();
+            db.put_cf(
+                &cf_handle::<DecodedLogs>(&db).unwrap(),
+                "12_3",
+                StoredDecodedData::default().compress(),
+            )
+            .unwrap();
+        }
+        std::fs::write(db_version_file_path(&path), "1").unwrap();
+
+        let db = init_db(&path).unwrap();
+        let id = TxLogId { tx: 12, block_number: 7, log: 3 };
+
+        assert_eq!(db.dae_get::<Logs>(id).unwrap().map(|log| log.block_number), Some(7));
+        assert!(db.dae_get::<DecodedLogs>(id).unwrap().is_some());
+        assert_eq!(db.dae_entries::<Logs>().unwrap(), 1);
+        assert_eq!(get_db_version(&path).unwrap(), DB_VERSION);
+    }
+
+    #[test]
+    fn merges_volumetric_timeframes() {
+        let path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+
+        {
+            let db = init_db(&path).unwrap();
+            db.create_cf("TimestampVolumetricsIndicesDay", &Options::default()).unwrap();
+
+            let value = VolumeKeys { volume_keys: vec![1, 2] };
+            let cf = db.cf_handle("TimestampVolumetricsIndicesDay").unwrap();
+            db.put_cf(&cf, 10u64.encode(), value.compress()).unwrap();
+        }
+        std::fs::write(db_version_file_path(&path), "2").unwrap();
+
+        let db = init_db(&path).unwrap();
+        let key = TimeframeKey::new(Timeframe::Daily, 10u64);
+
+        assert_eq!(
+            db.dae_get::<TimestampVolumetricsIndices>(key).unwrap(),
+            Some(VolumeKeys { volume_keys: vec![1, 2] })
+        );
+        assert!(db.cf_handle("TimestampVolumetricsIndicesDay").is_none());
+    }
+
+    #[test]
+    fn merges_temp_period_volumes() {
+        let path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+        let key = ShardedKey::new(H256::repeat_byte(0x11), 600);
+        let volumes = PeriodVolumes { volumes: Vec::new() };
+
+        {
+            let db = init_db(&path).unwrap();
+            db.create_cf("TempPeriodVolumesHour", &Options::default()).unwrap();
+
+            let cf = db.cf_handle("TempPeriodVolumesHour").unwrap();
+            db.put_cf(&cf, key.clone().encode(), volumes.clone().compress()).unwrap();
+        }
+        std::fs::write(db_version_file_path(&path), "6").unwrap();
+
+        let db = init_db(&path).unwrap();
+
+        assert_eq!(
+            db.dae_get::<TempPeriodVolumes>(TimeframeKey::new(Timeframe::Hourly, key.clone()))
+                .unwrap(),
+            Some(volumes)
+        );
+        assert_eq!(
+            db.dae_get::<TempPeriodVolumes>(TimeframeKey::new(Timeframe::FiveMinute, key)).unwrap(),
+            None
+        );
+        assert!(db.cf_handle("TempPeriodVolumesHour").is_none());
+    }
+
+    #[test]
+    fn rebuilds_address_traces() {
+        let path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+        let callee = H160::repeat_byte(3);
+        let traces = TxTraces {
+            traces: vec![
+                Trace {
+                    from: H160::repeat_byte(1),
+                    to: Some(H160::repeat_byte(2)),
+                    ..Default::default()
+                },
+                Trace {
+                    from: H160::repeat_byte(2),
+                    to: Some(callee),
+                    trace_address: vec![0],
+                    ..Default::default()
+                },
+            ],
+        };
+
+        {
+            let db = init_db(&path).unwrap();
+            db.dae_put::<Traces>(4, traces).unwrap();
+
+            // the last shard of the callee as stored by version 7
+            db.put_cf(
+                &cf_handle::<AddressTraces>(&db).unwrap(),
+                ShardedKey::last(callee).encode(),
+                IntegerList::from(vec![4u64]).compress(),
+            )
+            .unwrap();
+        }
+        std::fs::write(db_version_file_path(&path), "7").unwrap();
+
+        let db = init_db(&path).unwrap();
+        let entries = |address| {
+            db.dae_dup_cursor::<AddressTraces>()
+                .unwrap()
+                .walk_dup(address, None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(entries(callee), vec![(4, 4)]);
+        assert_eq!(entries(H160::repeat_byte(2)), vec![(4, 4)]);
+        assert!(entries(H160::repeat_byte(1)).is_empty());
+    }
+
+    #[test]
+    fn compresses_plain_transactions() {
+        // a legacy transaction as stored by version 4
+        const PLAIN: [u8; 197] = hex!(
+            // flags, hash, r and s of the signature
+            "011111111111111111111111111111111111111111111111111111111111111111"
+            "0100000000000000000000000000000000000000000000000000000000000000"
+            "0200000000000000000000000000000000000000000000000000000000000000"
+            // legacy variant, then the flags and fields of the legacy transaction
+            "00114522011111111111111111111111111111111111111111111111111111111111111111"
+            "0122222222222222222222222222222222222222220904a817c80052083333333333333333333333"
+            "3333333333333333330de0b6b3a7640000b443deadbeef"
+        );
+        let expected = TransactionSigned {
+            hash: H256::repeat_byte(0x11),
+            signature: Signature { r: U256::from(1), s: U256::from(2), odd_y_parity: true },
+            transaction: Transaction::Legacy(TxLegacy {
+                hash: H256::repeat_byte(0x11),
+                chain_id: 1,
+                from: H160::repeat_byte(0x22),
+                nonce: 9,
+                gas_price: 20_000_000_000,
+                gas_limit: 21_000,
+                to: TransactionKind::Call(H160::repeat_byte(0x33)),
+                value: 1_000_000_000_000_000_000,
+                block_number: 46_147,
+                transaction_index: 0,
+                input: vec![0xde, 0xad, 0xbe, 0xef].into(),
+            }),
+        };
+        assert_eq!(TransactionSigned::from_compact(&PLAIN, PLAIN.len()).0, expected);
+
+        let path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+
+        {
+            let db = init_db(&path).unwrap();
+            db.put_cf(&cf_handle::<Transactions>(&db).unwrap(), 5u64.encode(), PLAIN).unwrap();
+        }
+        std::fs::write(db_version_file_path(&path), "4").unwrap();
+
+        let db = init_db(&path).unwrap();
+        let stored =
+            db.get_cf(&cf_handle::<Transactions>(&db).unwrap(), 5u64.encode()).unwrap().unwrap();
+
+        assert!(stored.starts_with(&ZSTD_MAGIC) && stored.len() < PLAIN.len());
+        assert_eq!(db.dae_get::<Transactions>(5).unwrap(), Some(expected.clone()));
+
+        // a second run, as after an interruption, leaves the compressed transactions untouched
+        compress_transactions(&db).unwrap();
+        assert_eq!(db.dae_get::<Transactions>(5).unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn every_version_reaches_latest() {
+        for migration in MIGRATIONS {
+            assert!(migration.from < migration.to);
+            assert!(migrations_from(migration.from).is_some());
+        }
+
+        assert!(migrations_from(DB_VERSION).unwrap().is_empty());
+        assert!(migrations_from(0).is_none());
+    }
+}
@@ -0,0 +1,137 @@
+//! Options used to open the database.
+
+use rocksdb::{BlockBasedOptions, Cache, DBCompressionType as RocksCompressionType, Options, SliceTransform};
+use serde::{Deserialize, Serialize};
+
+use crate::tables::Tables;
+
+/// Options used to open the database, see [init_db_with_options](crate::init_db_with_options)
+/// and [open_db_secondary](crate::open_db_secondary).
+#[derive(Debug, Clone)]
+pub struct DatabaseOptions {
+    // The access type of blockstore. Default: Primary
+    pub access_type: AccessType,
+    /// Compression of every table without an entry in `table_compression`. Default: Snappy, the
+    /// RocksDB default.
+    pub compression: DatabaseCompressionType,
+    /// Compression of specific tables.
+    pub table_compression: Vec<(Tables, DatabaseCompressionType)>,
+    /// Size in bytes of the block cache shared by every table, `None` keeps the RocksDB default.
+    pub block_cache_size: Option<usize>,
+    /// Bits per key of the bloom filters of the tables read by key prefix, `None` disables them.
+    pub bloom_filter_bits: Option<f64>,
+    /// Maximum number of files kept open by RocksDB, `-1` keeps every file open. Default: -1
+    pub max_open_files: i32,
+}
+
+impl Default for DatabaseOptions {
+    fn default() -> Self {
+        Self {
+            access_type: AccessType::Primary,
+            compression: DatabaseCompressionType::default(),
+            table_compression: Vec::new(),
+            block_cache_size: None,
+            bloom_filter_bits: Some(10.0),
+            max_open_files: -1,
+        }
+    }
+}
+
+impl DatabaseOptions {
+    /// Options of the primary database, it creates the database and its missing column
+    /// families.
+    pub(crate) fn db_options(&self) -> Options {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        opts.set_max_open_files(self.max_open_files);
+
+        opts
+    }
+
+    /// Options of a secondary instance, it only opens what the primary created.
+    pub(crate) fn secondary_db_options(&self) -> Options {
+        let mut opts = Options::default();
+        // secondary instances have to keep every file of the primary open
+        opts.set_max_open_files(-1);
+
+        opts
+    }
+
+    /// Block cache shared by the column families of the database.
+    pub(crate) fn block_cache(&self) -> Option<Cache> {
+        self.block_cache_size.map(Cache::new_lru_cache)
+    }
+
+    /// Options of the column family `name`, names that aren't part of [Tables] get the default
+    /// compression only.
+    pub(crate) fn table_options(&self, name: &str, cache: Option<&Cache>) -> Options {
+        let table = name.parse::<Tables>().ok();
+
+        let compression = table
+            .and_then(|table| {
+                self.table_compression.iter().find(|(t, _)| *t == table).map(|(_, c)| c)
+            })
+            .unwrap_or(&self.compression);
+
+        let mut opts = Options::default();
+        opts.set_compression_type(compression.to_rocksdb_compression_type());
+
+        let mut block_opts = BlockBasedOptions::default();
+        if let Some(cache) = cache {
+            block_opts.set_block_cache(cache);
+        }
+
+        if let (Some(bits), Some(prefix_len)) =
+            (self.bloom_filter_bits, table.and_then(|table| table.key_prefix_len()))
+        {
+            // whole keys are filtered too, point lookups keep using the bloom filter
+            block_opts.set_bloom_filter(bits, false);
+            block_opts.set_whole_key_filtering(true);
+            opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(prefix_len));
+        }
+
+        opts.set_block_based_table_factory(&block_opts);
+
+        opts
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccessType {
+    /// Primary (read/write) access; only one process can have Primary access.
+    Primary,
+    /// Secondary (read) access; multiple processes can have Secondary access.
+    /// Additionally, Secondary access can be obtained while another process
+    /// already has Primary access.
+    Secondary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseCompressionType {
+    None,
+    Snappy,
+    Lz4,
+    Zlib,
+    Zstd,
+}
+
+impl Default for DatabaseCompressionType {
+    /// Snappy, the RocksDB default.
+    fn default() -> Self {
+        Self::Snappy
+    }
+}
+
+impl DatabaseCompressionType {
+    pub(crate) fn to_rocksdb_compression_type(&self) -> RocksCompressionType {
+        match self {
+            Self::None => RocksCompressionType::None,
+            Self::Snappy => RocksCompressionType::Snappy,
+            Self::Lz4 => RocksCompressionType::Lz4,
+            Self::Zlib => RocksCompressionType::Zlib,
+            Self::Zstd => RocksCompressionType::Zstd,
+        }
+    }
+}
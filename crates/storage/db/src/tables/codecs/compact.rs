@@ -1,6 +1,6 @@
 use crate::{
-    table::{Compress, Decompress},
-    tables::models::*,
+    table::{Compress, Decompress, Table},
+    tables::{models::*, Transactions},
 };
 use sip_codecs::{main_codec, Compact};
 use simp_primitives::*;
@@ -38,13 +38,14 @@ impl_compression_for_compact!(
     AbiData,
     TxLogs,
     TxLogId,
+    TxTraces,
     StoredLog,
     StoredDecodedData,
     ContractData,
     TxType,
     StoredContract,
     BlockBodyIndices,
-    TransactionSigned,
+    Receipt,
     CompactU256,
     VolumeKeyWithData,
     Volumetric,
@@ -56,6 +57,59 @@ impl_compression_for_compact!(
     PeriodVolumes
 );
 
+/// Magic number opening every zstd frame. Plain [TransactionSigned]s start with their flags byte,
+/// which is at most `1`.
+pub(crate) const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Upper bound of a decompressed [TransactionSigned], well above the calldata a block can hold.
+/// Frames claiming more are corrupted.
+const MAX_TRANSACTION_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Transactions are stored zstd compressed with the transaction dictionary since database version
+/// 5, the migration to it compresses the ones written before.
+impl Compress for TransactionSigned {
+    type Compressed = Vec<u8>;
+
+    fn compress_to_buf<B: bytes::BufMut + AsMut<[u8]>>(self, buf: &mut B) {
+        let mut plain = Vec::with_capacity(300);
+        let _ = Compact::to_compact(self, &mut plain);
+
+        TRANSACTION_COMPRESSOR.with(|compressor| match compressor.borrow_mut().compress(&plain) {
+            Ok(compressed) => buf.put_slice(&compressed),
+            // kept plain, decompressing tells both apart by the zstd magic number
+            Err(_) => buf.put_slice(&plain),
+        });
+    }
+}
+
+impl Decompress for TransactionSigned {
+    fn decompress<B: AsRef<[u8]>>(value: B) -> Result<TransactionSigned, DatabaseError> {
+        let value = value.as_ref();
+        if !value.starts_with(&ZSTD_MAGIC) {
+            return decode_transaction(value)
+        }
+
+        // the compressor writes the content size into every frame
+        let size = match zstd::zstd_safe::get_frame_content_size(value) {
+            Ok(Some(size)) if size <= MAX_TRANSACTION_SIZE => size as usize,
+            _ => return Err(DatabaseError::Codec(Transactions::NAME)),
+        };
+
+        let plain = TRANSACTION_DECOMPRESSOR
+            .with(|decompressor| decompressor.borrow_mut().decompress(value, size))
+            .map_err(|_| DatabaseError::Codec(Transactions::NAME))?;
+
+        decode_transaction(&plain)
+    }
+}
+
+/// Decodes a plain [TransactionSigned]. The compact decoder panics on truncated input, which is
+/// reported as a corrupted entry instead.
+fn decode_transaction(plain: &[u8]) -> Result<TransactionSigned, DatabaseError> {
+    std::panic::catch_unwind(|| TransactionSigned::from_compact(plain, plain.len()).0)
+        .map_err(|_| DatabaseError::Codec(Transactions::NAME))
+}
+
 macro_rules! impl_compression_fixed_compact {
     ($($name:tt),+) => {
         $(
@@ -123,3 +177,29 @@ macro_rules! add_wrapper_struct {
 
 add_wrapper_struct!((U256, CompactU256));
 add_wrapper_struct!((u64, CompactU64));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrupted_transactions_are_codec_errors() {
+        let transaction = TransactionSigned { hash: H256::repeat_byte(0x11), ..Default::default() };
+
+        let compressed = transaction.clone().compress();
+        assert!(compressed.starts_with(&ZSTD_MAGIC));
+        assert_eq!(TransactionSigned::decompress(&compressed).unwrap(), transaction);
+
+        let mut plain = Vec::new();
+        let _ = transaction.clone().to_compact(&mut plain);
+        assert_eq!(TransactionSigned::decompress(&plain).unwrap(), transaction);
+
+        // a truncated frame, a frame header without content and a truncated plain transaction
+        for corrupted in [&compressed[..compressed.len() - 1], &ZSTD_MAGIC[..], &plain[..10]] {
+            assert_eq!(
+                TransactionSigned::decompress(corrupted),
+                Err(DatabaseError::Codec(Transactions::NAME))
+            );
+        }
+    }
+}
@@ -1,5 +1,6 @@
 mod compact;
 pub use compact::CompactU256;
+pub(crate) use compact::ZSTD_MAGIC;
 
 mod scale;
 mod postcard;
\ No newline at end of file
@@ -5,16 +5,16 @@ pub mod codecs;
 pub mod models;
 use simp_primitives::{
     Address, BlockHash, BlockNumber, Header, Market, MarketAddress, PeriodVolumes, Protocol,
-    StoredDecodedData, StoredLog, TokenMarkets, TransactionSigned, TxHash, TxNumber, VolumeKey,
+    Receipt, StoredDecodedData, StoredLog, TokenMarkets, TransactionSigned, TxHash, TxNumber, VolumeKey,
     Volumetric, H256,
 };
 pub mod utils;
 pub use models::{
     sharded_key::ShardedKey, AbiData, BlockBodyIndices, ContractData, LogIndices, StoredContract,
-    TxIndices, TxLogs,
+    TxIndices, TxLogs, TxTraces,
 };
 
-use self::models::{VolumeKeys, VolumeKeysWithData};
+use self::models::{TimeframeKey, TxLogId, VolumeKeys, VolumeKeysWithData};
 /// Enum for the types of tables present in rocksdb.
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum TableType {
@@ -25,7 +25,7 @@ pub enum TableType {
 }
 
 /// Number of tables that should be present inside database.
-pub const NUM_TABLES: usize = 33;
+pub const NUM_TABLES: usize = 30;
 
 pub trait TableViewer<R> {
     /// type of error to return
@@ -112,34 +112,47 @@ tables!([
     (Transactions, TableType::Table),
     (TransactionBlock, TableType::Table),
     (TxHashNumber, TableType::Table),
+    (Receipts, TableType::Table),
     (TransactionLogs, TableType::Table),
     (BlockLogs, TableType::Table),
     (ContractLogs, TableType::Table),
     (Logs, TableType::Table),
     (DecodedLogs, TableType::Table),
+    (Traces, TableType::Table),
+    (AddressTraces, TableType::DupSort),
     (ContractProxy, TableType::Table),
     (ContractsData, TableType::Table),
     (MarketToProxy, TableType::Table),
     (Abi, TableType::Table),
     (UnknownContracts, TableType::Table),
     (TrackedContracts, TableType::Table),
-    (VolumetricsFiveMin, TableType::Table),
-    (VolumetricsHour, TableType::Table),
-    (VolumetricsDay, TableType::Table),
-    (MarketVolumetricsIndicesFiveMin, TableType::Table),
-    (MarketVolumetricsIndicesHour, TableType::Table),
-    (MarketVolumetricsIndicesDay, TableType::Table),
-    (TimestampVolumetricsIndicesFiveMin, TableType::Table),
-    (TimestampVolumetricsIndicesHour, TableType::Table),
-    (TimestampVolumetricsIndicesDay, TableType::Table),
+    (Volumetrics, TableType::Table),
+    (MarketVolumetricsIndices, TableType::Table),
+    (TimestampVolumetricsIndices, TableType::Table),
     (Protocols, TableType::Table),
     (MarketProtocol, TableType::Table),
     (TokensMarkets, TableType::Table),
-    (TempPeriodVolumesFive, TableType::Table),
-    (TempPeriodVolumesHour, TableType::Table),
+    (TempPeriodVolumes, TableType::Table),
     (SyncStage, TableType::Table)
 ]);
 
+impl Tables {
+    /// Length of the key prefix the table is read by, its entries are filtered on it.
+    pub const fn key_prefix_len(&self) -> Option<usize> {
+        match self {
+            // sharded by contract address
+            Tables::ContractLogs => Some(20),
+            // sharded by timeframe and market address
+            Tables::MarketVolumetricsIndices | Tables::TempPeriodVolumes => Some(33),
+            // logs of a transaction
+            Tables::Logs | Tables::DecodedLogs => Some(8),
+            // transactions of an address
+            Tables::AddressTraces => Some(20),
+            _ => None,
+        }
+    }
+}
+
 #[macro_export]
 /// Macro to declare key value table.
 macro_rules! table {
@@ -175,13 +188,13 @@ macro_rules! table {
 /// Macro to declare duplicate key value table.
 macro_rules! dupsort {
     ($(#[$docs:meta])+ ( $table_name:ident ) $key:ty | [$subkey:ty] $value:ty) => {
-        table!(
+        $crate::table!(
             $(#[$docs])+
             ///
             #[doc = concat!("`DUPSORT` table with subkey being: [`", stringify!($subkey), "`].")]
             ( $table_name ) $key | $value
         );
-        impl DupSort for $table_name {
+        impl $crate::table::DupSort for $table_name {
             type SubKey = $subkey;
         }
     };
@@ -222,6 +235,10 @@ table!(
     /// Stores the mapping of the transaction hash to the transaction number.
     ( TxHashNumber ) TxHash | TxNumber
 );
+table!(
+    /// (Canonical only) Stores the receipt of canonical transactions.
+    ( Receipts ) TxNumber | Receipt
+);
 
 // Logs tables
 table!(
@@ -241,19 +258,24 @@ table!(
 
 table!(
     /// stores the id to its logs
-    ( Logs ) String | StoredLog
+    ( Logs ) TxLogId | StoredLog
 );
 
-// TODO: traces are just listened to druing sync but not stored
-
-// table!(
-//     /// stores transaction traces
-//     ( Traces ) String | String
-// );
-
 table!(
     /// stores the id to its decoded log data
-    ( DecodedLogs ) String | StoredDecodedData
+    ( DecodedLogs ) TxLogId | StoredDecodedData
+);
+
+// Traces tables
+table!(
+    /// Stores the calls of a transaction.
+    ( Traces ) TxNumber | TxTraces
+);
+
+dupsort!(
+    /// Stores the transactions making internal calls from or to an address, the value repeats the
+    /// transaction number.
+    ( AddressTraces ) Address | [TxNumber] TxNumber
 );
 
 // ABI tables
@@ -289,43 +311,21 @@ table!(
     ( TrackedContracts ) Address | u32
 );
 
-table!(
-    /// stores volumetric 5m
-    ( VolumetricsFiveMin ) VolumeKey | Volumetric
-);
-table!(
-    /// stores volumetric 1 hour
-    ( VolumetricsHour ) VolumeKey | Volumetric
-);
-table!(
-    /// stores volumetric 1 day
-    ( VolumetricsDay ) VolumeKey | Volumetric
-);
+// Volumetric tables, shared by every timeframe
 
 table!(
-    /// stores marketAddress - volume  keys 5 min
-    ( MarketVolumetricsIndicesFiveMin ) ShardedKey<MarketAddress> | VolumeKeysWithData
-);
-table!(
-    /// stores marketAddress - volume  keys 1 hour
-    ( MarketVolumetricsIndicesHour ) ShardedKey<MarketAddress> | VolumeKeysWithData
-);
-table!(
-    /// stores marketAddress - volume  keys day
-    ( MarketVolumetricsIndicesDay ) ShardedKey<MarketAddress> | VolumeKeysWithData
+    /// stores the volumetrics of every timeframe
+    ( Volumetrics ) TimeframeKey<VolumeKey> | Volumetric
 );
 
 table!(
-    /// stores timestamp - volume  keys 5 min
-    ( TimestampVolumetricsIndicesFiveMin ) u64 | VolumeKeys
-);
-table!(
-    /// stores timestamp - volume  keys 1 hour
-    ( TimestampVolumetricsIndicesHour ) u64 | VolumeKeys
+    /// stores marketAddress - volume keys of every timeframe
+    ( MarketVolumetricsIndices ) TimeframeKey<ShardedKey<MarketAddress>> | VolumeKeysWithData
 );
+
 table!(
-    /// stores timestamp - volume  keys day
-    ( TimestampVolumetricsIndicesDay ) u64 | VolumeKeys
+    /// stores timestamp - volume keys of every timeframe
+    ( TimestampVolumetricsIndices ) TimeframeKey<u64> | VolumeKeys
 );
 
 table!(
@@ -344,15 +344,9 @@ table!(
 );
 
 table!(
-    /// Stores the five minute volumes into short term storage
-        /// key = "periodTimestamp-marketAddress"
-    ( TempPeriodVolumesFive ) ShardedKey<MarketAddress> | PeriodVolumes
-);
-
-table!(
-    /// Stores the hourly volumes into short term storage
-    /// key = "periodTimestamp-marketAddress"
-    ( TempPeriodVolumesHour )  ShardedKey<MarketAddress> | PeriodVolumes
+    /// Stores the volumes of the open periods of every timeframe into short term storage
+    /// key = "timeframe-marketAddress-periodTimestamp"
+    ( TempPeriodVolumes ) TimeframeKey<ShardedKey<MarketAddress>> | PeriodVolumes
 );
 
 // pipeline checkpoints
@@ -369,16 +363,14 @@ mod tests {
     use std::str::FromStr;
 
     use crate::tables::{
-        MarketProtocol, MarketVolumetricsIndicesDay, MarketVolumetricsIndicesFiveMin,
-        MarketVolumetricsIndicesHour, Protocols, Tables, TimestampVolumetricsIndicesDay,
-        TimestampVolumetricsIndicesFiveMin, TimestampVolumetricsIndicesHour, VolumetricsDay,
-        VolumetricsFiveMin, VolumetricsHour,
+        MarketProtocol, MarketVolumetricsIndices, Protocols, Tables, TimestampVolumetricsIndices,
+        Volumetrics,
     };
 
     use super::{
-        Abi, BlockIndices, BlockLogs, CanonicalHeaders, ContractLogs, ContractProxy, ContractsData,
-        DecodedLogs, HeaderNumbers, Headers, Logs, MarketToProxy, SyncStage, TableType,
-        TempPeriodVolumesFive, TempPeriodVolumesHour, TokensMarkets, TrackedContracts,
+        Abi, AddressTraces, BlockIndices, BlockLogs, CanonicalHeaders, ContractLogs, ContractProxy, ContractsData,
+        DecodedLogs, HeaderNumbers, Headers, Logs, MarketToProxy, Receipts, SyncStage, TableType,
+        TempPeriodVolumes, TokensMarkets, TrackedContracts, Traces,
         TransactionBlock, TransactionLogs, Transactions, TxHashNumber, UnknownContracts,
         NUM_TABLES,
     };
@@ -391,43 +383,27 @@ mod tests {
         (TableType::Table, Transactions::const_name()),
         (TableType::Table, TransactionBlock::const_name()),
         (TableType::Table, TxHashNumber::const_name()),
+        (TableType::Table, Receipts::const_name()),
         (TableType::Table, TransactionLogs::const_name()),
         (TableType::Table, BlockLogs::const_name()),
         (TableType::Table, ContractLogs::const_name()),
         (TableType::Table, Logs::const_name()),
         (TableType::Table, DecodedLogs::const_name()),
+        (TableType::Table, Traces::const_name()),
+        (TableType::DupSort, AddressTraces::const_name()),
         (TableType::Table, ContractProxy::const_name()),
         (TableType::Table, ContractsData::const_name()),
         (TableType::Table, MarketToProxy::const_name()),
         (TableType::Table, Abi::const_name()),
         (TableType::Table, UnknownContracts::const_name()),
         (TableType::Table, TrackedContracts::const_name()),
-        (TableType::Table, VolumetricsFiveMin::const_name()),
-        (TableType::Table, VolumetricsHour::const_name()),
-        (TableType::Table, VolumetricsDay::const_name()),
-        (
-            TableType::Table,
-            MarketVolumetricsIndicesFiveMin::const_name(),
-        ),
-        (TableType::Table, MarketVolumetricsIndicesHour::const_name()),
-        (TableType::Table, MarketVolumetricsIndicesDay::const_name()),
-        (
-            TableType::Table,
-            TimestampVolumetricsIndicesFiveMin::const_name(),
-        ),
-        (
-            TableType::Table,
-            TimestampVolumetricsIndicesHour::const_name(),
-        ),
-        (
-            TableType::Table,
-            TimestampVolumetricsIndicesDay::const_name(),
-        ),
+        (TableType::Table, Volumetrics::const_name()),
+        (TableType::Table, MarketVolumetricsIndices::const_name()),
+        (TableType::Table, TimestampVolumetricsIndices::const_name()),
         (TableType::Table, Protocols::const_name()),
         (TableType::Table, MarketProtocol::const_name()),
         (TableType::Table, TokensMarkets::const_name()),
-        (TableType::Table, TempPeriodVolumesFive::const_name()),
-        (TableType::Table, TempPeriodVolumesHour::const_name()),
+        (TableType::Table, TempPeriodVolumes::const_name()),
         (TableType::Table, SyncStage::const_name()),
     ];
 
@@ -1,3 +1,5 @@
+use crate::table::{Decode, Encode};
+use interfaces::db::DatabaseError;
 use simp_primitives::{LogNumber, TxNumber, BlockNumber};
 use sip_codecs::{main_codec, Compact};
 
@@ -31,9 +33,54 @@ impl From<(TxNumber, LogNumber, BlockNumber)> for TxLogId {
     }
 }
 
+/// Log ids are encoded as big-endian `tx | block_number | log`, so the logs of a transaction are
+/// contiguous. Transaction numbers grow with the block, which keeps the logs in block order too.
+impl Encode for TxLogId {
+    type Encoded = [u8; 24];
 
-impl Into<String> for TxLogId {
-    fn into(self) -> String {
-        format!{"{}_{}", self.tx, self.log}
+    fn encode(self) -> Self::Encoded {
+        let mut buf = [0u8; 24];
+        buf[..8].copy_from_slice(&self.tx.to_be_bytes());
+        buf[8..16].copy_from_slice(&self.block_number.to_be_bytes());
+        buf[16..].copy_from_slice(&self.log.to_be_bytes());
+        buf
+    }
+}
+
+impl Decode for TxLogId {
+    fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, DatabaseError> {
+        let value = value.as_ref();
+        if value.len() != 24 {
+            return Err(DatabaseError::DecodeError)
+        }
+
+        Ok(TxLogId {
+            tx: u64::decode(&value[..8])?,
+            block_number: u64::decode(&value[8..16])?,
+            log: u64::decode(&value[16..])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tx_log_id_encoding_is_ordered() {
+        let ids = [
+            TxLogId { tx: 1, block_number: 1, log: 2 },
+            TxLogId { tx: 1, block_number: 1, log: 10 },
+            TxLogId { tx: 2, block_number: 1, log: 0 },
+            TxLogId { tx: 10, block_number: 2, log: 0 },
+        ];
+
+        for pair in ids.windows(2) {
+            assert!(pair[0].encode() < pair[1].encode());
+        }
+
+        for id in ids {
+            assert_eq!(TxLogId::decode(id.encode()).unwrap(), id);
+        }
     }
 }
\ No newline at end of file
@@ -18,10 +18,12 @@ mod blocks;
 pub use blocks::*;
 
 mod logs;
+mod traces;
 pub mod volumetric;
 pub use volumetric::*;
 
 pub use logs::*;
+pub use traces::*;
 // pub use sharded_key::ShardedKey;
 
 /// Macro that implements [`Encode`] and [`Decode`] for uint types.
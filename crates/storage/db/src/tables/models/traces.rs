@@ -0,0 +1,18 @@
+use std::collections::BTreeSet;
+
+use simp_primitives::{Address, Trace};
+use sip_codecs::{main_codec, Compact};
+
+/// The calls of a transaction, depth first as listed by `trace_block`.
+#[main_codec]
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct TxTraces {
+    pub traces: Vec<Trace>,
+}
+
+impl TxTraces {
+    /// Addresses calling or called by a contract during the transaction, without duplicates.
+    pub fn internal_call_addresses(&self) -> BTreeSet<Address> {
+        self.traces.iter().filter(|trace| trace.is_internal()).flat_map(Trace::addresses).collect()
+    }
+}
@@ -1,7 +1,49 @@
 // use simp_primitives::rpc_utils::rl;
+use crate::table::{Decode, Encode};
+use interfaces::db::DatabaseError;
+use serde::{Deserialize, Serialize};
 use sip_codecs::{main_codec, Compact};
 use simp_primitives::VolumeKey;
 
+pub use simp_primitives::Timeframe;
+
+/// Key of a volumetric table, prefixed by the timeframe it belongs to so every timeframe shares
+/// the same tables.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct TimeframeKey<K> {
+    /// Timeframe of the entry.
+    pub timeframe: Timeframe,
+    /// Key of the entry inside the timeframe.
+    pub key: K,
+}
+
+impl<K> TimeframeKey<K> {
+    /// Creates a new `TimeframeKey<K>`.
+    pub fn new(timeframe: Timeframe, key: K) -> Self {
+        Self { timeframe, key }
+    }
+}
+
+impl<K: Encode> Encode for TimeframeKey<K> {
+    type Encoded = Vec<u8>;
+
+    fn encode(self) -> Self::Encoded {
+        let mut buf = vec![self.timeframe.id()];
+        buf.extend_from_slice(self.key.encode().as_ref());
+        buf
+    }
+}
+
+impl<K: Decode> Decode for TimeframeKey<K> {
+    fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, DatabaseError> {
+        let value = value.as_ref();
+        let (id, key) = value.split_first().ok_or(DatabaseError::DecodeError)?;
+        let timeframe = Timeframe::from_id(*id).ok_or(DatabaseError::DecodeError)?;
+
+        Ok(TimeframeKey { timeframe, key: K::decode(key)? })
+    }
+}
+
 
 /// The storage of the volumetric index keys
 #[main_codec]
@@ -1,5 +1,5 @@
 //! Small database table utilities and helper functions.
-use crate::table::{Decode, Decompress, Table, TableRow};
+use crate::table::{Decode, Decompress, DupSort, Encode, Table, TableRow};
 use interfaces::db::DatabaseError;
 
 #[macro_export]
@@ -45,9 +45,9 @@ where
     T::Key: Decode,
     T::Value: Decompress,
 {
-    let key = Decode::decode(kv.0)?;
+    let key = Decode::decode(kv.0).map_err(|_| DatabaseError::Codec(T::NAME))?;
 
-    let value = Decompress::decompress(kv.1)?;
+    let value = Decompress::decompress(kv.1).map_err(|_| DatabaseError::Codec(T::NAME))?;
     Ok((key, value))
 }
 
@@ -57,9 +57,8 @@ pub fn decode_value<'a, T>(
 ) -> Result<T::Value, DatabaseError>
 where
     T: Table,
-{   
-
-    Ok(Decompress::decompress(kv.1)?)
+{
+    Decompress::decompress(kv.1).map_err(|_| DatabaseError::Codec(T::NAME))
 }
 
 /// Helper function to decode a value. It can be a key or subkey.
@@ -67,5 +66,15 @@ pub fn decode_one<T>(value: Vec<u8>) -> Result<T::Value, DatabaseError>
 where
     T: Table,
 {
-    Ok(Decompress::decompress(value)?)
+    Decompress::decompress(value).map_err(|_| DatabaseError::Codec(T::NAME))
+}
+
+/// Helper function to encode the `key||subkey` key a value of a DupSort table is stored under.
+pub fn encode_dup_key<T>(key: T::Key, subkey: T::SubKey) -> Vec<u8>
+where
+    T: DupSort,
+{
+    let mut buf = key.encode().as_ref().to_vec();
+    buf.extend_from_slice(subkey.encode().as_ref());
+    buf
 }
@@ -9,7 +9,7 @@ use std::{
 pub const DB_VERSION_FILE_NAME: &str = "database.version";
 /// The version of the database stored in the [DB_VERSION_FILE_NAME] file in the same directory as
 /// database. Example: `1`.
-pub const DB_VERSION: u64 = 1;
+pub const DB_VERSION: u64 = 8;
 
 /// Error when checking a database version using [check_db_version_file]
 #[derive(thiserror::Error, Debug)]
@@ -61,7 +61,12 @@ pub fn get_db_version<P: AsRef<Path>>(db_path: P) -> Result<u64, DatabaseVersion
 /// This function will create a file if it does not exist,
 /// and will entirely replace its contents if it does.
 pub fn create_db_version_file<P: AsRef<Path>>(db_path: P) -> io::Result<()> {
-    fs::write(db_version_file_path(db_path), DB_VERSION.to_string())
+    write_db_version_file(db_path, DB_VERSION)
+}
+
+/// Writes `version` into the database version file, used to record the progress of migrations.
+pub fn write_db_version_file<P: AsRef<Path>>(db_path: P, version: u64) -> io::Result<()> {
+    fs::write(db_version_file_path(db_path), version.to_string())
 }
 
 /// Returns a database version file path.
@@ -1,12 +1,10 @@
 use std::collections::HashMap;
 use db::tables::models::{ContractData, AbiContract};
-use db::tables::utils::decoder;
 use db::tables::{self, MarketToProxy, ContractsData, ContractProxy, Abi, AbiData, UnknownContracts};
 use db::transaction::DbTx;
 use ethabi::ethereum_types::H256;
 use simp_primitives::{Address, StoredLog, DecodedData};
 use interfaces::Result;
-use rocksdb::ReadOptions;
 use crate::DatabaseProvider;
 use crate::traits::{AbiProvider, AbiWriter};
 use ethabi::{Contract, RawLog, Event, Token};
@@ -74,7 +72,11 @@ fn get_token_type(token: Token) -> Result<TokenWType> {
     })
 }
 
-impl AbiProvider for DatabaseProvider {
+fn missing_abi(id: u64) -> interfaces::Error {
+    interfaces::Error::Custom(format!("missing abi {}", id))
+}
+
+impl<TX: DbTx> AbiProvider for DatabaseProvider<TX> {
     fn decode_logs(&self, logs: Vec<StoredLog>, abi: &Vec<AbiContract>) -> Result<Vec<Option<Vec<DecodedData>>>> {
         // TODO: make sure to use only the correct abi and not past abis
         // TODO: this is only relatable to evms, should find a better way to inject different decoding behaviours
@@ -135,13 +137,13 @@ impl AbiProvider for DatabaseProvider {
     }
 
     fn get_abi_by_id(&self, id: u64) -> Result<Option<AbiData>> {
-        let abi = self.db.dae_get::<Abi>(id).unwrap();
+        let abi = self.db.dae_get::<Abi>(id)?;
 
-        Ok(Some(abi.unwrap()))
+        Ok(abi)
     }
 
     fn get_proxy_data(&self, address: Address) -> Result<Option<tables::StoredContract>> {
-        let proxy_data = self.db.dae_get::<ContractProxy>(address).unwrap();
+        let proxy_data = self.db.dae_get::<ContractProxy>(address)?;
 
         Ok(proxy_data)
     }
@@ -149,45 +151,49 @@ impl AbiProvider for DatabaseProvider {
     fn get_abis_by_address(&self, address: Address) -> Result<Option<Vec<AbiContract>>> {
 
         // check if we found the abi
-        let missing_abi = self.address_without_abi(address).unwrap();
+        let missing_abi = self.address_without_abi(address)?;
 
         if missing_abi.is_some() {
             return Ok(None);
         }
         // check if proxy
-        let is_proxy = self.has_proxy(address).unwrap();
+        let is_proxy = self.has_proxy(address)?;
 
         match is_proxy {
             Some(proxy) => {
-                let contracts = self.get_proxy_data(proxy).unwrap();
-                let proxy_abi = self.get_abi_by_id(contracts.clone().unwrap().abi_id).unwrap();
+                let contracts = self.get_proxy_data(proxy)?.ok_or_else(|| {
+                    interfaces::Error::Custom(format!("missing proxy data of {:?}", proxy))
+                })?;
+                let proxy_abi = self.get_abi_by_id(contracts.abi_id)?.ok_or_else(|| missing_abi(contracts.abi_id))?;
                 let mut abis = vec![];  
 
                 abis.push(AbiContract { 
                     address, 
-                    abi: proxy_abi.unwrap(), 
+                    abi: proxy_abi, 
                     contract_type: String::from("proxy") 
                 });
 
-                for contract in contracts.unwrap().implementations {
-                    let address = self.get_contract_data(contract.address).unwrap();
+                for contract in contracts.implementations {
+                    let data = self.get_contract_data(contract.address)?.ok_or_else(|| {
+                        interfaces::Error::Custom(format!("missing contract data of {:?}", contract.address))
+                    })?;
 
-                    let abi = self.get_abi_by_id(address.unwrap().abi_id).unwrap();
+                    let abi = self.get_abi_by_id(data.abi_id)?.ok_or_else(|| missing_abi(data.abi_id))?;
                     
-                    abis.push(AbiContract { address: contract.address, abi: abi.unwrap(), contract_type: String::from("contract") });
+                    abis.push(AbiContract { address: contract.address, abi, contract_type: String::from("contract") });
                 }
                 Ok(Some(abis))
             },
             None => {
-                let contract_data = self.get_contract_data(address).unwrap();
+                let contract_data = self.get_contract_data(address)?;
                 match contract_data {
                     Some(contract) => {
-                        let abi = self.get_abi_by_id(contract.abi_id).unwrap();
+                        let abi = self.get_abi_by_id(contract.abi_id)?.ok_or_else(|| missing_abi(contract.abi_id))?;
 
                         Ok(Some(vec![AbiContract {
                             address,
                             contract_type: String::from("contract"),
-                            abi: abi.unwrap()
+                            abi,
                         }]))
                     },
                     None => Ok(None)
@@ -197,36 +203,23 @@ impl AbiProvider for DatabaseProvider {
     }
 
     fn get_contract_data(&self, address: Address) -> Result<Option<ContractData>> {
-        let data = self.db.dae_get::<ContractsData>(address).unwrap();
+        let data = self.db.dae_get::<ContractsData>(address)?;
         Ok(data)
     }
 
     fn has_proxy(&self, address: Address) -> Result<Option<Address>> {
-        let proxy = self.db.dae_get::<MarketToProxy>(address).unwrap();
+        let proxy = self.db.dae_get::<MarketToProxy>(address)?;
         Ok(proxy)
     }
 
     fn get_latest_abi(&self) -> Result<Option<u64>> {
-        let opts = ReadOptions::default();
-
-        let mut iter = self.db.dae_new_cursor::<Abi>(opts)?;
-
-        iter.seek_to_last();
+        let latest = self.db.dae_get_last::<Abi>()?;
 
-        if iter.valid() {
-            let k = iter.key().unwrap();
-            let v = iter.value().unwrap();
-
-            let (key, _val) = decoder::<Abi>((k.to_vec(), v.to_vec())).unwrap();
-
-            Ok(Some(key))
-        } else {
-            Ok(None)
-        }
+        Ok(latest.map(|(key, _)| key))
     }
 
     fn address_without_abi(&self, address: Address) -> Result<Option<(Address, u32)> > {
-        let ts = self.db.dae_get::<UnknownContracts>(address).unwrap();
+        let ts = self.db.dae_get::<UnknownContracts>(address)?;
         match ts {
             Some(ts) => {
                 Ok(Some((address, ts)))
@@ -236,9 +229,9 @@ impl AbiProvider for DatabaseProvider {
     }
 }
 
-impl AbiWriter for DatabaseProvider {
+impl<TX: DbTx> AbiWriter for DatabaseProvider<TX> {
     fn insert_abi(&self, abi: AbiData) -> Result<Option<u64>> {
-        let latest_abi = self.get_latest_abi().unwrap();
+        let latest_abi = self.get_latest_abi()?;
         // TODO: find a way to check for abi uniqueness (maybe by hash)
         let new_abi_id = match latest_abi {
             Some(id) => id + 1,
@@ -259,7 +252,7 @@ impl AbiWriter for DatabaseProvider {
 
     fn insert_contract_proxy_index(&self, address: Address, proxy: Address, force_upsert:bool) -> Result<Address> {
 
-        let exists = self.has_proxy(address).unwrap();
+        let exists = self.has_proxy(address)?;
 
         match exists {
             Some(p) => {
@@ -280,7 +273,7 @@ impl AbiWriter for DatabaseProvider {
     }
 
     fn upsert_proxy(&self, address: Address, abi_id: u64, verified: bool, implementations: Vec<tables::models::ProxyImplementations>) -> Result<Address> {
-        let exists = self.get_proxy_data(address).unwrap();
+        let exists = self.get_proxy_data(address)?;
 
         match exists {
             Some(p) => {    
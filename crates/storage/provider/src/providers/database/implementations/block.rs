@@ -1,9 +1,10 @@
 use simp_primitives::{BlockHashOrNumber, Block, H256};
 use interfaces::Result;
 use crate::DatabaseProvider;
+use db::transaction::DbTx;
 use crate::traits::{BlockNumReader,HeaderProvider, TransactionsProvider, BlockReader};
 
-impl BlockReader for DatabaseProvider {
+impl<TX: DbTx> BlockReader for DatabaseProvider<TX> {
     fn block(&self, id: BlockHashOrNumber) -> Result<Option<Block>> {
         if let Some(number) = self.convert_hash_or_number(id)? {
             if let Some(header) = self.header_by_number(number)? {
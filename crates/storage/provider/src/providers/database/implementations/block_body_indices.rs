@@ -6,7 +6,7 @@ use crate::DatabaseProvider;
 use crate::traits::BlockNumReader;
 use db::transaction::DbTx;
 
-impl BlockBodyIndicesProvider for DatabaseProvider {
+impl<TX: DbTx> BlockBodyIndicesProvider for DatabaseProvider<TX> {
     fn block_body_indices(&self, block: BlockHashOrNumber) -> Result<Option<BlockBodyIndices>> {
         let bn = self.convert_hash_or_number(block)?;
         match bn {
@@ -19,7 +19,7 @@ impl BlockBodyIndicesProvider for DatabaseProvider {
     }
 }
 
-impl BlockBodyIndicesWriter for DatabaseProvider {
+impl<TX: DbTx> BlockBodyIndicesWriter for DatabaseProvider<TX> {
     fn insert_block_body_indices(
         &self,
         block_number: BlockNumber,
@@ -30,6 +30,11 @@ impl BlockBodyIndicesWriter for DatabaseProvider {
         self.db.dae_put::<BlockIndices>(block_number, index.clone())?;
         Ok(index)
     }
+
+    fn remove_block_body_indices_above(&self, block_number: BlockNumber) -> Result<()> {
+        self.remove_from::<BlockIndices>(block_number + 1, |_| Ok(()))?;
+        Ok(())
+    }
 }
 
 
@@ -1,6 +1,5 @@
 use db::tables::CanonicalHeaders;
 use interfaces::Result;
-use rocksdb::ReadOptions;
 
 use crate::traits::BlockHashWriter;
 use crate::{traits::BlockHashReader, DatabaseProvider};
@@ -9,37 +8,24 @@ use simp_primitives::{
 };
 
 use db::transaction::DbTx;
-use db::tables::utils::decoder;
-use db::table::Encode;
 
-impl BlockHashReader for DatabaseProvider {
+impl<TX: DbTx> BlockHashReader for DatabaseProvider<TX> {
     fn block_hash(&self, number: u64) -> Result<Option<H256>> {
-        Ok(self.db.dae_get::<CanonicalHeaders>(number).unwrap())
+        Ok(self.db.dae_get::<CanonicalHeaders>(number)?)
     }
 
     fn block_hashes_range(&self, start: BlockNumber, end: BlockNumber) -> Result<Vec<H256>> {
-        let start = start.encode().to_vec();
-
-        let mut opts = ReadOptions::default();
-        opts.set_iterate_range(start..end.encode().to_vec());
-        let mut iter = self.db.dae_new_cursor::<CanonicalHeaders>(opts).unwrap();
-        iter.seek_to_first();
-        let mut bn_range = Vec::new();
-
-        while iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-            let kv = decoder::<CanonicalHeaders>((k.as_ref().to_vec(), value.as_ref().to_vec()))
-                .unwrap();
-            bn_range.push(kv.1);
-            iter.next();
-        }
+        let mut cursor = self.db.dae_cursor::<CanonicalHeaders>()?;
+        let bn_range = cursor
+            .walk(start..end)?
+            .map(|entry| entry.map(|(_, hash)| hash))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(bn_range)
     }
 }
 
-impl BlockHashWriter for DatabaseProvider {
+impl<TX: DbTx> BlockHashWriter for DatabaseProvider<TX> {
     fn insert_block_hash(&self,number:BlockNumber,hash:H256) -> Result<()> {
         // TODO: add validation?
         self.db.dae_put::<CanonicalHeaders>(number, hash)?;
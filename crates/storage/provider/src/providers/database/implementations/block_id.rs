@@ -1,35 +1,25 @@
 use db::tables::HeaderNumbers;
 use interfaces::Result;
 use simp_primitives::{BlockNumber, H256};
-use rocksdb::ReadOptions;
 
 use crate::traits::BlockNumWriter;
 use crate::{traits::BlockNumReader, DatabaseProvider};
 use db::transaction::DbTx;
-use db::tables::utils::decoder;
 
-impl BlockNumReader for DatabaseProvider {
+impl<TX: DbTx> BlockNumReader for DatabaseProvider<TX> {
     fn last_block_number(&self) -> Result<BlockNumber> {
-        let opts = ReadOptions::default();
-        let mut iter = self.db.dae_new_cursor::<HeaderNumbers>(opts).unwrap();
-        iter.seek_to_last();
-        if iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-            let kv =
-                decoder::<HeaderNumbers>((k.as_ref().to_vec(), value.as_ref().to_vec())).unwrap();
-            Ok(kv.1)
-        } else {
-            panic!("No valid block number found")
+        match self.db.dae_cursor::<HeaderNumbers>()?.last()? {
+            Some((_, number)) => Ok(number),
+            None => Err(interfaces::Error::Custom("No valid block number found".to_string())),
         }
     }
 
     fn block_number(&self, hash: H256) -> Result<Option<BlockNumber>> {
-        Ok(self.db.dae_get::<HeaderNumbers>(hash).unwrap())
+        Ok(self.db.dae_get::<HeaderNumbers>(hash)?)
     }
 }
 
-impl BlockNumWriter for DatabaseProvider {
+impl<TX: DbTx> BlockNumWriter for DatabaseProvider<TX> {
     fn insert_block_number(&self, hash: H256, number: BlockNumber) -> Result<()> {
         // TODO: insert validation?
         self.db.dae_put::<HeaderNumbers>(hash, number)?;
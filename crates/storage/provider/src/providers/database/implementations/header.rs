@@ -1,15 +1,12 @@
-use db::tables::Headers;
+use db::tables::{CanonicalHeaders, HeaderNumbers, Headers};
 use interfaces::Result;
 use simp_primitives::{BlockHash, Header, BlockNumber};
-use rocksdb::ReadOptions;
-use db::tables::utils::decoder;
 
 use crate::DatabaseProvider;
 use crate::traits::{BlockNumReader, HeaderProvider, HeaderWriter};
-use db::table::Encode;
 use db::transaction::DbTx;
 
-impl HeaderProvider for DatabaseProvider {
+impl<TX: DbTx> HeaderProvider for DatabaseProvider<TX> {
 
     fn header(&self, block_hash: &BlockHash) -> Result<Option<Header>> {
         if let Some(num) = self.block_number(*block_hash)? {
@@ -20,53 +17,48 @@ impl HeaderProvider for DatabaseProvider {
     }
 
     fn header_by_number(&self, num: BlockNumber) -> Result<Option<Header>> {
-        let header = self.db.dae_get::<Headers>(num).unwrap();
+        let header = self.db.dae_get::<Headers>(num)?;
         Ok(header)
     }
 
     // It will always include the range bounds
     fn headers_range(&self, range: (BlockNumber, BlockNumber)) -> Result<Vec<Header>> {
-        let mut opts = ReadOptions::default();
-        opts.set_iterate_range(range.0.encode().as_slice()..range.1.encode().as_slice());
-        let mut iter = self.db.dae_new_cursor::<Headers>(opts).unwrap();
-        let mut headers = Vec::new();
-        iter.seek_to_first();
-
-        while iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-            let kv = decoder::<Headers>((k.as_ref().to_vec(), value.as_ref().to_vec())).unwrap();
-            headers.push(kv.1);
-            iter.next();
-        }
+        let mut cursor = self.db.dae_cursor::<Headers>()?;
+        let headers = cursor
+            .walk(range.0..range.1)?
+            .map(|entry| entry.map(|(_, header)| header))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(headers)
     }
 
     fn latest_header(&self) -> Result<Option<Header> > {
-        let mut iter = self.db.dae_new_cursor::<Headers>(ReadOptions::default()).unwrap();
-        iter.seek_to_last();
-
-        if iter.valid() {
-            let decoded = decoder::<Headers>(
-                (
-                    iter.key().unwrap().to_vec(),
-                    iter.value().unwrap().to_vec()
-                )
-            ).unwrap();
-
-            Ok(Some(decoded.1))
-        } else {
-            Ok(None)
-        }
+        let latest = self.db.dae_cursor::<Headers>()?.last()?;
+
+        Ok(latest.map(|(_, header)| header))
     }
 }
 
-impl HeaderWriter for DatabaseProvider {
+impl<TX: DbTx> HeaderWriter for DatabaseProvider<TX> {
     fn insert_header(&self, block_number: &BlockNumber, header: Header) -> Result<Option<BlockNumber> > {
         self.db.dae_put::<Headers>(*block_number, header)?;
         Ok(Some(*block_number))
     }
+
+    fn remove_headers_above(&self, block_number: BlockNumber) -> Result<()> {
+        // HeaderNumbers is keyed by hash, so it is cleaned up from both block number tables
+        self.remove_from::<Headers>(block_number + 1, |(_, header)| {
+            self.db.dae_delete::<HeaderNumbers>(header.hash)?;
+            Ok(())
+        })?;
+
+        self.remove_from::<CanonicalHeaders>(block_number + 1, |(_, hash)| {
+            self.db.dae_delete::<HeaderNumbers>(hash)?;
+            Ok(())
+        })?;
+
+        Ok(())
+    }
 }
 
 
@@ -76,7 +68,7 @@ mod tests {
 
     use db::{init_db, test_utils::ERROR_TEMPDIR};
     use simp_primitives::Header;
-    use crate::traits::{HeaderProvider, HeaderWriter, BlockNumWriter, BlockHashWriter};
+    use crate::traits::{HeaderProvider, HeaderWriter, BlockNumWriter, BlockHashWriter, BlockHashReader};
     use crate::DatabaseProvider;
 
     fn get_provider() -> DatabaseProvider {
@@ -143,4 +135,30 @@ mod tests {
         assert_eq!(headers_from_db.len(), headers.len());
         assert_eq!(headers_from_db[0].hash, headers[0].hash);
     }
+
+    #[test]
+    fn remove_headers_above_target() {
+        let headers = get_mocks_headers();
+
+        let headers: Vec<Header> = serde_json::from_str(&headers).unwrap();
+
+        let provider = get_provider();
+
+        for header in headers.clone() {
+            provider.insert_header(&header.number, header.clone()).unwrap();
+            provider.insert_block_number(header.hash, header.number).unwrap();
+            provider.insert_block_hash(header.number, header.hash).unwrap();
+        }
+
+        let min = headers.iter().map(|h| h.number).min().unwrap();
+
+        provider.remove_headers_above(min).unwrap();
+
+        for header in headers {
+            let kept = header.number <= min;
+            assert_eq!(provider.header_by_number(header.number).unwrap().is_some(), kept);
+            assert_eq!(provider.header(&header.hash).unwrap().is_some(), kept);
+            assert_eq!(provider.block_hash(header.number).unwrap().is_some(), kept);
+        }
+    }
 }
\ No newline at end of file
@@ -1,68 +1,34 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use chrono::Utc;
 use db::{
     common::PairResult,
     tables::{
         models::{sharded_key::NUM_OF_INDICES_IN_SHARD, TxLogId},
-        BlockIndices, ContractLogs, DecodedLogs, Logs, ShardedKey, TransactionLogs, TxLogs,
+        BlockIndices, BlockLogs, ContractLogs, DecodedLogs, Logs, ShardedKey, TransactionLogs,
+        TxLogs,
     },
 };
 use simp_primitives::{
-    Address, BlockHashOrNumber, BlockNumber, Log, StoredDecodedData, StoredLog, TxNumber,
+    Address, BlockHashOrNumber, BlockNumber, Log, LogNumber, StoredDecodedData, StoredLog,
+    TxNumber,
 };
 
-use rocksdb::{PrefixRange, ReadOptions};
-
 use crate::traits::{
     AbiProvider, BlockNumReader, LogsProvider, LogsWriter, TrackingProvider, TransactionsProvider, AbiWriter,
+    MAX_LOGS_BLOCK_RANGE,
 };
 use crate::{traits::StoredOrDecodedLog, DatabaseProvider};
-use db::table::Encode;
-use db::tables::utils::decoder;
 use db::transaction::DbTx;
-use interfaces::Result;
+use interfaces::{Error, Result};
 
-impl LogsProvider for DatabaseProvider {
+impl<TX: DbTx> LogsProvider for DatabaseProvider<TX> {
     fn logs_by_tx_id(
         &self,
         tx_id: simp_primitives::TxNumber,
         decoded: bool,
     ) -> Result<Option<Vec<StoredOrDecodedLog>>> {
-        let mut logs = Vec::new();
-
-        let mut opts = ReadOptions::default();
-        // converts the TxNumber to a string and then to bytes
-        opts.set_iterate_range(PrefixRange(tx_id.to_string().as_bytes()));
-
-        if decoded {
-            let mut iter = self.db.dae_new_cursor::<DecodedLogs>(opts).unwrap();
-            iter.seek_to_first();
-
-            while iter.valid() {
-                let k = iter.key().unwrap();
-                let value = iter.value().unwrap();
-                let kv = decoder::<DecodedLogs>((k.as_ref().to_vec(), value.as_ref().to_vec())).unwrap();
-                let decoded_log = StoredOrDecodedLog::Decoded(kv.1);
-                logs.push(decoded_log);
-                iter.next();
-            }
-        } else {
-
-            let mut iter = self.db.dae_new_cursor::<Logs>(opts).unwrap();
-            iter.seek_to_first();
-    
-            while iter.valid() {
-                let k = iter.key().unwrap();
-                let value = iter.value().unwrap();
-                let kv = decoder::<Logs>((k.as_ref().to_vec(), value.as_ref().to_vec())).unwrap();
-                let raw_log = StoredOrDecodedLog::Raw(kv.1);
-                logs.push(raw_log);
-                iter.next();
-            }
-        }
-        Ok(Some(logs))
-
+        Ok(Some(self.logs_by_tx_range(tx_id, tx_id, decoded)?))
     }
 
     fn logs_by_block(
@@ -78,21 +44,11 @@ impl LogsProvider for DatabaseProvider {
                 match block_body_index {
                     None => Ok(None),
                     Some(index) => {
-                        // iter for tx_ids
-                        let tx_start = index.first_tx_num;
-                        let tx_end = index.last_tx_num();
-
-                        let mut logs = Vec::new();
-
-                        for tx_id in tx_start..=tx_end {
-                            let tx_logs = self.logs_by_tx_id(tx_id, decoded)?;
-                            match tx_logs {
-                                None => continue,
-                                Some(tx_logs) => {
-                                    logs.extend(tx_logs);
-                                }
-                            }
-                        }
+                        let logs = self.logs_by_tx_range(
+                            index.first_tx_num,
+                            index.last_tx_num(),
+                            decoded,
+                        )?;
 
                         Ok(Some(logs))
                     }
@@ -107,8 +63,8 @@ impl LogsProvider for DatabaseProvider {
         end: BlockNumber,
         decoded: bool,
     ) -> Result<Vec<StoredOrDecodedLog>> {
-        if end - start > 10 {
-            panic!("Range too big");
+        if end.saturating_sub(start) >= MAX_LOGS_BLOCK_RANGE {
+            return Err(Error::RangeTooLarge { start, end, max: MAX_LOGS_BLOCK_RANGE })
         }
 
         let mut logs = Vec::new();
@@ -149,119 +105,102 @@ impl LogsProvider for DatabaseProvider {
         to: Option<BlockNumber>,
         decoded: bool,
     ) -> Result<Vec<StoredOrDecodedLog>> {
-        let mut logs = Vec::new();
-        let mut opts = ReadOptions::default();
-        opts.set_iterate_range(PrefixRange(address.encode().as_slice()));
-        if decoded {
-            let mut iter = self
-                .db
-                .dae_new_cursor::<ContractLogs>(opts)
-                .unwrap();
-            iter.seek_to_first();
-            let min = from.unwrap_or(0);
-            let max = match to {
-                Some(x) => x,
-                None => u64::MAX,
-            };
+        self.logs_by_address_page(address, from, to, 0, usize::MAX, decoded)
+    }
 
-            while iter.valid() {
-                let k = iter.key().unwrap();
-                let value = iter.value().unwrap();
-                let kv =
-                    decoder::<ContractLogs>((k.as_ref().to_vec(), value.as_ref().to_vec())).unwrap();
-                let shard = kv.0.max_shard_value;
-                if shard <= min {
-                    iter.next()
-                } else {
-                    let logs_ids = kv.1.log_ids;
-
-                    for log_id in logs_ids {
-                        if log_id.block_number <= max && log_id.block_number >= min {
-                            let id: String = log_id.into();
-                            let log = self.db.dae_get::<DecodedLogs>(String::from(id))?;
-                            match log {
-                                None => continue,
-                                Some(log) => {
-                                    let decoded_log = StoredOrDecodedLog::Decoded(log);
-                                    logs.push(decoded_log)
-                                },
-                            }
-                        }
-                    }
-                    iter.next()
-                }
-            }
-        } else {
-            let mut iter = self
-                .db
-                .dae_new_cursor::<ContractLogs>(opts)
-                .unwrap();
+    fn logs_by_address_page(
+        &self,
+        address: Address,
+        from: Option<BlockNumber>,
+        to: Option<BlockNumber>,
+        offset: usize,
+        limit: usize,
+        decoded: bool,
+    ) -> Result<Vec<StoredOrDecodedLog>> {
+        let mut logs = Vec::new();
+        let mut skipped = 0;
+        let min = from.unwrap_or(0);
+        let max = match to {
+            Some(x) => x,
+            None => u64::MAX,
+        };
 
-            iter.seek_to_first();
+        // shards are keyed by their highest block, the first one holding `min` is the first at or
+        // above it
+        let mut cursor = self.db.dae_cursor::<ContractLogs>()?;
 
-            let min = from.unwrap_or(0);
-            let max = match to {
-                Some(x) => x,
-                None => u64::MAX,
-            };
+        for entry in cursor.walk(ShardedKey::new(address, min)..=ShardedKey::last(address))? {
+            let (key, shard) = entry?;
 
-            while iter.valid() {
-                let k = iter.key().unwrap();
-                let value = iter.value().unwrap();
-                let kv =
-                    decoder::<ContractLogs>((k.as_ref().to_vec(), value.as_ref().to_vec())).unwrap();
-                let shard = kv.0.max_shard_value;
-                if shard <= min {
-                    iter.next()
-                } else {
-                    let logs_ids = kv.1.log_ids;
-
-                    for log_id in logs_ids {
-                        if log_id.block_number <= max && log_id.block_number >= min {
-                            let id: String = log_id.into();
-                            let log = self.db.dae_get::<Logs>(String::from(id))?;
-                            match log {
-                                None => continue,
-                                Some(log) => {
-                                    let raw_log = StoredOrDecodedLog::Raw(log);
-                                    logs.push(raw_log)
-                                },
-                            }
-                        }
+            for log_id in shard.log_ids {
+                if log_id.block_number <= max && log_id.block_number >= min {
+                    if logs.len() == limit {
+                        return Ok(logs)
                     }
 
-                    if shard >= max {
-                        break;
+                    let log = if decoded {
+                        self.db.dae_get::<DecodedLogs>(log_id)?.map(StoredOrDecodedLog::Decoded)
                     } else {
-                        iter.next()
+                        self.db.dae_get::<Logs>(log_id)?.map(StoredOrDecodedLog::Raw)
+                    };
+
+                    // logs without decoded data aren't counted by the offset either
+                    match log {
+                        Some(_) if skipped < offset => skipped += 1,
+                        Some(log) => logs.push(log),
+                        None => {}
                     }
                 }
             }
+
+            // the logs of the highest block of a full shard can continue in the next one
+            if key.max_shard_value > max {
+                break;
+            }
         }
-        
+
         Ok(logs)
     }
 
     fn get_address_logs_latest_partition(&self, address: Address) -> PairResult<ContractLogs> {
-        let mut opts = ReadOptions::default();
-        opts.set_iterate_range(PrefixRange(address.encode().as_slice()));
-
-        let mut iter = self.db.dae_new_cursor::<ContractLogs>(opts).unwrap();
-        iter.seek_to_last();
-        if iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-            let kv =
-                decoder::<ContractLogs>((k.as_ref().to_vec(), value.as_ref().to_vec())).unwrap();
-
-            Ok(Some(kv))
+        let mut cursor = self.db.dae_cursor::<ContractLogs>()?;
+        let shard = cursor.walk_back(Some(ShardedKey::last(address)))?.next().transpose()?;
+
+        Ok(shard.filter(|(key, _)| key.key == address))
+    }
+}
+
+impl<TX: DbTx> DatabaseProvider<TX> {
+    /// Returns the logs of the transactions from `first_tx` to `last_tx`, in block, transaction
+    /// and log order.
+    fn logs_by_tx_range(
+        &self,
+        first_tx: TxNumber,
+        last_tx: TxNumber,
+        decoded: bool,
+    ) -> Result<Vec<StoredOrDecodedLog>> {
+        let range = TxLogId { tx: first_tx, block_number: 0, log: 0 }..=TxLogId {
+            tx: last_tx,
+            block_number: BlockNumber::MAX,
+            log: LogNumber::MAX,
+        };
+        let mut logs = Vec::new();
+
+        if decoded {
+            for entry in self.db.dae_cursor::<DecodedLogs>()?.walk(range)? {
+                logs.push(StoredOrDecodedLog::Decoded(entry?.1));
+            }
         } else {
-            Ok(None)
+            for entry in self.db.dae_cursor::<Logs>()?.walk(range)? {
+                logs.push(StoredOrDecodedLog::Raw(entry?.1));
+            }
         }
+
+        Ok(logs)
     }
 }
 
-impl LogsWriter for DatabaseProvider {
+impl<TX: DbTx> LogsWriter for DatabaseProvider<TX> {
     fn insert_raw_logs(&self, log: (TxLogId, StoredLog)) -> Result<()> {
         self.db.dae_put::<Logs>(log.0.into(), log.1)?;
         Ok(())
@@ -277,14 +216,19 @@ impl LogsWriter for DatabaseProvider {
         for address in logs.keys() {
             let is_required = self.is_contract_tracked(*address)?;
             if is_required {
-                let abi = self.get_abis_by_address(*address)?.unwrap();
+                // nothing to decode with until the abi of the contract is known
+                let abi = match self.get_abis_by_address(*address)? {
+                    Some(abi) => abi,
+                    None => continue,
+                };
                 let mut stored_logs = Vec::new();
                 let address_logs_ids = logs.get(address).unwrap();
 
                 for log_id in address_logs_ids {
-                    let id: String = log_id.clone().into();
                     // TODO: method to catch missing logs
-                    let stored_log = self.db.dae_get::<Logs>(String::from(id))?.unwrap();
+                    let stored_log = self.db.dae_get::<Logs>(*log_id)?.ok_or_else(|| {
+                        interfaces::Error::Custom(format!("missing log {:?}", log_id))
+                    })?;
                     stored_logs.push(stored_log);
                 }
 
@@ -311,89 +255,31 @@ impl LogsWriter for DatabaseProvider {
     }
 
     fn insert_logs_by_address(&self, logs: &HashMap<Address, Vec<TxLogId>>) -> Result<()> {
-        for address in logs.keys() {
-            // resolve partition
-
-            let logs = logs.get(address).unwrap();
+        for (address, logs) in logs {
             // ATTENTION: this assumes that logs are always stored in order
-            let latest_shard = self.get_address_logs_latest_partition(*address).unwrap();
-            // if shard is full create a new one, else write as many in there and update the key max value
-            match latest_shard {
-                Some(shard) => {
-                    if shard.1.log_ids.len() >= NUM_OF_INDICES_IN_SHARD {
-                        // new shard
-                        let logs_max = logs.last().unwrap().block_number;
-
-                        let shard = ShardedKey::new(*address, logs_max);
-
-                        self.db.dae_put::<ContractLogs>(
-                            shard,
-                            TxLogs {
-                                log_ids: logs.to_vec(),
-                            },
-                        )?;
-                    } else {
-                        // fill shard to max and then continue on new shard
-                        let available_slots = NUM_OF_INDICES_IN_SHARD - shard.1.log_ids.len();
-                        let mut new_logs_ids = shard.1.log_ids.clone();
-                        let mut new_key = shard.0.clone();
-
-                        let logs_to_save = &logs[0..available_slots];
-                        new_logs_ids.extend(logs_to_save);
-
-                        let bn = logs_to_save.last().unwrap().block_number;
-                        new_key.max_shard_value = bn;
-                        self.db.dae_put::<ContractLogs>(
-                            new_key.clone(),
-                            TxLogs {
-                                log_ids: new_logs_ids,
-                            },
-                        )?;
-
-                        self.db.dae_delete::<ContractLogs>(shard.0.clone())?;
-                        // new shard to be created
-                        if logs.len() > logs_to_save.len() {
-                            let logs_to_save = &logs[available_slots..=logs.len()];
-                            let mut new_shard = new_key.clone();
-                            let max_bn = logs_to_save.last().unwrap().block_number;
-                            new_shard.max_shard_value = max_bn;
-
-                            self.db.dae_put::<ContractLogs>(
-                                new_shard,
-                                TxLogs {
-                                    log_ids: logs_to_save.to_vec(),
-                                },
-                            )?;
-                        }
-                    }
-                }
-                None => {
-                    let mut logs_to_save = logs.clone();
+            // the open shard is keyed by `ShardedKey::last`, it is filled up first and the logs
+            // that don't fit go to new shards. Full shards are keyed by their highest block.
+            let last = ShardedKey::last(*address);
 
-                    while logs_to_save.len() > 0 {
-                        let max = match logs_to_save.len() < NUM_OF_INDICES_IN_SHARD {
-                            true => logs_to_save.len(),
-                            false => NUM_OF_INDICES_IN_SHARD,
-                        };
+            let mut log_ids = match self.db.dae_get::<ContractLogs>(last.clone())? {
+                Some(shard) => shard.log_ids,
+                None => Vec::new(),
+            };
+            log_ids.extend_from_slice(logs);
 
-                        let l = logs_to_save.clone()[0..max].to_vec();
-                        logs_to_save = logs_to_save.clone()[max..logs_to_save.len()].to_vec();
+            let mut shards = log_ids.chunks(NUM_OF_INDICES_IN_SHARD).peekable();
 
-                        let logs_max = l.last().unwrap().block_number;
-                        
-                        // check amount of ids 
-                        let shard = ShardedKey::new(*address, logs_max);
+            while let Some(shard) = shards.next() {
+                let key = match shards.peek() {
+                    Some(_) => ShardedKey::new(
+                        *address,
+                        shard.last().expect("shards are not empty").block_number,
+                    ),
+                    None => last.clone(),
+                };
 
-                        self.db.dae_put::<ContractLogs>(
-                            shard,
-                            TxLogs {
-                                log_ids: l.to_vec(),
-                            },
-                        )?;
-                    }
-                    
-                }
-            };
+                self.db.dae_put::<ContractLogs>(key, TxLogs { log_ids: shard.to_vec() })?;
+            }
         }
         Ok(())
     }
@@ -402,7 +288,7 @@ impl LogsWriter for DatabaseProvider {
         &self,
         logs: Vec<(TxNumber, Vec<Log>)>,
     ) -> Result<HashMap<Address, Vec<TxLogId>>> {
-        // the ID of a saved log is the composition of the txNumber_logNumber starting from zero
+        // a saved log is identified by its txNumber and its index in the tx starting from zero
         let mut tx_log_ids = vec![];
 
         let mut logs_by_address: HashMap<Address, Vec<TxLogId>> = HashMap::new();
@@ -439,16 +325,59 @@ impl LogsWriter for DatabaseProvider {
             self.db.dae_put::<TransactionLogs>(tx.0, tx_logs)?;
         }
 
-        self.insert_logs_by_address(&logs_by_address).unwrap();
-        self.decode_and_store_logs(&logs_by_address).unwrap();
+        self.insert_logs_by_address(&logs_by_address)?;
+        self.decode_and_store_logs(&logs_by_address)?;
 
         Ok(logs_by_address)
     }
+
+    fn remove_logs_above(&self, block_number: BlockNumber, first_tx: TxNumber) -> Result<()> {
+        let mut addresses = HashSet::new();
+
+        self.remove_from::<TransactionLogs>(first_tx, |(_, tx_logs)| {
+            for log_id in tx_logs.log_ids.into_iter().filter(|id| id.tx >= first_tx) {
+                if let Some(log) = self.db.dae_get::<Logs>(log_id)? {
+                    addresses.insert(log.address);
+                }
+
+                self.db.dae_delete::<Logs>(log_id)?;
+                self.db.dae_delete::<DecodedLogs>(log_id)?;
+            }
+
+            Ok(())
+        })?;
+
+        self.remove_from::<BlockLogs>(block_number + 1, |_| Ok(()))?;
+
+        // shards are keyed by their highest block, so only the ones above the target need trimming
+        for address in addresses {
+            let shards = self
+                .db
+                .dae_cursor::<ContractLogs>()?
+                .walk(ShardedKey::new(address, block_number + 1)..=ShardedKey::last(address))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let mut log_ids = Vec::new();
+            for (key, shard) in shards {
+                self.db.dae_delete::<ContractLogs>(key)?;
+                log_ids.extend(shard.log_ids.into_iter().filter(|id| id.tx < first_tx));
+            }
+
+            // the trimmed tail becomes the open shard, keying it by its last block could
+            // overwrite a full shard ending in the same block
+            if !log_ids.is_empty() {
+                self.db.dae_put::<ContractLogs>(ShardedKey::last(address), TxLogs { log_ids })?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use crate::traits::{AbiProvider, LogsProvider, LogsWriter};
+    use db::tables::models::sharded_key::NUM_OF_INDICES_IN_SHARD;
     use crate::{providers::options::AccessType, DatabaseProvider};
     use db::tables::models::AbiContract;
     use db::tables::AbiData;
@@ -529,6 +458,24 @@ mod test {
         assert_eq!(logs.unwrap().len(), tx_ids.len());
     }
 
+    #[test]
+    fn logs_by_tx_id_only_matches_its_tx() {
+        let provider = get_provider();
+        let stored_log = simp_primitives::StoredLog::default();
+
+        for tx in [1, 10, 100, 2] {
+            for log in 0..12 {
+                provider
+                    .insert_raw_logs((TxLogId::from((tx, log, tx)), stored_log.clone()))
+                    .unwrap();
+            }
+        }
+
+        let logs = provider.logs_by_tx_id(1, false).unwrap();
+
+        assert_eq!(logs.unwrap().len(), 12);
+    }
+
     #[test]
     fn insert_decoded_data_and_retrieve() {
         let provider = get_provider();
@@ -606,4 +553,105 @@ mod test {
         assert!(logs_by_address.len() > 0);
 
     }
+
+    #[test]
+    fn insert_logs_by_address_across_blocks() {
+        let provider = get_provider();
+        let address = Address::repeat_byte(0xbb);
+        let logs = |block_number, count| {
+            vec![Log { address, block_number, ..Default::default() }; count]
+        };
+
+        // the second block fits in the open shard
+        provider.insert_logs(vec![(1, logs(1, 3))]).unwrap();
+        provider.insert_logs(vec![(2, logs(2, 2))]).unwrap();
+
+        let by_address = provider.logs_by_address(address, None, None, false).unwrap();
+        assert_eq!(by_address.len(), 5);
+
+        // the third block fills up the open shard and opens the next one
+        provider.insert_logs(vec![(3, logs(3, NUM_OF_INDICES_IN_SHARD))]).unwrap();
+        provider.insert_logs(vec![(4, logs(4, 1))]).unwrap();
+
+        let by_address = provider.logs_by_address(address, None, None, false).unwrap();
+        assert_eq!(by_address.len(), NUM_OF_INDICES_IN_SHARD + 6);
+
+        let by_address = provider.logs_by_address(address, Some(3), Some(3), false).unwrap();
+        assert_eq!(by_address.len(), NUM_OF_INDICES_IN_SHARD);
+    }
+
+    #[test]
+    fn remove_logs_above_target() {
+        let provider = get_provider();
+        let logs = get_uni_factory_logs();
+        let mut logs: Vec<Log> = serde_json::from_str(&logs).unwrap();
+
+        let half = logs.len() / 2;
+        for (i, log) in logs.iter_mut().enumerate() {
+            log.block_number = if i < half { 1 } else { 2 };
+        }
+
+        provider
+            .insert_logs(vec![(1, logs[..half].to_vec()), (2, logs[half..].to_vec())])
+            .unwrap();
+
+        provider.remove_logs_above(1, 2).unwrap();
+
+        let kept = provider.logs_by_tx_id(1, false).unwrap().unwrap();
+        assert_eq!(kept.len(), half);
+
+        let removed = provider.logs_by_tx_id(2, false).unwrap().unwrap();
+        assert!(removed.is_empty());
+
+        let by_address = provider
+            .logs_by_address(logs[0].address, None, None, false)
+            .unwrap();
+        assert_eq!(by_address.len(), half);
+    }
+
+    #[test]
+    fn remove_logs_above_block_straddling_shards() {
+        let provider = get_provider();
+        let address = Address::repeat_byte(0xaa);
+        let logs = |block_number, count| {
+            vec![Log { address, block_number, ..Default::default() }; count]
+        };
+
+        // the first shard fills up in the middle of block 5, whose other logs open the next one
+        provider
+            .insert_logs(vec![
+                (1, logs(4, NUM_OF_INDICES_IN_SHARD - 10)),
+                (2, logs(5, 20)),
+                (3, logs(6, 5)),
+            ])
+            .unwrap();
+
+        provider.remove_logs_above(5, 3).unwrap();
+
+        let by_address = provider.logs_by_address(address, None, None, false).unwrap();
+        assert_eq!(by_address.len(), NUM_OF_INDICES_IN_SHARD + 10);
+    }
+
+    #[test]
+    fn remove_logs_above_full_and_open_shards() {
+        let provider = get_provider();
+        let address = Address::repeat_byte(0xaa);
+        let logs = |block_number, count| {
+            vec![Log { address, block_number, ..Default::default() }; count]
+        };
+
+        // the full shard ends in block 6, above the target, and the open shard follows it
+        provider
+            .insert_logs(vec![
+                (1, logs(4, NUM_OF_INDICES_IN_SHARD - 10)),
+                (2, logs(6, 20)),
+                (3, logs(7, 5)),
+            ])
+            .unwrap();
+
+        provider.remove_logs_above(5, 2).unwrap();
+
+        let by_address = provider.logs_by_address(address, None, None, false).unwrap();
+        assert_eq!(by_address.len(), NUM_OF_INDICES_IN_SHARD - 10);
+    }
 }
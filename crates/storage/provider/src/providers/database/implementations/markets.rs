@@ -11,7 +11,7 @@ use simp_primitives::{H256, Market, TokenMarkets};
 
 
 
-impl MarketProvider for DatabaseProvider {
+impl<TX: DbTx> MarketProvider for DatabaseProvider<TX> {
     fn create_market(&self, market: Market, market_address: H256) -> Result<()> {
         self.db.dae_put::<MarketProtocol>(market_address, market.clone())?;
         for token in market.input_tokens {
@@ -22,6 +22,15 @@ impl MarketProvider for DatabaseProvider {
     }
 
     fn delete_market(&self, market_address: H256) -> Result<()> {
+        if let Some(market) = self.db.dae_get::<MarketProtocol>(market_address)? {
+            for token in market.input_tokens {
+                if let Some(mut token_markets) = self.db.dae_get::<TokensMarkets>(token)? {
+                    token_markets.market_addresses.retain(|address| *address != market_address);
+                    self.db.dae_put::<TokensMarkets>(token, token_markets)?;
+                }
+            }
+        }
+
         self.db.dae_delete::<MarketProtocol>(market_address)?;
 
         Ok(())
@@ -140,5 +149,10 @@ mod test {
             Some(markets) => assert!(markets.market_addresses.len() == 2),
             _ => panic!()
         }
+
+        provider.delete_market(H256::from_str("0x0000000000000000000000000000000000000000000000000000000000000001").unwrap()).expect("Expect to delete market");
+
+        let token_markets = provider.get_token_markets(new_market.input_tokens[0]).expect("Expect to retrieve token markets");
+        assert_eq!(token_markets.unwrap().market_addresses, vec![H256::from_str("0x0000000000000000000000000000000000000000000000000000000000000002").unwrap()]);
     }
 }
\ No newline at end of file
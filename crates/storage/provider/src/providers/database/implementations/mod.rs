@@ -1,5 +1,7 @@
 pub mod logs;
 pub mod transactions;
+pub mod receipts;
+pub mod traces;
 pub mod block_body_indices;
 pub mod block_hash;
 pub mod block;
@@ -2,8 +2,6 @@ use crate::DatabaseProvider;
 use crate::traits::ProtocolProvider;
 use db::tables::Protocols;
 use interfaces::Result;
-use rocksdb::ReadOptions;
-use db::tables::utils::decoder;
 use db::transaction::DbTx;
 // use db::table::Encode;
 // use db::tables::models::VolumeKeysWithData;
@@ -11,24 +9,13 @@ use simp_primitives::{Protocol, ProtocolStatus,H256};
 
 
 
-impl ProtocolProvider for DatabaseProvider {
-    fn create_protocol(&self, factory_address:H256) -> Result<()> {
+impl<TX: DbTx> ProtocolProvider for DatabaseProvider<TX> {
+    fn create_protocol(&self, factory_address:H256) -> Result<u64> {
         
         // get new_id
-        let mut opts = ReadOptions::default();
-        opts.set_iterate_range(..);
-        let mut iter = self.db.dae_new_cursor::<Protocols>(opts).unwrap();
-        iter.seek_to_last();
-
-
-        // TODO: double check this works
-        let new_id = if iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-            let kv = decoder::<Protocols>((k.as_ref().to_vec(), value.as_ref().to_vec())).unwrap();
-            kv.0 + 1
-        } else {
-            1 as u64
+        let new_id = match self.db.dae_get_last::<Protocols>()? {
+            Some((last_id, _)) => last_id + 1,
+            None => 1 as u64,
         };
 
         let protocol = Protocol {
@@ -44,7 +31,7 @@ impl ProtocolProvider for DatabaseProvider {
 
 
         self.db.dae_put::<Protocols>(new_id, protocol)?;
-        Ok(())
+        Ok(new_id)
     }
 
     fn delete_protocol(&self, protocol_id: u64) -> Result<()> {
@@ -58,20 +45,11 @@ impl ProtocolProvider for DatabaseProvider {
     }
 
     fn get_all_protocols(&self) -> Result<Vec<Protocol>> {
-        let mut protocols = vec!();
-        let mut opts = ReadOptions::default();
-        opts.set_iterate_range(..);
-        let mut iter = self.db.dae_new_cursor::<Protocols>(opts).unwrap();
-
-        iter.seek_to_first();
-
-        while iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-            let kv = decoder::<Protocols>((k.as_ref().to_vec(), value.as_ref().to_vec())).unwrap();
-            protocols.push(kv.1);
-            iter.next();
-        }
+        let mut cursor = self.db.dae_cursor::<Protocols>()?;
+        let protocols = cursor
+            .walk(..)?
+            .map(|entry| entry.map(|(_, protocol)| protocol))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(protocols)
     }
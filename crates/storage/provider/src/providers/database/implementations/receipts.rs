@@ -0,0 +1,94 @@
+use crate::traits::{BlockBodyIndicesProvider, ReceiptsProvider, ReceiptsWriter, TransactionsProvider};
+use crate::DatabaseProvider;
+use db::tables::Receipts;
+use db::transaction::DbTx;
+use interfaces::Result;
+use simp_primitives::{BlockHashOrNumber, Receipt, TxHash, TxNumber};
+
+impl<TX: DbTx> ReceiptsProvider for DatabaseProvider<TX> {
+    fn receipt(&self, id: TxNumber) -> Result<Option<Receipt>> {
+        Ok(self.db.dae_get::<Receipts>(id)?)
+    }
+
+    fn receipt_by_hash(&self, hash: TxHash) -> Result<Option<Receipt>> {
+        match self.transaction_id(hash)? {
+            Some(id) => self.receipt(id),
+            None => Ok(None),
+        }
+    }
+
+    fn receipts_by_block(&self, block: BlockHashOrNumber) -> Result<Option<Vec<Receipt>>> {
+        match self.block_body_indices(block)? {
+            None => Ok(None),
+            Some(index) => {
+                let mut cursor = self.db.dae_cursor::<Receipts>()?;
+                let receipts = cursor
+                    .walk(index.first_tx_num..index.next_tx_num())?
+                    .map(|entry| entry.map(|(_, receipt)| receipt))
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+
+                Ok(Some(receipts))
+            }
+        }
+    }
+}
+
+impl<TX: DbTx> ReceiptsWriter for DatabaseProvider<TX> {
+    fn insert_receipts(&self, first_tx: TxNumber, receipts: Vec<Receipt>) -> Result<()> {
+        for (tx_num, receipt) in (first_tx..).zip(receipts) {
+            self.db.dae_put::<Receipts>(tx_num, receipt)?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_receipts_from(&self, first_tx: TxNumber) -> Result<()> {
+        self.remove_from::<Receipts>(first_tx, |_| Ok(()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::providers::options::AccessType;
+    use crate::traits::{BlockBodyIndicesWriter, ReceiptsProvider, ReceiptsWriter};
+    use crate::DatabaseProvider;
+    use db::tables::BlockBodyIndices;
+    use db::{init_db, test_utils::ERROR_TEMPDIR};
+    use simp_primitives::{BlockHashOrNumber, Receipt};
+
+    fn get_provider() -> DatabaseProvider {
+        let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
+        DatabaseProvider::new(db, AccessType::Primary)
+    }
+
+    fn receipt(gas_used: u64) -> Receipt {
+        Receipt {
+            success: true,
+            cumulative_gas_used: gas_used,
+            gas_used,
+            effective_gas_price: 1_000_000_000,
+            contract_address: None,
+        }
+    }
+
+    #[test]
+    fn insert_and_remove_receipts() {
+        let provider = get_provider();
+
+        provider.insert_block_body_indices(1, BlockBodyIndices { first_tx_num: 1, tx_count: 2 }).unwrap();
+        provider.insert_block_body_indices(2, BlockBodyIndices { first_tx_num: 3, tx_count: 1 }).unwrap();
+        provider.insert_receipts(1, vec![receipt(21_000), receipt(50_000), receipt(70_000)]).unwrap();
+
+        let receipts = provider.receipts_by_block(BlockHashOrNumber::Number(1)).unwrap().unwrap();
+        assert_eq!(receipts, vec![receipt(21_000), receipt(50_000)]);
+        assert_eq!(provider.receipt(3).unwrap(), Some(receipt(70_000)));
+
+        provider.remove_receipts_from(3).unwrap();
+
+        assert!(provider.receipt(3).unwrap().is_none());
+        assert_eq!(provider.receipts_by_block(BlockHashOrNumber::Number(2)).unwrap(), Some(vec![]));
+        assert!(provider.receipt(2).unwrap().is_some());
+    }
+}
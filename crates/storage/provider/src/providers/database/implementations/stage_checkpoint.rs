@@ -2,17 +2,22 @@ use db::{transaction::DbTx, tables::SyncStage};
 
 use crate::{traits::{StageCheckpointProvider, StageCheckpointWriter}, DatabaseProvider};
 
-impl StageCheckpointProvider for DatabaseProvider {
+impl<TX: DbTx> StageCheckpointProvider for DatabaseProvider<TX> {
     fn get_stage_checkpoint(&self,id: simp_primitives::StageId) -> interfaces::Result<Option<simp_primitives::BlockNumber> > {
-        let bn = self.db.dae_get::<SyncStage>(id.to_string());
+        let bn = self.db.dae_get::<SyncStage>(id.to_string())?;
 
-        Ok(bn.unwrap())
+        Ok(bn)
     }
 }
 
-impl StageCheckpointWriter for DatabaseProvider {
+impl<TX: DbTx> StageCheckpointWriter for DatabaseProvider<TX> {
     fn save_stage_checkpoint(&self,id: simp_primitives::StageId, checkpoint: simp_primitives::BlockNumber) -> interfaces::Result<()> {
-        self.db.dae_put::<SyncStage>(id.to_string(), checkpoint).unwrap();
+        self.db.dae_put::<SyncStage>(id.to_string(), checkpoint)?;
+        Ok(())
+    }
+
+    fn delete_stage_checkpoint(&self, id: simp_primitives::StageId) -> interfaces::Result<()> {
+        self.db.dae_delete::<SyncStage>(id.to_string())?;
         Ok(())
     }
 }
\ No newline at end of file
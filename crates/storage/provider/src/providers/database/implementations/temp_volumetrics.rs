@@ -1,86 +1,46 @@
 use crate::{DatabaseProvider, traits::TempVolumetrics};
 use crate::traits::Timeframe;
-use db::tables::{TempPeriodVolumesFive, ShardedKey, TempPeriodVolumesHour};
+use db::tables::{models::TimeframeKey, TempPeriodVolumes, ShardedKey};
 use interfaces::Result;
 use db::transaction::DbTx;
-use simp_primitives::{PeriodVolumes, Volumetric,H256};
-
+use simp_primitives::{MarketAddress, PeriodVolumes, Volumetric, H256};
 
+/// Key of the temporary volumes of the market for the period of `timeframe` starting at
+/// `timestamp`.
+fn temp_volumes_key(market_address: &H256, timestamp: u64, timeframe: Timeframe) -> TimeframeKey<ShardedKey<MarketAddress>> {
+    TimeframeKey::new(timeframe, ShardedKey::new(*market_address, timestamp))
+}
 
-impl TempVolumetrics for DatabaseProvider {
+impl<TX: DbTx> TempVolumetrics for DatabaseProvider<TX> {
 
-    fn get_temp_volumetric (&self, market_address: &H256, timestamp: u64, timeframe:crate::traits::Timeframe) -> Result<Option<PeriodVolumes>> {
-        let result = match timeframe {
-          Timeframe::FiveMinute => self.db.dae_get::<TempPeriodVolumesFive>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp}),
-          Timeframe::Hourly => self.db.dae_get::<TempPeriodVolumesHour>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp}),
-          _ => panic!("Timeframe not valid")
-        }?;
-        Ok(result)
+    fn get_temp_volumetric (&self, market_address: &H256, timestamp: u64, timeframe: Timeframe) -> Result<Option<PeriodVolumes>> {
+        Ok(self.db.dae_get::<TempPeriodVolumes>(temp_volumes_key(market_address, timestamp, timeframe))?)
     }
 
-    fn set_temp_volumetric (&self, market_address: &H256, timestamp: u64, timeframe:crate::traits::Timeframe, period_volume: Volumetric) -> Result<()> {
-      let  existing = self.get_temp_volumetric(market_address, timestamp, timeframe)?;
-
-      match existing {
-        Some(mut e) => {
-          e.volumes.push(period_volume);
-          let _ = match timeframe {
-            Timeframe::FiveMinute => self.db.dae_put::<TempPeriodVolumesFive>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp},e),
-            Timeframe::Hourly => self.db.dae_put::<TempPeriodVolumesHour>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp},e),
-            _ => panic!("Timeframe not valid")
-          }?;
-        },
-        _ => {
-          let new: PeriodVolumes = PeriodVolumes{volumes:vec![period_volume]};
-          let _ = match timeframe {
-            Timeframe::FiveMinute => self.db.dae_put::<TempPeriodVolumesFive>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp},new),
-            Timeframe::Hourly => self.db.dae_put::<TempPeriodVolumesHour>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp},new),
-            _ => panic!("Timeframe not valid")
-          }?;
-        }
-      }
-      
-      
-      Ok(())
-      
+    fn set_temp_volumetric (&self, market_address: &H256, timestamp: u64, timeframe: Timeframe, period_volume: Volumetric) -> Result<()> {
+      self.write_period_volumes(market_address, timestamp, vec![period_volume], timeframe)
     }
 
-    fn read_period_volumes (&self, market_address: &H256, timestamp: u64, timeframe: crate::traits::Timeframe) -> Result<Option<PeriodVolumes>> {        
+    fn read_period_volumes (&self, market_address: &H256, timestamp: u64, timeframe: Timeframe) -> Result<Option<PeriodVolumes>> {        
         let res = self.get_temp_volumetric(market_address, timestamp, timeframe)?;
         Ok(res)
     }
 
-    fn write_period_volumes (&self, market_address: &H256, timestamp: u64,volumes: Vec<Volumetric>, timeframe: crate::traits::Timeframe) -> Result<()> {
-        let existing = self.get_temp_volumetric(market_address, timestamp, timeframe)?;
-
-        match existing  {
+    fn write_period_volumes (&self, market_address: &H256, timestamp: u64,volumes: Vec<Volumetric>, timeframe: Timeframe) -> Result<()> {
+        let period_volumes = match self.get_temp_volumetric(market_address, timestamp, timeframe)? {
           Some(mut pv) => {
               pv.volumes.extend(volumes);
-              let _ = match timeframe {
-                Timeframe::FiveMinute => self.db.dae_put::<TempPeriodVolumesFive>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp},pv),
-                Timeframe::Hourly => self.db.dae_put::<TempPeriodVolumesHour>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp},pv),
-                _ => panic!("Timeframe not valid")
-              }?;
+              pv
           },
-          _ => {
-            let new: PeriodVolumes = PeriodVolumes{volumes};
-            let _ = match timeframe {
-              Timeframe::FiveMinute => self.db.dae_put::<TempPeriodVolumesFive>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp},new),
-              Timeframe::Hourly => self.db.dae_put::<TempPeriodVolumesHour>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp},new),
-              _ => panic!("Timeframe not valid")
-            }?;
-          }
-        }
-        
+          None => PeriodVolumes{volumes},
+        };
+
+        self.db.dae_put::<TempPeriodVolumes>(temp_volumes_key(market_address, timestamp, timeframe), period_volumes)?;
         Ok(())
     }
 
-    fn delete_period_volumes (&self, market_address: &H256, timestamp: u64, timeframe: crate::traits::Timeframe) -> Result<()> {
-      let _ = match timeframe {
-        Timeframe::FiveMinute => self.db.dae_delete::<TempPeriodVolumesFive>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp}),
-        Timeframe::Hourly => self.db.dae_delete::<TempPeriodVolumesHour>(ShardedKey{key: market_address.clone(),max_shard_value:timestamp}),
-        _ => panic!("Timeframe not valid")
-      }?;
+    fn delete_period_volumes (&self, market_address: &H256, timestamp: u64, timeframe: Timeframe) -> Result<()> {
+      self.db.dae_delete::<TempPeriodVolumes>(temp_volumes_key(market_address, timestamp, timeframe))?;
       Ok(())
     }
 
@@ -183,5 +143,30 @@ mod test {
   
   }
 
+  #[test]
+  fn timeframes_are_kept_apart()  {
+    let provider = get_provider();
+    let volume = Volumetric {
+        timestamp: 1,
+        market_address: H256::zero(),
+        swaps_out: vec![],
+        swaps_in: vec![],
+        withdrawal: vec![],
+        mint: vec![],
+        transfer: U256::from(0),
+    };
+
+    for (count, timeframe) in Timeframe::ALL.iter().enumerate() {
+      provider.write_period_volumes(&H256::zero(), 0, vec![volume.clone(); count + 1], *timeframe).unwrap();
+    }
+    provider.delete_period_volumes(&H256::zero(), 0, Timeframe::FiveMinute).unwrap();
+
+    assert_eq!(provider.read_period_volumes(&H256::zero(), 0, Timeframe::FiveMinute).unwrap(), None);
+    for (count, timeframe) in Timeframe::ALL.iter().enumerate().skip(1) {
+      let volumes = provider.read_period_volumes(&H256::zero(), 0, *timeframe).unwrap().unwrap();
+      assert_eq!(volumes.volumes.len(), count + 1);
+    }
+  }
+
 }
  
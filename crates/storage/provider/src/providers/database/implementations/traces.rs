@@ -0,0 +1,164 @@
+use std::collections::BTreeSet;
+
+use crate::traits::{BlockBodyIndicesProvider, TracesProvider, TracesWriter};
+use crate::DatabaseProvider;
+use db::tables::{AddressTraces, Traces, TxTraces};
+use db::transaction::DbTx;
+use interfaces::Result;
+use simp_primitives::{Address, BlockHashOrNumber, Trace, TxNumber};
+
+impl<TX: DbTx> TracesProvider for DatabaseProvider<TX> {
+    fn traces_by_tx_id(&self, id: TxNumber) -> Result<Option<Vec<Trace>>> {
+        Ok(self.db.dae_get::<Traces>(id)?.map(|traces| traces.traces))
+    }
+
+    fn traces_by_block(&self, block: BlockHashOrNumber) -> Result<Option<Vec<(TxNumber, Vec<Trace>)>>> {
+        match self.block_body_indices(block)? {
+            None => Ok(None),
+            Some(index) => {
+                let mut cursor = self.db.dae_cursor::<Traces>()?;
+                let traces = cursor
+                    .walk(index.first_tx_num..index.next_tx_num())?
+                    .map(|entry| entry.map(|(tx, traces)| (tx, traces.traces)))
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+
+                Ok(Some(traces))
+            }
+        }
+    }
+
+    fn internal_call_transactions(
+        &self,
+        address: Address,
+        from: Option<TxNumber>,
+        to: Option<TxNumber>,
+    ) -> Result<Vec<TxNumber>> {
+        let max = to.unwrap_or(TxNumber::MAX);
+        let mut txs = Vec::new();
+
+        let mut cursor = self.db.dae_dup_cursor::<AddressTraces>()?;
+
+        for entry in cursor.walk_dup(address, Some(from.unwrap_or(0)))? {
+            let (tx, _) = entry?;
+
+            if tx > max {
+                break
+            }
+            txs.push(tx);
+        }
+
+        Ok(txs)
+    }
+}
+
+impl<TX: DbTx> TracesWriter for DatabaseProvider<TX> {
+    fn insert_traces(&self, traces: Vec<(TxNumber, Vec<Trace>)>) -> Result<()> {
+        for (tx, traces) in traces {
+            let traces = TxTraces { traces };
+
+            for address in traces.internal_call_addresses() {
+                self.db.dae_put_dup::<AddressTraces>(address, tx, tx)?;
+            }
+
+            self.db.dae_put::<Traces>(tx, traces)?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_traces_from(&self, first_tx: TxNumber) -> Result<()> {
+        let mut addresses = BTreeSet::new();
+        self.remove_from::<Traces>(first_tx, |(_, traces)| {
+            addresses.extend(traces.internal_call_addresses());
+            Ok(())
+        })?;
+
+        for address in addresses {
+            // the cursor is closed before deleting, writes are refused while it is open
+            let txs = self
+                .db
+                .dae_dup_cursor::<AddressTraces>()?
+                .walk_dup(address, Some(first_tx))?
+                .map(|entry| entry.map(|(tx, _)| tx))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            for tx in txs {
+                self.db.dae_delete_dup::<AddressTraces>(address, tx)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::providers::options::AccessType;
+    use crate::traits::{BlockBodyIndicesWriter, TracesProvider, TracesWriter};
+    use crate::DatabaseProvider;
+    use db::tables::BlockBodyIndices;
+    use db::{init_db, test_utils::ERROR_TEMPDIR};
+    use simp_primitives::{Address, BlockHashOrNumber, Trace};
+
+    fn get_provider() -> DatabaseProvider {
+        let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
+        DatabaseProvider::new(db, AccessType::Primary)
+    }
+
+    /// A transaction from `0x01..` calling `to`, which calls `internal`.
+    fn traces(to: u8, internal: u8) -> Vec<Trace> {
+        vec![
+            Trace {
+                from: Address::repeat_byte(1),
+                to: Some(Address::repeat_byte(to)),
+                ..Default::default()
+            },
+            Trace {
+                from: Address::repeat_byte(to),
+                to: Some(Address::repeat_byte(internal)),
+                trace_address: vec![0],
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn index_internal_calls() {
+        let provider = get_provider();
+        let callee = Address::repeat_byte(3);
+
+        provider.insert_block_body_indices(1, BlockBodyIndices { first_tx_num: 1, tx_count: 2 }).unwrap();
+        provider.insert_traces(vec![(1, traces(2, 3)), (2, traces(4, 5))]).unwrap();
+        provider.insert_traces(vec![(3, traces(2, 3))]).unwrap();
+
+        let block = provider.traces_by_block(BlockHashOrNumber::Number(1)).unwrap().unwrap();
+        assert_eq!(block, vec![(1, traces(2, 3)), (2, traces(4, 5))]);
+
+        assert_eq!(provider.internal_call_transactions(callee, None, None).unwrap(), vec![1, 3]);
+        assert_eq!(provider.internal_call_transactions(callee, Some(2), None).unwrap(), vec![3]);
+        assert_eq!(provider.internal_call_transactions(callee, None, Some(2)).unwrap(), vec![1]);
+        // the sender only takes part in the transaction itself
+        assert!(provider.internal_call_transactions(Address::repeat_byte(1), None, None).unwrap().is_empty());
+
+        provider.remove_traces_from(2).unwrap();
+
+        assert!(provider.traces_by_tx_id(3).unwrap().is_none());
+        assert_eq!(provider.internal_call_transactions(callee, None, None).unwrap(), vec![1]);
+        assert!(provider.internal_call_transactions(Address::repeat_byte(5), None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn remove_pending_traces() {
+        let provider = get_provider();
+
+        // the removal walks the rows and entries written earlier in the same transaction
+        let tx = provider.tx_mut();
+        tx.insert_traces(vec![(1, traces(2, 3)), (2, traces(2, 3))]).unwrap();
+        tx.remove_traces_from(2).unwrap();
+        tx.commit().unwrap();
+
+        assert!(provider.traces_by_tx_id(2).unwrap().is_none());
+        let txs = provider.internal_call_transactions(Address::repeat_byte(3), None, None).unwrap();
+        assert_eq!(txs, vec![1]);
+    }
+}
@@ -6,14 +6,14 @@ use crate::traits::{TrackingWriter, TrackingProvider};
 use crate::DatabaseProvider;
 use interfaces::Result;
 
-impl TrackingProvider for DatabaseProvider {
+impl<TX: DbTx> TrackingProvider for DatabaseProvider<TX> {
     fn is_contract_tracked(&self, address: Address) -> Result<bool> {
         let tracked = self.db.dae_get::<tables::TrackedContracts>(address)?;
         Ok(tracked.is_some())
     }
 }
 
-impl TrackingWriter for DatabaseProvider {
+impl<TX: DbTx> TrackingWriter for DatabaseProvider<TX> {
     fn insert_tracked_contract(&self, address: Address) -> Result<()> {
         let ts = OffsetDateTime::now_utc().microsecond();
         self.db.dae_put::<tables::TrackedContracts>(address, ts)?;
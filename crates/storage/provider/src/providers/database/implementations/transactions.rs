@@ -1,14 +1,14 @@
 use crate::DatabaseProvider;
-use crate::traits::{TransactionsProvider, TransactionsWriter, BlockNumReader};
+use crate::traits::{
+    TransactionsProvider, TransactionsWriter, BlockNumReader, MAX_TRANSACTIONS_BLOCK_RANGE,
+    MAX_TRANSACTIONS_TX_RANGE,
+};
 use db::tables::{TransactionBlock, self, TxHashNumber, BlockIndices, Transactions, TxIndices};
-use interfaces::Result;
+use interfaces::{Error, Result};
 use simp_primitives::{BlockNumber, TransactionSigned, BlockHashOrNumber, TxNumber, TxHash};
-use rocksdb::ReadOptions;
-use db::tables::utils::decoder;
 use db::transaction::DbTx;
-use db::table::Encode;
 
-impl TransactionsProvider for DatabaseProvider {
+impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
     fn transaction_block(&self, id: simp_primitives::TxNumber) -> Result<Option<BlockNumber>> {
         Ok(self.db.dae_get::<TransactionBlock>(id)?)
     }
@@ -67,24 +67,15 @@ impl TransactionsProvider for DatabaseProvider {
         start: BlockNumber,
         end: BlockNumber,
     ) -> Result<Vec<Vec<TransactionSigned>>> {
-        if end - start > 50 {
-            panic!("Range too big");
+        if end.saturating_sub(start) > MAX_TRANSACTIONS_BLOCK_RANGE {
+            return Err(Error::RangeTooLarge { start, end, max: MAX_TRANSACTIONS_BLOCK_RANGE })
         }
 
         let mut txs = Vec::new();
-        let mut opts = ReadOptions::default();
-        opts.set_iterate_range(start.encode().as_slice()..end.encode().as_slice());
+        let mut cursor = self.db.dae_cursor::<BlockIndices>()?;
 
-        let mut iter = self.db.dae_new_cursor::<BlockIndices>(opts).unwrap();
-        iter.seek_to_first();
-
-        while iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-
-            let kv = decoder::<BlockIndices>((k.to_vec(), value.to_vec())).unwrap();
-
-            let index = kv.1;
+        for entry in cursor.walk(start..end)? {
+            let (_, index) = entry?;
             let tx_start = index.first_tx_num;
             let tx_end = index.last_tx_num();
 
@@ -99,7 +90,6 @@ impl TransactionsProvider for DatabaseProvider {
             }
 
             txs.push(txs_block);
-            iter.next();
         }
 
         Ok(txs)
@@ -110,45 +100,26 @@ impl TransactionsProvider for DatabaseProvider {
         start: simp_primitives::TxNumber,
         end: simp_primitives::TxNumber,
     ) -> Result<Vec<TransactionSigned>> {
-        if end - start > 10000 {
-            panic!("Range too big");
+        if end.saturating_sub(start) > MAX_TRANSACTIONS_TX_RANGE {
+            return Err(Error::RangeTooLarge { start, end, max: MAX_TRANSACTIONS_TX_RANGE })
         }
 
-        let mut txs = Vec::new();
-        let mut opts = ReadOptions::default();
-        opts.set_iterate_range(start.encode().as_slice()..end.encode().as_slice());
-
-        let mut iter = self.db.dae_new_cursor::<Transactions>(opts).unwrap();
-        iter.seek_to_first();
-
-        while iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-
-            let kv = decoder::<Transactions>((k.to_vec(), value.to_vec())).unwrap();
-            txs.push(kv.1);
-            iter.next();
-        }
+        let mut cursor = self.db.dae_cursor::<Transactions>()?;
+        let txs = cursor
+            .walk(start..end)?
+            .map(|entry| entry.map(|(_, tx)| tx))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(txs)
     }
 }
 
-impl TransactionsWriter for DatabaseProvider {
+impl<TX: DbTx> TransactionsWriter for DatabaseProvider<TX> {
     // ATTENTION: this method must be called per block.
     fn insert_transactions(&self, transactions: Vec<TransactionSigned>) -> Result<(TxIndices, Vec<(TxNumber, TxHash)>)> {
-        let opts = ReadOptions::default();
-        let mut latest_tx = self.db.dae_new_cursor::<Transactions>(opts)?;
-        latest_tx.seek_to_last();
-
-        let latest_key = match latest_tx.valid() {
-            true => {
-                let (key, val) = (latest_tx.key().unwrap(), latest_tx.value().unwrap());
-                let latest_key = decoder::<Transactions>((key.to_vec(), val.to_vec())).unwrap();
-                latest_key.0
-            },
-            // TODO: error handling, dont assueme its just empty
-            false => 0,
+        let latest_key = match self.db.dae_get_last::<Transactions>()? {
+            Some((latest_key, _)) => latest_key,
+            None => 0,
         };
 
         let mut key_holder = latest_key.clone() + 1;
@@ -169,6 +140,17 @@ impl TransactionsWriter for DatabaseProvider {
             tx_count: transactions.len() as u64,
         }, num_hash))
     }
+
+    fn remove_transactions_from(&self, first_tx: TxNumber) -> Result<()> {
+        self.remove_from::<Transactions>(first_tx, |(_, tx)| {
+            self.db.dae_delete::<TxHashNumber>(tx.hash())?;
+            Ok(())
+        })?;
+
+        self.remove_from::<TransactionBlock>(first_tx, |_| Ok(()))?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)] 
@@ -180,8 +162,12 @@ mod tests {
     use db::{init_db, test_utils::ERROR_TEMPDIR};
     use simp_primitives::TransactionSigned;
     use serde_json::Value;
-    use crate::traits::{TransactionsWriter, TransactionsProvider, BlockBodyIndicesWriter};
+    use crate::traits::{
+        TransactionsWriter, TransactionsProvider, BlockBodyIndicesWriter,
+        MAX_TRANSACTIONS_BLOCK_RANGE, MAX_TRANSACTIONS_TX_RANGE,
+    };
     use crate::DatabaseProvider;
+    use interfaces::Error;
 
     fn get_provider() -> DatabaseProvider {
         let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
@@ -286,6 +272,15 @@ mod tests {
         let txs_by_block = provider.transactions_by_block(simp_primitives::BlockHashOrNumber::Number(1)).unwrap().unwrap();
 
         assert_eq!(txs_by_block.len(), 1);
+
+        assert!(matches!(
+            provider.transactions_by_block_range(0, MAX_TRANSACTIONS_BLOCK_RANGE + 1),
+            Err(Error::RangeTooLarge { .. })
+        ));
+        assert!(matches!(
+            provider.transactions_by_tx_range(0, MAX_TRANSACTIONS_TX_RANGE + 1),
+            Err(Error::RangeTooLarge { .. })
+        ));
     }
 
     #[test]
@@ -348,6 +343,35 @@ mod tests {
         assert_eq!(tx.hash(), txs[0].hash());
     }
 
+    #[test]
+    fn test_remove_txs_from() {
+        let provider = get_provider();
+
+        let txs = get_mock_txs();
+
+        let txs: Vec<Value> = serde_json::from_str(&txs).unwrap();
+
+        let txs = txs.iter().map(|tx| {
+            let tx = TransactionSigned::from(tx.clone());
+            tx
+        }).collect::<Vec<TransactionSigned>>();
+
+        let first = provider.insert_transactions(txs.clone()).unwrap();
+        let second = provider.insert_transactions(txs.clone()).unwrap();
+
+        provider.remove_transactions_from(second.0.first_tx_num).unwrap();
+
+        assert!(provider.transaction_by_id(first.0.first_tx_num).unwrap().is_some());
+        assert!(provider.transaction_by_id(second.0.first_tx_num).unwrap().is_none());
+        assert!(provider.transaction_block(second.0.first_tx_num).unwrap().is_none());
+
+        // both inserts share the same hashes, so the lookup is gone for the kept copy as well
+        assert!(provider.transaction_id(txs[0].hash()).unwrap().is_none());
+
+        let inserted = provider.insert_transactions(txs.clone()).unwrap();
+        assert_eq!(inserted.0.first_tx_num, second.0.first_tx_num);
+    }
+
     #[test]
     fn test_tx_block() {
         let provider = get_provider();
@@ -3,24 +3,64 @@ use std::collections::HashMap;
 use crate::DatabaseProvider;
 use crate::traits::{ Timeframe, VolumetricReader, VolumetricWriter};
 use db::tables::models::sharded_key::NUM_OF_INDICES_IN_SHARD;
-use db::tables::{VolumetricsDay, VolumetricsHour, VolumetricsFiveMin, MarketVolumetricsIndicesDay, MarketVolumetricsIndicesHour, MarketVolumetricsIndicesFiveMin, TimestampVolumetricsIndicesDay, TimestampVolumetricsIndicesHour, TimestampVolumetricsIndicesFiveMin, ShardedKey};
+use db::tables::{Volumetrics, MarketVolumetricsIndices, TimestampVolumetricsIndices, ShardedKey};
 use interfaces::Result;
-use rocksdb::ReadOptions;
-use db::tables::utils::decoder;
 use db::transaction::DbTx;
-use db::tables::models::{VolumeKeysWithData, VolumeKeyWithData, VolumeKeys};
+use db::tables::models::{TimeframeKey, VolumeKeysWithData, VolumeKeyWithData, VolumeKeys};
 use simp_primitives::{H256, MarketAddress, VolumeKey, Volumetric};
 
+/// A shard of the market address index, holding the volume keys up to its max timestamp.
+type MarketIndexShard = (ShardedKey<MarketAddress>, VolumeKeysWithData);
+
+impl<TX: DbTx> DatabaseProvider<TX> {
+    /// Returns every index shard of the market, ordered by their max timestamp.
+    fn market_index_shards(&self, market_address: H256, timeframe: Timeframe) -> Result<Vec<MarketIndexShard>> {
+        let mut cursor = self.db.dae_cursor::<MarketVolumetricsIndices>()?;
+        let shards = cursor
+            .walk(
+                TimeframeKey::new(timeframe, ShardedKey::new(market_address, 0))..=
+                    TimeframeKey::new(timeframe, ShardedKey::last(market_address)),
+            )?
+            .map(|entry| entry.map(|(key, value)| (key.key, value)))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(shards)
+    }
+
+    /// Returns the index shard of the market with the highest max timestamp.
+    fn latest_market_index_shard(&self, market_address: H256, timeframe: Timeframe) -> Result<Option<MarketIndexShard>> {
+        let mut cursor = self.db.dae_cursor::<MarketVolumetricsIndices>()?;
+        let shard = cursor
+            .walk_back(Some(TimeframeKey::new(timeframe, ShardedKey::last(market_address))))?
+            .next()
+            .transpose()?;
+
+        Ok(shard
+            .filter(|(key, _)| key.timeframe == timeframe && key.key.key == market_address)
+            .map(|(key, value)| (key.key, value)))
+    }
+
+    /// Appends volume keys to the timestamp index of the timeframe.
+    fn extend_ts_index(&self, timestamp: u64, keys: Vec<VolumeKey>, timeframe: Timeframe) -> Result<()> {
+        let index_key = TimeframeKey::new(timeframe, timestamp);
+
+        let entry = match self.db.dae_get::<TimestampVolumetricsIndices>(index_key.clone())? {
+            Some(mut matched_existing) => {
+                matched_existing.volume_keys.extend(keys);
+                matched_existing
+            },
+            None => VolumeKeys { volume_keys: keys },
+        };
+        self.db.dae_put::<TimestampVolumetricsIndices>(index_key, entry)?;
 
+        Ok(())
+    }
+}
 
-impl VolumetricReader for DatabaseProvider {
+impl<TX: DbTx> VolumetricReader for DatabaseProvider<TX> {
 
     fn get_volume_helper(&self, key: VolumeKey, timeframe: Timeframe) -> Result<Option<Volumetric>> {
-        let volume = match timeframe {
-            Timeframe::Daily => self.db.dae_get::<VolumetricsDay>(key),
-            Timeframe::Hourly => self.db.dae_get::<VolumetricsHour>(key),
-            Timeframe::FiveMinute => self.db.dae_get::<VolumetricsFiveMin>(key),
-        }?;
+        let volume = self.db.dae_get::<Volumetrics>(TimeframeKey::new(timeframe, key))?;
 
         Ok(volume)
 
@@ -28,140 +68,67 @@ impl VolumetricReader for DatabaseProvider {
 
 
     fn get_market_range(&self, market_address: H256, timeframe: crate::traits::volumetric::Timeframe, from: Option<u64>, to: Option<u64>) -> Result<Vec<Volumetric>> {
-        let mut volumes = Vec::new();
-        let mut options = rocksdb::ReadOptions::default();
-        options.set_iterate_range(rocksdb::PrefixRange(market_address.as_bytes()));
-
-        let mut iter = match timeframe {
-            Timeframe::Daily =>  self.db.dae_new_cursor::<MarketVolumetricsIndicesDay>(options),
-            Timeframe::Hourly =>self.db.dae_new_cursor::<MarketVolumetricsIndicesHour>(options),
-            Timeframe::FiveMinute => self.db.dae_new_cursor::<MarketVolumetricsIndicesFiveMin>(options),
-        }?;
+        self.get_market_range_page(market_address, timeframe, from.unwrap_or_default(), to.unwrap_or(u64::MAX), usize::MAX)
+    }
 
-        iter.seek_to_first();
+    fn get_market_range_page(&self, market_address: H256, timeframe: Timeframe, from: u64, to: u64, limit: usize) -> Result<Vec<Volumetric>> {
+        let mut volumes = Vec::new();
 
-        while iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
+        // shards are keyed by their max timestamp, the first one holding `from` is the first at or
+        // above it
+        let mut cursor = self.db.dae_cursor::<MarketVolumetricsIndices>()?;
+        let shards = cursor.walk(
+            TimeframeKey::new(timeframe, ShardedKey::new(market_address, from))..=
+                TimeframeKey::new(timeframe, ShardedKey::last(market_address)),
+        )?;
 
-            let location_keys = match timeframe {
-                Timeframe::Daily => decoder::<MarketVolumetricsIndicesDay>((k.to_vec(), value.to_vec())).unwrap(),
-                Timeframe::Hourly => decoder::<MarketVolumetricsIndicesHour>((k.to_vec(), value.to_vec())).unwrap(),
-                Timeframe::FiveMinute => decoder::<MarketVolumetricsIndicesFiveMin>((k.to_vec(), value.to_vec())).unwrap(),
-            };
-            let final_volume = &location_keys.1.volume_keys[location_keys.1.volume_keys.len() - 1].clone();
-
-            match from {
-                Some(f) => {
-                    if final_volume.timestamp < f {
-                        continue
-                    }
-                },
-                _ => ()
-            }
+        for entry in shards {
+            let (_, shard) = entry?;
 
-            for key in location_keys.1.volume_keys {
-                match (from,to) {
-                    (Some(f),Some(t)) => {
-                        if key.timestamp >= f  && key.timestamp <= t{
-                            let volume = self.get_volume_helper(key.key, timeframe)?;
-                            match volume {
-                                Some(v) => volumes.push(v),
-                                None => ()
-                            }
-                        }
-                    },
-                    (Some(f), None) => {
-                        if key.timestamp >= f {
-                            let volume = self.get_volume_helper(key.key, timeframe)?;
-                            match volume {
-                                Some(v) => volumes.push(v),
-                                None => ()
-                            }
-                        }
-                    },
-                    _ => {
-                        let volume = self.get_volume_helper(key.key, timeframe)?;
-                        match volume {
-                            Some(v) => volumes.push(v),
-                            None => ()
-                        }
-                    }
+            for key in shard.volume_keys {
+                if key.timestamp < from {
+                    continue
+                }
+                if key.timestamp > to || volumes.len() >= limit {
+                    return Ok(volumes)
                 }
 
-               
-            }
-            match to {
-                Some(t) => {
-                    if final_volume.timestamp > t {
-                        break
-                    }
-                },
-                _ => ()
+                if let Some(volume) = self.get_volume_helper(key.key, timeframe)? {
+                    volumes.push(volume);
+                }
             }
-            iter.next();
         }
 
         Ok(volumes)
-
     }
 
-
-
     fn get_by_timestamp(&self, timeframe: crate::traits::volumetric::Timeframe, timestamp: u64) -> Result<Vec<Volumetric>> {
+        self.get_by_timestamp_page(timeframe, timestamp, 0, usize::MAX)
+    }
 
-        let keys = match timeframe {
-            Timeframe::Daily => self.db.dae_get::<TimestampVolumetricsIndicesDay>(timestamp),
-            Timeframe::Hourly => self.db.dae_get::<TimestampVolumetricsIndicesHour>(timestamp),
-            Timeframe::FiveMinute => self.db.dae_get::<TimestampVolumetricsIndicesFiveMin>(timestamp),
-        }?;
-
-        let mut volumes_response = Vec::new();
-
-        let _ = match keys {
-            None => (),
-            Some(volume_keys) => {
-                for v_key in volume_keys.volume_keys{
-                    let volume = self.get_volume_helper(v_key, timeframe)?;
+    fn get_by_timestamp_page(&self, timeframe: Timeframe, timestamp: u64, offset: usize, limit: usize) -> Result<Vec<Volumetric>> {
+        let keys = match self.db.dae_get::<TimestampVolumetricsIndices>(TimeframeKey::new(timeframe, timestamp))? {
+            Some(keys) => keys.volume_keys,
+            None => return Ok(Vec::new()),
+        };
 
-                    let _ = match volume {
-                        Some(matched_volume) => volumes_response.push(matched_volume),
-                        None => (),
-                    };
-                }
+        let mut volumes = Vec::new();
+        for key in keys.into_iter().skip(offset).take(limit) {
+            if let Some(volume) = self.get_volume_helper(key, timeframe)? {
+                volumes.push(volume);
             }
-        };
+        }
 
-        Ok(volumes_response)
+        Ok(volumes)
     }
 
     fn get_latest_market_volume (&self, market_address: H256,timeframe:Timeframe) -> Result<Option<Volumetric>> {
-        let mut options = rocksdb::ReadOptions::default();
-        options.set_iterate_range(rocksdb::PrefixRange(market_address.as_bytes()));
-
-        let mut iter = match timeframe {
-            Timeframe::Daily =>  self.db.dae_new_cursor::<MarketVolumetricsIndicesDay>(options),
-            Timeframe::Hourly =>self.db.dae_new_cursor::<MarketVolumetricsIndicesHour>(options),
-            Timeframe::FiveMinute => self.db.dae_new_cursor::<MarketVolumetricsIndicesFiveMin>(options),
-        }?;
-
-        iter.seek_to_last();
-
-        // market not found
-        if iter.valid() != true {
-            ()
-        }
-
-        let k = iter.key().unwrap();
-        let value = iter.value().unwrap();
-
-        let kv = match timeframe {
-            Timeframe::Daily => decoder::<MarketVolumetricsIndicesDay>((k.to_vec(), value.to_vec())).unwrap(),
-            Timeframe::Hourly => decoder::<MarketVolumetricsIndicesHour>((k.to_vec(), value.to_vec())).unwrap(),
-            Timeframe::FiveMinute => decoder::<MarketVolumetricsIndicesFiveMin>((k.to_vec(), value.to_vec())).unwrap(),
+        let kv = match self.latest_market_index_shard(market_address, timeframe)? {
+            Some(kv) => kv,
+            // market not found
+            None => return Ok(None),
         };
 
-
         let key = kv.1.volume_keys.last();
 
         let volume = match key {
@@ -181,31 +148,10 @@ impl VolumetricReader for DatabaseProvider {
 
     fn get_market_volume_keys (&self, market_address: H256, timeframe: Timeframe) -> Result<Option<Vec<VolumeKeyWithData>>> {
         let mut keys : Vec<VolumeKeyWithData> = vec![];
-        let mut options = rocksdb::ReadOptions::default();
-        options.set_iterate_range(rocksdb::PrefixRange(market_address.as_bytes()));
-        
-        let mut iter = match timeframe {
-            Timeframe::Daily =>  self.db.dae_new_cursor::<MarketVolumetricsIndicesDay>(options),
-            Timeframe::Hourly =>self.db.dae_new_cursor::<MarketVolumetricsIndicesHour>(options),
-            Timeframe::FiveMinute => self.db.dae_new_cursor::<MarketVolumetricsIndicesFiveMin>(options),
-        }?;
-
-        iter.seek_to_first();
-        
-
-        while iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-            let mut kv: (ShardedKey<H256>, VolumeKeysWithData) = match timeframe {
-                Timeframe::Daily => decoder::<MarketVolumetricsIndicesDay>((k.to_vec(), value.to_vec())).unwrap(),
-                Timeframe::Hourly => decoder::<MarketVolumetricsIndicesHour>((k.to_vec(), value.to_vec())).unwrap(),
-                Timeframe::FiveMinute => decoder::<MarketVolumetricsIndicesFiveMin>((k.to_vec(), value.to_vec())).unwrap(),
-            };
 
+        for mut kv in self.market_index_shards(market_address, timeframe)? {
             keys.append(&mut kv.1.volume_keys);
-            iter.next();
-        
-        };
+        }
 
 
         Ok(Some(keys))
@@ -216,51 +162,32 @@ impl VolumetricReader for DatabaseProvider {
     
 }
 
-impl VolumetricWriter for DatabaseProvider {
+impl<TX: DbTx> VolumetricWriter for DatabaseProvider<TX> {
 
     fn get_last_volume_id_or_default(&self, timeframe:Timeframe) -> Result<u64> {
-        let mut opts = ReadOptions::default();
-        opts.set_iterate_range(..);
-
-
-        let mut iter = match timeframe {
-            Timeframe::Daily => self.db.dae_new_cursor::<VolumetricsDay>(opts),
-            Timeframe::Hourly => self.db.dae_new_cursor::<VolumetricsHour>(opts),
-            Timeframe::FiveMinute => self.db.dae_new_cursor::<VolumetricsFiveMin>(opts),
-        }?;
-
-        iter.seek_to_last();
-        let new_key = if iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-    
-            let kv = match timeframe {
-                Timeframe::Daily => decoder::<VolumetricsDay>((k.to_vec(), value.to_vec())).unwrap(),
-                Timeframe::Hourly => decoder::<VolumetricsHour>((k.to_vec(), value.to_vec())).unwrap(),
-                Timeframe::FiveMinute => decoder::<VolumetricsFiveMin>((k.to_vec(), value.to_vec())).unwrap(),
-            };
-            kv.0 + 1
-        } else {
-            1
+        let last_key = self
+            .db
+            .dae_cursor::<Volumetrics>()?
+            .walk_back(Some(TimeframeKey::new(timeframe, VolumeKey::MAX)))?
+            .next()
+            .transpose()?
+            .filter(|(key, _)| key.timeframe == timeframe)
+            .map(|(key, _)| key.key);
+
+        let new_key = match last_key {
+            Some(key) => key + 1,
+            None => 1,
         };
         Ok(new_key)
     }
 
     fn set_volume_helper (&self, key: VolumeKey, volume: Volumetric, timeframe: Timeframe) -> Result<()> {
-        let _ = match timeframe {
-            Timeframe::Daily => self.db.dae_put::<VolumetricsDay>(key, volume),
-            Timeframe::Hourly => self.db.dae_put::<VolumetricsHour>(key, volume),
-            Timeframe::FiveMinute => self.db.dae_put::<VolumetricsFiveMin>(key, volume),
-        }?;
+        self.db.dae_put::<Volumetrics>(TimeframeKey::new(timeframe, key), volume)?;
         Ok(())
     }
 
     fn set_market_index_helper(&self, key: ShardedKey<MarketAddress>, value: VolumeKeysWithData, timeframe: Timeframe) -> Result<()> {
-        let _ = match timeframe {
-            Timeframe::Daily =>  self.db.dae_put::<MarketVolumetricsIndicesDay>(key, value),
-            Timeframe::Hourly =>self.db.dae_put::<MarketVolumetricsIndicesHour>(key, value),
-            Timeframe::FiveMinute => self.db.dae_put::<MarketVolumetricsIndicesFiveMin>(key,value),
-        }?;
+        self.db.dae_put::<MarketVolumetricsIndices>(TimeframeKey::new(timeframe, key), value)?;
 
         Ok(())
     }
@@ -307,85 +234,14 @@ impl VolumetricWriter for DatabaseProvider {
     }
 
     fn add_ts_index(&self, volume_data:(&u64,&u64), timeframe:Timeframe) -> Result<()> {
-        let volume_ts = volume_data.0;
-
         // timestamp index table
-        let _ = match timeframe {
-            Timeframe::Daily => {
-                let existing = self.db.dae_get::<TimestampVolumetricsIndicesDay>(volume_ts.clone())?;
-                match existing {
-                    Some(mut matched_existing) => {
-                        matched_existing.volume_keys.push(volume_data.1.clone());
-                        self.db.dae_put::<TimestampVolumetricsIndicesDay>(volume_ts.clone(), matched_existing)?;
-                    },
-                    None => {
-                        let new_entry = VolumeKeys {
-                            volume_keys: vec![*volume_data.1]
-                        };
-                        self.db.dae_put::<TimestampVolumetricsIndicesDay>(volume_ts.clone(), new_entry)?;
-                    }
-                }
-            },
-            Timeframe::Hourly => {
-                let existing = self.db.dae_get::<TimestampVolumetricsIndicesHour>(volume_ts.clone())?;
-                match existing {
-                    Some(mut matched_existing) => {
-                        matched_existing.volume_keys.push(volume_data.1.clone());
-                        self.db.dae_put::<TimestampVolumetricsIndicesFiveMin>(volume_ts.clone(), matched_existing)?;
-                    },
-                    None => {
-                        let new_entry = VolumeKeys {
-                            volume_keys: vec![*volume_data.1]
-                        };
-                        self.db.dae_put::<TimestampVolumetricsIndicesHour>(volume_ts.clone(), new_entry)?;
-                    }
-                }
-            },
-            Timeframe::FiveMinute => {
-                let existing = self.db.dae_get::<TimestampVolumetricsIndicesFiveMin>(volume_ts.clone())?;
-                match existing {
-                    Some(mut matched_existing) => {
-                        matched_existing.volume_keys.push(volume_data.1.clone());
-                        self.db.dae_put::<TimestampVolumetricsIndicesFiveMin>(volume_ts.clone(), matched_existing)?;
-                    },
-                    None => {
-                        let new_entry = VolumeKeys {
-                            volume_keys: vec![*volume_data.1]
-                        };
-                        self.db.dae_put::<TimestampVolumetricsIndicesFiveMin>(volume_ts.clone(), new_entry)?;
-                    }
-                }
-            },
-        };
-
-        Ok(())
+        self.extend_ts_index(*volume_data.0, vec![*volume_data.1], timeframe)
     }
 
     fn add_market_index(&self, volume_data:(&H256,&u64,&u64), timeframe:Timeframe) -> Result<()> {
 
         // market address index table
-        let mut options = rocksdb::ReadOptions::default();
-        options.set_iterate_range(rocksdb::PrefixRange(volume_data.0.as_bytes()));
-
-        let mut iter = match timeframe {
-            Timeframe::Daily =>  self.db.dae_new_cursor::<MarketVolumetricsIndicesDay>(options),
-            Timeframe::Hourly =>self.db.dae_new_cursor::<MarketVolumetricsIndicesHour>(options),
-            Timeframe::FiveMinute => self.db.dae_new_cursor::<MarketVolumetricsIndicesFiveMin>(options),
-        }?;
-
-
-        iter.seek_to_last();
-        while iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-
-            let kv = match timeframe {
-                Timeframe::Daily => decoder::<MarketVolumetricsIndicesDay>((k.to_vec(), value.to_vec())).unwrap(),
-                Timeframe::Hourly => decoder::<MarketVolumetricsIndicesHour>((k.to_vec(), value.to_vec())).unwrap(),
-                Timeframe::FiveMinute => decoder::<MarketVolumetricsIndicesFiveMin>((k.to_vec(), value.to_vec())).unwrap(),
-            };
-
-
+        if let Some(kv) = self.latest_market_index_shard(*volume_data.0, timeframe)? {
             let key = kv.0;
             let value = kv.1;
 
@@ -397,26 +253,15 @@ impl VolumetricWriter for DatabaseProvider {
                     new_key.max_shard_value = volume_data.1.clone()
                 }
 
-                match timeframe {
-                    Timeframe::Daily => self.db.dae_delete::<MarketVolumetricsIndicesDay>(key).unwrap(),
-                    Timeframe::Hourly => self.db.dae_delete::<MarketVolumetricsIndicesHour>(key).unwrap(),
-                    Timeframe::FiveMinute => self.db.dae_delete::<MarketVolumetricsIndicesFiveMin>(key).unwrap(),
-                };
+                self.db.dae_delete::<MarketVolumetricsIndices>(TimeframeKey::new(timeframe, key))?;
                 self.set_market_index_helper(new_key,new_value,timeframe)?;
                 return Ok(())
-                // break
             }
-
-            break;
         }
 
 
         // matched no shards, create new shard
-        let shard_key = match timeframe {
-            Timeframe::Daily => ShardedKey {key: volume_data.0.clone() as MarketAddress, max_shard_value: volume_data.1.clone()},
-            Timeframe::Hourly => ShardedKey{key: volume_data.0.clone() as MarketAddress, max_shard_value: volume_data.1.clone()},
-            Timeframe::FiveMinute => ShardedKey{key: volume_data.0.clone() as MarketAddress, max_shard_value: volume_data.1.clone()},
-        };
+        let shard_key = ShardedKey {key: volume_data.0.clone() as MarketAddress, max_shard_value: volume_data.1.clone()};
         let shard_value = VolumeKeysWithData {
             volume_keys: vec![VolumeKeyWithData{key:volume_data.2.clone(), timestamp: volume_data.1.clone()}],
         };
@@ -437,29 +282,7 @@ impl VolumetricWriter for DatabaseProvider {
 
     fn bulk_volume_market_indices (&self, market_address: &H256, keys: Vec<VolumeKeyWithData>,timeframe: Timeframe) -> Result<()> {
         // market address index table
-        let mut options = rocksdb::ReadOptions::default();
-        options.set_iterate_range(rocksdb::PrefixRange(market_address.as_bytes()));
-
-        let mut iter = match timeframe {
-            Timeframe::Daily =>  self.db.dae_new_cursor::<MarketVolumetricsIndicesDay>(options),
-            Timeframe::Hourly =>self.db.dae_new_cursor::<MarketVolumetricsIndicesHour>(options),
-            Timeframe::FiveMinute => self.db.dae_new_cursor::<MarketVolumetricsIndicesFiveMin>(options),
-        }?;
-
-
-
-        iter.seek_to_last();
-        if iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-
-            let kv = match timeframe {
-                Timeframe::Daily => decoder::<MarketVolumetricsIndicesDay>((k.to_vec(), value.to_vec())).unwrap(),
-                Timeframe::Hourly => decoder::<MarketVolumetricsIndicesHour>((k.to_vec(), value.to_vec())).unwrap(),
-                Timeframe::FiveMinute => decoder::<MarketVolumetricsIndicesFiveMin>((k.to_vec(), value.to_vec())).unwrap(),
-            };
-
-
+        if let Some(kv) = self.latest_market_index_shard(*market_address, timeframe)? {
             let key = kv.0;
             let value = kv.1;
             let available_slots = value.volume_keys.len() - NUM_OF_INDICES_IN_SHARD;
@@ -470,11 +293,7 @@ impl VolumetricWriter for DatabaseProvider {
             new_key.max_shard_value = new_shard_value.volume_keys.last().unwrap().timestamp.clone();
             self.set_market_index_helper(new_key,new_shard_value,timeframe)?;
             
-            match timeframe {
-                Timeframe::Daily => self.db.dae_delete::<MarketVolumetricsIndicesDay>(key).unwrap(),
-                Timeframe::Hourly => self.db.dae_delete::<MarketVolumetricsIndicesHour>(key).unwrap(),
-                Timeframe::FiveMinute => self.db.dae_delete::<MarketVolumetricsIndicesFiveMin>(key).unwrap(),
-            };
+            self.db.dae_delete::<MarketVolumetricsIndices>(TimeframeKey::new(timeframe, key))?;
 
             // new shard to be created
             if keys.len() > keys_to_save.len() {
@@ -492,54 +311,7 @@ impl VolumetricWriter for DatabaseProvider {
     }
 
     fn bulk_volume_timestamp_indices (&self, timestamp: u64, keys: Vec<u64>, timeframe: Timeframe) -> Result<()> {
-        let _ = match timeframe {
-            Timeframe::Daily => {
-                let existing = self.db.dae_get::<TimestampVolumetricsIndicesDay>(timestamp)?;
-                match existing {
-                    Some(mut matched_existing) => {
-                        matched_existing.volume_keys.extend(keys);
-                        self.db.dae_put::<TimestampVolumetricsIndicesDay>(timestamp, matched_existing)?;
-                    },
-                    None => {
-                        let new_entry = VolumeKeys {
-                            volume_keys: keys
-                        };
-                        self.db.dae_put::<TimestampVolumetricsIndicesDay>(timestamp, new_entry)?;
-                    }
-                }
-            },
-            Timeframe::Hourly => {
-                let existing = self.db.dae_get::<TimestampVolumetricsIndicesHour>(timestamp)?;
-                match existing {
-                    Some(mut matched_existing) => {
-                        matched_existing.volume_keys.extend(keys);
-                        self.db.dae_put::<TimestampVolumetricsIndicesHour>(timestamp, matched_existing)?;
-                    },
-                    None => {
-                        let new_entry = VolumeKeys {
-                            volume_keys: keys
-                        };
-                        self.db.dae_put::<TimestampVolumetricsIndicesHour>(timestamp, new_entry)?;
-                    }
-                }
-            },
-            Timeframe::FiveMinute => {
-                let existing = self.db.dae_get::<TimestampVolumetricsIndicesFiveMin>(timestamp)?;
-                match existing {
-                    Some(mut matched_existing) => {
-                        matched_existing.volume_keys.extend(keys);
-                        self.db.dae_put::<TimestampVolumetricsIndicesFiveMin>(timestamp, matched_existing)?;
-                    },
-                    None => {
-                        let new_entry = VolumeKeys {
-                            volume_keys: keys
-                        };
-                        self.db.dae_put::<TimestampVolumetricsIndicesFiveMin>(timestamp, new_entry)?;
-                    }
-                }
-            },
-        };
-    Ok(())
+        self.extend_ts_index(timestamp, keys, timeframe)
     }
 }
 
@@ -748,5 +520,32 @@ mod test {
         assert!(retrieved_v.len() == 2);
     }
 
-   
+    #[test]
+    fn timeframes_are_isolated() {
+        let provider = get_provider();
+        let new_volume = |timestamp| Volumetric {
+            timestamp,
+            market_address: H256::zero(),
+            swaps_out: vec![],
+            swaps_in: vec![],
+            withdrawal: vec![],
+            mint: vec![],
+            transfer: U256::from_str("0").unwrap(),
+        };
+
+        provider.add_volume(new_volume(1), Timeframe::Hourly, 1, true).expect("Expect to insert volume");
+        provider.add_volume(new_volume(1), Timeframe::Hourly, 2, true).expect("Expect to insert volume");
+        provider.add_volume(new_volume(1), Timeframe::Daily, 1, true).expect("Expect to insert volume");
+
+        assert_eq!(provider.get_by_timestamp(Timeframe::Hourly, 1).unwrap().len(), 2);
+        assert_eq!(provider.get_by_timestamp(Timeframe::Daily, 1).unwrap().len(), 1);
+        assert!(provider.get_by_timestamp(Timeframe::FiveMinute, 1).unwrap().is_empty());
+
+        assert_eq!(provider.get_last_volume_id_or_default(Timeframe::Hourly).unwrap(), 3);
+        assert_eq!(provider.get_last_volume_id_or_default(Timeframe::Daily).unwrap(), 2);
+        assert_eq!(provider.get_last_volume_id_or_default(Timeframe::FiveMinute).unwrap(), 1);
+
+        assert_eq!(provider.get_market_volume_keys(H256::zero(), Timeframe::Hourly).unwrap().unwrap().len(), 2);
+        assert_eq!(provider.get_market_volume_keys(H256::zero(), Timeframe::Daily).unwrap().unwrap().len(), 1);
+    }
 }
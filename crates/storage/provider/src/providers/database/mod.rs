@@ -1,4 +1,4 @@
 mod provider;
-pub use provider::DatabaseProvider;
+pub use provider::{DatabaseProvider, DatabaseProviderRW};
 pub mod options;
 pub mod implementations;
\ No newline at end of file
@@ -1,8 +1,9 @@
 use db::{
-    tables::utils::decoder, table::Encode,
+    implementation::dae_rocksdb::{Database, DbTxMut, SecondaryDB},
+    table::{Table, TableRow},
 };
-use interfaces::Result;
-use rocksdb::{BoundColumnFamily, ReadOptions, TransactionDB, MultiThreaded};
+use interfaces::{db::DatabaseError, Result};
+use rocksdb::BoundColumnFamily;
 
 use std::sync::Arc;
 
@@ -11,34 +12,109 @@ use crate::traits::ShardedTableProvider;
 use super::options::AccessType;
 use db::transaction::DbTx;
 /// A provider struct that fetchs data from the database.
-pub struct DatabaseProvider {
-    pub db: TransactionDB<MultiThreaded>,
+///
+/// By default every write is committed on its own, use [DatabaseProvider::tx_mut] to group
+/// writes into a single atomic unit.
+pub struct DatabaseProvider<TX = Database> {
+    pub db: TX,
     pub access_type: AccessType,
-    _phantom_data: std::marker::PhantomData<TransactionDB>,
+    _phantom_data: std::marker::PhantomData<Database>,
 }
 
-impl DatabaseProvider {
+/// A provider whose writes are only persisted once [DatabaseProviderRW::commit] is called.
+pub type DatabaseProviderRW<'a> = DatabaseProvider<DbTxMut<'a>>;
+
+impl<TX> DatabaseProvider<TX> {
     /// Creates a provider with an inner read-only transaction.
-    pub fn new(db: TransactionDB<MultiThreaded>, access_type: AccessType) -> Self {
+    pub fn new(db: TX, access_type: AccessType) -> Self {
         Self {
             db,
             access_type,
             _phantom_data: std::marker::PhantomData,
         }
     }
+}
 
+impl DatabaseProvider {
     pub fn get_cf(&self, table: &str) -> Option<Arc<BoundColumnFamily>> {
         let cf = self.db.cf_handle(table);
 
         cf
     }
 
-    pub fn into_db(self) -> TransactionDB {
+    pub fn into_db(self) -> Database {
         self.db
     }
+
+    /// Opens a read-write transaction. Nothing written through the returned provider is visible
+    /// to other readers until it is committed.
+    pub fn tx_mut(&self) -> DatabaseProviderRW<'_> {
+        DatabaseProvider::new(DbTxMut::new(&self.db), self.access_type.clone())
+    }
 }
 
-impl ShardedTableProvider for DatabaseProvider {
+impl<'a> DatabaseProviderRW<'a> {
+    /// Puts a value into the transaction.
+    pub fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<()> {
+        Ok(self.db.dae_put::<T>(key, value)?)
+    }
+
+    /// Deletes a value in the transaction.
+    pub fn delete<T: Table>(&self, key: T::Key) -> Result<bool> {
+        Ok(self.db.dae_delete::<T>(key)?)
+    }
+
+    /// Commits all the writes of the transaction atomically.
+    pub fn commit(self) -> Result<()> {
+        Ok(self.db.commit()?)
+    }
+}
+
+impl DatabaseProvider<SecondaryDB> {
+    /// Reads the writes made to the primary database since the last catch up.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        self.db
+            .try_catch_up_with_primary()
+            .map_err(|err| DatabaseError::Io(err.into_string()).into())
+    }
+}
+
+/// Number of rows [DatabaseProvider::remove_from] reads before deleting them.
+const REMOVE_BATCH_SIZE: usize = 1_000;
+
+impl<TX: DbTx> DatabaseProvider<TX> {
+    /// Removes every entry of `T` with a key greater than or equal to `from`. `on_removed` is
+    /// called with every removed row, so callers can clean up the indices that point to them.
+    ///
+    /// Rows are read and deleted in batches, the cursor is closed before deleting them since a
+    /// transaction can't be written while a cursor over it is open.
+    pub(crate) fn remove_from<T: Table>(
+        &self,
+        from: T::Key,
+        mut on_removed: impl FnMut(TableRow<T>) -> Result<()>,
+    ) -> Result<()> {
+        loop {
+            let batch = self
+                .db
+                .dae_cursor::<T>()?
+                .walk(from.clone()..)?
+                .take(REMOVE_BATCH_SIZE)
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let done = batch.len() < REMOVE_BATCH_SIZE;
+
+            for (key, value) in batch {
+                self.db.dae_delete::<T>(key.clone())?;
+                on_removed((key, value))?;
+            }
+
+            if done {
+                return Ok(())
+            }
+        }
+    }
+}
+
+impl<TX: DbTx> ShardedTableProvider for DatabaseProvider<TX> {
     #[allow(unused_variables)]
     fn get_latest_shard<T: db::table::Table>(&self, prefix: &[u8]) -> Result<Option<&[u8]>> {
 
@@ -65,22 +141,10 @@ impl ShardedTableProvider for DatabaseProvider {
 
     fn get_shard<T: db::table::Table>(&self, prefix:  T::Key) -> Result<Option<T::Value>> {
         // resolves for the specified shard or finds the nearest one using a cursor
-        let opts = ReadOptions::default();
-
-        let mut iter = self.db.dae_new_cursor::<T>(opts).unwrap();
-        iter.seek(prefix.encode());
-
-        if iter.valid() {
-            let k = iter.key().unwrap();
-            let v = iter.value().unwrap();
+        let shard = self.db.dae_cursor::<T>()?.seek(prefix)?;
 
-            let (_key, val) = decoder::<T>((k.to_vec(),v.to_vec())).unwrap();
-
-            Ok(Some(val))
-        } else {
-            // if shard key is incorrect (looking at wrong shard division)
-            Ok(None)
-        }
+        // if shard key is incorrect (looking at wrong shard division) there is nothing to return
+        Ok(shard.map(|(_key, val)| val))
     }
 }
 
@@ -93,9 +157,7 @@ mod test {
         test_utils::ERROR_TEMPDIR,
     };
     use simp_primitives::{Header, Address};
-    use rocksdb::PrefixRange;
-    use crate::traits::HeaderProvider;
-    use db::table::Encode;
+    use crate::traits::{HeaderProvider, HeaderWriter};
 
     #[test]
     fn test_db() {
@@ -133,6 +195,55 @@ mod test {
         assert_eq!(headers_db.len(), 7);
     }
 
+    #[test]
+    fn test_tx_mut_commits_atomically() {
+        let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
+        let provider = DatabaseProvider::new(db, AccessType::Primary);
+
+        let provider_rw = provider.tx_mut();
+        provider_rw.insert_header(&1, Header::default()).unwrap();
+        provider_rw.put::<Headers>(2, Header::default()).unwrap();
+
+        // writes are visible inside the transaction only
+        assert!(provider_rw.header_by_number(2).unwrap().is_some());
+        assert!(provider.header_by_number(1).unwrap().is_none());
+
+        provider_rw.commit().unwrap();
+
+        assert!(provider.header_by_number(1).unwrap().is_some());
+        assert!(provider.header_by_number(2).unwrap().is_some());
+
+        let provider_rw = provider.tx_mut();
+        provider_rw.delete::<Headers>(1).unwrap();
+        drop(provider_rw);
+
+        assert!(provider.header_by_number(1).unwrap().is_some());
+    }
+
+    #[test]
+    fn remove_from_in_batches() {
+        let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
+        let provider = DatabaseProvider::new(db, AccessType::Primary);
+        let provider_rw = provider.tx_mut();
+
+        let rows = 2 * REMOVE_BATCH_SIZE as u64 + 10;
+        for block_number in 0..rows {
+            provider_rw.insert_header(&block_number, Header::default()).unwrap();
+        }
+
+        let mut removed = 0;
+        provider_rw
+            .remove_from::<Headers>(10, |_| {
+                removed += 1;
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(removed, rows - 10);
+        assert!(provider_rw.header_by_number(9).unwrap().is_some());
+        assert!(provider_rw.header_by_number(10).unwrap().is_none());
+    }
+
     #[test]
     fn test_logs_rw_by_address() {
         let db = init_db(&tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path()).unwrap();
@@ -226,20 +337,13 @@ mod test {
             provider.db.dae_put::<ContractLogs>(key, log.2).unwrap();
         }
 
-        let mut opts = ReadOptions::default();
-        opts.set_iterate_range(PrefixRange(address_0.encode().as_slice()));
-
-        let mut iter = provider.db.dae_new_cursor::<ContractLogs>(opts).unwrap();
+        let mut cursor = provider.db.dae_cursor::<ContractLogs>().unwrap();
 
-        iter.seek_to_first();
         let mut logs_0 = Vec::new();
-        while iter.valid() {
-            let k = iter.key().unwrap();
-            let value = iter.value().unwrap();
-            let kv = decoder::<ContractLogs>((k.to_vec(), value.to_vec())).unwrap();
+        for entry in cursor.walk(ShardedKey::new(address_0, 0)..=ShardedKey::last(address_0)).unwrap() {
+            let kv = entry.unwrap();
             println!("{:?}, {:?}", kv.0, kv.1);
             logs_0.push(kv.1);
-            iter.next();
         }
 
         assert_eq!(logs_0.len(), 5);
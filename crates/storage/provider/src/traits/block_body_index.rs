@@ -12,5 +12,8 @@ pub trait BlockBodyIndicesProvider: Send + Sync {
 #[auto_impl(&, Arc, Box)]
 pub trait BlockBodyIndicesWriter: Send + Sync {
     fn insert_block_body_indices(&self, block_number: BlockNumber, index: BlockBodyIndices) -> Result<BlockBodyIndices>;
+
+    /// Removes the body indices of every block above `block_number`.
+    fn remove_block_body_indices_above(&self, block_number: BlockNumber) -> Result<()>;
 }
 
@@ -33,4 +33,8 @@ pub trait HeaderProvider: HeaderWriter + Send + Sync {
 #[auto_impl(&, Arc)]
 pub trait HeaderWriter: Send + Sync {
     fn insert_header(&self,block_number: &BlockNumber, header: Header) -> Result<Option<BlockNumber>>;
+
+    /// Removes the headers, canonical hashes and hash to number entries of every block above
+    /// `block_number`.
+    fn remove_headers_above(&self, block_number: BlockNumber) -> Result<()>;
 }
\ No newline at end of file
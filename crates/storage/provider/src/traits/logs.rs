@@ -11,10 +11,11 @@ use simp_primitives::{
 
 use super::{AbiProvider, BlockNumReader, TrackingProvider};
 
-pub enum StoredOrDecodedLog {
-    Raw(StoredLog),
-    Decoded(StoredDecodedData),
-}
+pub use simp_primitives::StoredOrDecodedLog;
+
+/// Maximum number of blocks read by [LogsProvider::logs_by_block_range]. Every log of these
+/// blocks is read, a block of a busy chain holds several hundred of them.
+pub const MAX_LOGS_BLOCK_RANGE: u64 = 100;
 
 ///  Client trait for fetching [TransactionSigned] related data.
 #[auto_impl::auto_impl(&, Arc)]
@@ -40,7 +41,8 @@ pub trait LogsProvider: BlockNumReader + Send + Sync {
         decoded: bool,
     ) -> Result<Option<Vec<StoredOrDecodedLog>>>;
 
-    /// Get logs by block range.
+    /// Get logs by block range, inclusive. Ranges of more than [MAX_LOGS_BLOCK_RANGE] blocks are
+    /// rejected.
     fn logs_by_block_range(
         &self,
         start: BlockNumber,
@@ -56,6 +58,17 @@ pub trait LogsProvider: BlockNumReader + Send + Sync {
         decoded: bool,
     ) -> Result<Vec<StoredOrDecodedLog>>;
 
+    /// Get at most `limit` logs by address, skipping the first `offset` logs between the blocks.
+    fn logs_by_address_page(
+        &self,
+        address: Address,
+        from: Option<BlockNumber>,
+        to: Option<BlockNumber>,
+        offset: usize,
+        limit: usize,
+        decoded: bool,
+    ) -> Result<Vec<StoredOrDecodedLog>>;
+
     fn get_address_logs_latest_partition(&self, address: Address) -> PairResult<ContractLogs>;
 }
 
@@ -73,4 +86,8 @@ pub trait LogsWriter: Send + Sync + TrackingProvider + AbiProvider {
     ) -> Result<HashMap<Address, Vec<TxLogId>>>;
 
     fn insert_logs_by_address(&self, logs: &HashMap<Address, Vec<TxLogId>>) -> Result<()>;
+
+    /// Removes the raw and decoded logs emitted by transactions starting at `first_tx` and
+    /// drops them from the block and address indices of every block above `block_number`.
+    fn remove_logs_above(&self, block_number: BlockNumber, first_tx: TxNumber) -> Result<()>;
 }
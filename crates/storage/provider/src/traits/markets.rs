@@ -2,11 +2,12 @@ use interfaces::Result;
 use simp_primitives::{H256, Market, TokenMarkets};
 
 /// Client trait for [Market]s
+#[auto_impl::auto_impl(&, Arc)]
 pub trait MarketProvider: Send + Sync {
     /// Creates a new market.
     fn create_market (&self, market: Market, market_address: H256) -> Result<()>;
 
-    /// Deletes an existing market. IF the market does not exist, an Error Result will be thrown
+    /// Deletes an existing market and removes it from the markets of its input tokens.
     fn delete_market (&self, market_address: H256) -> Result<()>;
 
     /// Retrieves a single market
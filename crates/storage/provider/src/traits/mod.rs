@@ -14,10 +14,18 @@ mod block_hash;
 pub use block_hash::{BlockHashReader, BlockHashWriter};
 
 mod transactions;
-pub use transactions::{TransactionsProvider, TransactionsWriter};
+pub use transactions::{
+    TransactionsProvider, TransactionsWriter, MAX_TRANSACTIONS_BLOCK_RANGE, MAX_TRANSACTIONS_TX_RANGE,
+};
+
+mod receipts;
+pub use receipts::{ReceiptsProvider, ReceiptsWriter};
+
+mod traces;
+pub use traces::{TracesProvider, TracesWriter};
 
 mod logs;
-pub use logs::{LogsProvider, LogsWriter, StoredOrDecodedLog};
+pub use logs::{LogsProvider, LogsWriter, StoredOrDecodedLog, MAX_LOGS_BLOCK_RANGE};
 
 mod block_body_index;
 pub use block_body_index::{BlockBodyIndicesProvider, BlockBodyIndicesWriter};
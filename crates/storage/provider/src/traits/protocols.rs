@@ -2,10 +2,11 @@ use interfaces::Result;
 use simp_primitives::{Protocol,H256};
 
 /// Client trait for [Protocol]s
+#[auto_impl::auto_impl(&, Arc)]
 pub trait ProtocolProvider: Send + Sync {
 
-    /// Creates a new protocol with a blank status
-    fn create_protocol (&self, factory_address: H256) -> Result<()>;
+    /// Creates a new protocol with a blank status and returns its id
+    fn create_protocol (&self, factory_address: H256) -> Result<u64>;
     // fn create_protocol (&self, protocol: Protocol) -> Result<()>;
 
     /// deletes an existing protocol
@@ -0,0 +1,24 @@
+use interfaces::Result;
+use simp_primitives::{BlockHashOrNumber, Receipt, TxHash, TxNumber};
+
+/// Client trait for fetching [Receipt]s.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait ReceiptsProvider: Send + Sync {
+    /// Get the receipt of a transaction by its internal identifier.
+    fn receipt(&self, id: TxNumber) -> Result<Option<Receipt>>;
+
+    /// Get the receipt of a transaction by its hash.
+    fn receipt_by_hash(&self, hash: TxHash) -> Result<Option<Receipt>>;
+
+    /// Get the receipts of the transactions of a block, in the order of the transactions.
+    fn receipts_by_block(&self, block: BlockHashOrNumber) -> Result<Option<Vec<Receipt>>>;
+}
+
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait ReceiptsWriter: Send + Sync {
+    /// Inserts the receipts of consecutive transactions, the first one being `first_tx`.
+    fn insert_receipts(&self, first_tx: TxNumber, receipts: Vec<Receipt>) -> Result<()>;
+
+    /// Removes the receipt of every transaction starting at `first_tx`.
+    fn remove_receipts_from(&self, first_tx: TxNumber) -> Result<()>;
+}
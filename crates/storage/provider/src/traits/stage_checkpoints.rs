@@ -12,4 +12,7 @@ pub trait StageCheckpointProvider: Send + Sync {
 pub trait StageCheckpointWriter: Send + Sync {
     /// Save stage checkpoint.
     fn save_stage_checkpoint(&self, id: StageId, checkpoint: BlockNumber) -> Result<()>;
+
+    /// Removes the stage checkpoint, the stage runs from the start again.
+    fn delete_stage_checkpoint(&self, id: StageId) -> Result<()>;
 }
@@ -0,0 +1,32 @@
+use interfaces::Result;
+use simp_primitives::{Address, BlockHashOrNumber, Trace, TxNumber};
+
+/// Client trait for fetching the [Trace]s of transactions.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait TracesProvider: Send + Sync {
+    /// Get the calls of a transaction, depth first.
+    fn traces_by_tx_id(&self, id: TxNumber) -> Result<Option<Vec<Trace>>>;
+
+    /// Get the calls of every traced transaction of a block.
+    fn traces_by_block(&self, block: BlockHashOrNumber) -> Result<Option<Vec<(TxNumber, Vec<Trace>)>>>;
+
+    /// Get the transactions from `from` to `to` making internal calls from or to `address`.
+    fn internal_call_transactions(
+        &self,
+        address: Address,
+        from: Option<TxNumber>,
+        to: Option<TxNumber>,
+    ) -> Result<Vec<TxNumber>>;
+}
+
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait TracesWriter: Send + Sync {
+    /// Inserts the calls of transactions and indexes their internal calls by address.
+    ///
+    /// Transactions have to be inserted in order.
+    fn insert_traces(&self, traces: Vec<(TxNumber, Vec<Trace>)>) -> Result<()>;
+
+    /// Removes the calls of every transaction starting at `first_tx`, together with their index
+    /// entries.
+    fn remove_traces_from(&self, first_tx: TxNumber) -> Result<()>;
+}
@@ -7,6 +7,12 @@ use simp_primitives::{
 
 use super::BlockNumReader;
 
+/// Maximum number of blocks read by [TransactionsProvider::transactions_by_block_range].
+pub const MAX_TRANSACTIONS_BLOCK_RANGE: u64 = 50;
+
+/// Maximum number of transactions read by [TransactionsProvider::transactions_by_tx_range].
+pub const MAX_TRANSACTIONS_TX_RANGE: u64 = 10_000;
+
 ///  Client trait for fetching [TransactionSigned] related data.
 #[auto_impl::auto_impl(&, Arc)]
 pub trait TransactionsProvider: BlockNumReader + Send + Sync {
@@ -30,14 +36,16 @@ pub trait TransactionsProvider: BlockNumReader + Send + Sync {
         block: BlockHashOrNumber,
     ) -> Result<Option<Vec<TransactionSigned>>>;
 
-    /// Get transactions by block range.
+    /// Get transactions by block range, exclusive. Ranges of more than
+    /// [MAX_TRANSACTIONS_BLOCK_RANGE] blocks are rejected.
     fn transactions_by_block_range(
         &self,
         start: BlockNumber,
         end: BlockNumber,
     ) -> Result<Vec<Vec<TransactionSigned>>>;
 
-    /// Get transactions by tx range.
+    /// Get transactions by tx range, exclusive. Ranges of more than [MAX_TRANSACTIONS_TX_RANGE]
+    /// transactions are rejected.
     fn transactions_by_tx_range(
         &self,
         start: simp_primitives::TxNumber,
@@ -51,4 +59,8 @@ pub trait TransactionsWriter: Send + Sync {
         &self,
         transactions: Vec<TransactionSigned>,
     ) -> Result<(TxIndices, Vec<(TxNumber, TxHash)>)>;
+
+    /// Removes every transaction starting at `first_tx`, together with its hash and block
+    /// lookups.
+    fn remove_transactions_from(&self, first_tx: TxNumber) -> Result<()>;
 }
\ No newline at end of file
@@ -4,20 +4,14 @@ use interfaces::Result;
 use db::tables::models::sharded_key::ShardedKey;
 use db::tables::models::volumetric::VolumeKeysWithData;
 
-// TODO switch to main repo timeframe type
-
-#[derive(Debug, Clone, Copy)]
-pub enum Timeframe {
-    Daily,
-    Hourly,
-    FiveMinute,
-}
+pub use db::tables::models::Timeframe;
 
 
 
 
 
 /// Client trait for reading [Volumetric]s
+#[auto_impl::auto_impl(&, Arc)]
 pub trait VolumetricReader: Send + Sync {
     // /// Retrieves all volumes for a given market, for a given timeframe
     // fn get_market_volumes(&self, market_address: H256, timeframe: Timeframe) -> Result<Vec<Volumetric>>;
@@ -36,11 +30,18 @@ pub trait VolumetricReader: Send + Sync {
     /// None, None: All market volumes
     fn get_market_range(&self, market_address: H256, timeframe: Timeframe, from: Option<u64>, to:Option<u64>) -> Result<Vec<Volumetric>>;
 
+    /// Gets at most `limit` volumes of a market between `from` and `to` inclusive, ordered by
+    /// timestamp. Index shards ending before `from` aren't read.
+    fn get_market_range_page(&self, market_address: H256, timeframe: Timeframe, from: u64, to: u64, limit: usize) -> Result<Vec<Volumetric>>;
+
     
     /// Gets all volumes for a given timestamp, for a given range
     /// Returns a vector of Tuples, containing the market address and market volume
     fn get_by_timestamp(&self, timeframe:Timeframe, timestamp: u64) -> Result<Vec<Volumetric>>;
 
+    /// Gets at most `limit` volumes for a given timestamp, skipping the first `offset` ones.
+    fn get_by_timestamp_page(&self, timeframe: Timeframe, timestamp: u64, offset: usize, limit: usize) -> Result<Vec<Volumetric>>;
+
     fn get_latest_market_volume (&self, market_address: H256,timeframe:Timeframe) -> Result<Option<Volumetric>>;
 
     fn get_volume_by_key (&self, key: u64,timeframe: Timeframe) -> Result<Option<Volumetric>>;